@@ -0,0 +1,167 @@
+//! Human approval of tool calls before they run.
+//!
+//! Tools report whether they need approval through `ftooling::Tool::requires_approval`. When
+//! the model calls one, `ChatService` asks the configured [`ToolApprover`]; without one,
+//! `stream_turn` emits [`ChatEvent::ToolApprovalRequested`](crate::ChatEvent::ToolApprovalRequested)
+//! and waits for a decision through the request's [`ToolApprovalHandle`]. A denied call is not
+//! executed; the model receives the denial as the call's tool result.
+//!
+//! ```rust
+//! use fchat::{ToolApprovalDecision, ToolApprover};
+//! use fprovider::ToolCall;
+//! use ftooling::ToolExecutionContext;
+//!
+//! let approver = |call: ToolCall, _context: ToolExecutionContext| async move {
+//!     if call.arguments.contains("production") {
+//!         ToolApprovalDecision::deny("production deploys need a change ticket")
+//!     } else {
+//!         ToolApprovalDecision::Approve
+//!     }
+//! };
+//! let _approver: &dyn ToolApprover = &approver;
+//! ```
+
+use std::fmt::{Debug, Formatter};
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+
+use fprovider::{ToolCall, ToolResult};
+use ftooling::ToolExecutionContext;
+use futures_channel::oneshot;
+
+use crate::ChatError;
+use crate::store::ChatFuture;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ToolApprovalDecision {
+    Approve,
+    /// Runs the call with these arguments instead of the ones the model sent.
+    EditArguments(String),
+    /// Skips the call; the model receives the denial, with the reason when given.
+    Deny {
+        reason: Option<String>,
+    },
+}
+
+impl ToolApprovalDecision {
+    pub fn deny(reason: impl Into<String>) -> Self {
+        Self::Deny {
+            reason: Some(reason.into()),
+        }
+    }
+
+    /// The call to execute, or the tool result reporting the denial.
+    pub(crate) fn apply(self, mut tool_call: ToolCall) -> Result<ToolCall, ToolResult> {
+        match self {
+            Self::Approve => Ok(tool_call),
+            Self::EditArguments(arguments) => {
+                tool_call.arguments = arguments;
+                Ok(tool_call)
+            }
+            Self::Deny { reason } => Err(ToolResult {
+                tool_call_id: tool_call.id,
+                output: match reason {
+                    Some(reason) => format!("Tool call denied by the user: {reason}"),
+                    None => "Tool call denied by the user.".to_string(),
+                },
+            }),
+        }
+    }
+}
+
+/// Decides on tool calls that require approval during `run_turn` and `stream_turn`.
+///
+/// Implemented for async closures taking the call and its execution context.
+pub trait ToolApprover: Send + Sync {
+    fn review<'a>(
+        &'a self,
+        tool_call: &'a ToolCall,
+        context: &'a ToolExecutionContext,
+    ) -> ChatFuture<'a, ToolApprovalDecision>;
+}
+
+impl<F, Fut> ToolApprover for F
+where
+    F: Fn(ToolCall, ToolExecutionContext) -> Fut + Send + Sync,
+    Fut: Future<Output = ToolApprovalDecision> + Send + 'static,
+{
+    fn review<'a>(
+        &'a self,
+        tool_call: &'a ToolCall,
+        context: &'a ToolExecutionContext,
+    ) -> ChatFuture<'a, ToolApprovalDecision> {
+        Box::pin(self(tool_call.clone(), context.clone()))
+    }
+}
+
+/// A tool call paused in `stream_turn` until a decision is delivered through `handle`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ToolApprovalRequest {
+    pub tool_call: ToolCall,
+    pub handle: ToolApprovalHandle,
+}
+
+/// Delivers the decision for one [`ToolApprovalRequest`]. The stream resumes on its next poll
+/// after a decision; dropping every clone of the handle without deciding denies the call.
+#[derive(Clone)]
+pub struct ToolApprovalHandle {
+    sender: Arc<Mutex<Option<oneshot::Sender<ToolApprovalDecision>>>>,
+}
+
+impl ToolApprovalHandle {
+    pub(crate) fn channel() -> (Self, oneshot::Receiver<ToolApprovalDecision>) {
+        let (sender, receiver) = oneshot::channel();
+        let handle = Self {
+            sender: Arc::new(Mutex::new(Some(sender))),
+        };
+        (handle, receiver)
+    }
+
+    /// Fails when a decision was already delivered or the turn has ended.
+    pub fn decide(&self, decision: ToolApprovalDecision) -> Result<(), ChatError> {
+        let sender = self
+            .sender
+            .lock()
+            .map_err(|_| ChatError::tooling("tool approval lock poisoned"))?
+            .take()
+            .ok_or_else(|| ChatError::invalid_request("tool call was already decided"))?;
+        sender
+            .send(decision)
+            .map_err(|_| ChatError::invalid_request("turn awaiting this tool approval has ended"))
+    }
+
+    pub fn approve(&self) -> Result<(), ChatError> {
+        self.decide(ToolApprovalDecision::Approve)
+    }
+
+    pub fn edit_arguments(&self, arguments: impl Into<String>) -> Result<(), ChatError> {
+        self.decide(ToolApprovalDecision::EditArguments(arguments.into()))
+    }
+
+    pub fn deny(&self, reason: impl Into<String>) -> Result<(), ChatError> {
+        self.decide(ToolApprovalDecision::deny(reason))
+    }
+
+    pub fn is_decided(&self) -> bool {
+        self.sender
+            .lock()
+            .map(|sender| sender.is_none())
+            .unwrap_or(true)
+    }
+}
+
+impl Debug for ToolApprovalHandle {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ToolApprovalHandle")
+            .field("decided", &self.is_decided())
+            .finish()
+    }
+}
+
+impl PartialEq for ToolApprovalHandle {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.sender, &other.sender)
+    }
+}
+
+impl Eq for ToolApprovalHandle {}
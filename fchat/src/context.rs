@@ -0,0 +1,314 @@
+//! Context window strategies applied to the conversation before each provider request.
+//!
+//! A [`ContextStrategy`] receives the full conversation (system prompt, stored history and the
+//! new user message) and removes what should not be sent. Strategies never remove system
+//! messages or the final message, and anything they drop is reported through
+//! [`ChatEvent::ContextTrimmed`](crate::ChatEvent::ContextTrimmed).
+//!
+//! ```rust
+//! use fchat::{ContextStrategy, HeadTailStrategy};
+//! use fprovider::{Message, Role};
+//!
+//! let mut messages = vec![Message::new(Role::System, "Be concise.")];
+//! for index in 0..6 {
+//!     messages.push(Message::new(Role::User, format!("message {index}")));
+//! }
+//!
+//! let removed = HeadTailStrategy::new(1, 2).trim(&mut messages);
+//! assert_eq!(removed.len(), 3);
+//! assert_eq!(messages.len(), 4);
+//! assert_eq!(messages[1].content, "message 0");
+//! ```
+
+use fprovider::{Message, Role};
+
+/// Estimates the tokens a message occupies in a request.
+pub type TokenEstimator = fn(&Message) -> u32;
+
+/// Rough estimate of four characters per token plus a small per-message overhead.
+pub fn estimate_tokens(message: &Message) -> u32 {
+    let chars = u32::try_from(message.content.chars().count()).unwrap_or(u32::MAX);
+    chars.div_ceil(4).saturating_add(4)
+}
+
+pub trait ContextStrategy: Send + Sync {
+    /// Short stable name reported in [`ContextTrim::strategy`].
+    fn name(&self) -> &'static str;
+
+    /// Removes messages from `messages` and returns them in their original order.
+    fn trim(&self, messages: &mut Vec<Message>) -> Vec<Message>;
+
+    /// Tokens `message` is estimated to occupy, reported in [`ContextTrim::removed_tokens`].
+    fn estimate(&self, message: &Message) -> u32 {
+        estimate_tokens(message)
+    }
+}
+
+/// What a strategy removed from one turn's conversation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContextTrim {
+    pub strategy: String,
+    pub removed_messages: usize,
+    /// Estimated with the strategy's [`ContextStrategy::estimate`].
+    pub removed_tokens: u32,
+    pub remaining_messages: usize,
+}
+
+impl ContextTrim {
+    pub(crate) fn new(
+        strategy: &dyn ContextStrategy,
+        removed: &[Message],
+        remaining_messages: usize,
+    ) -> Self {
+        Self {
+            strategy: strategy.name().to_string(),
+            removed_messages: removed.len(),
+            removed_tokens: removed
+                .iter()
+                .map(|message| strategy.estimate(message))
+                .sum(),
+            remaining_messages,
+        }
+    }
+}
+
+/// Keeps the most recent messages that fit in a token budget, plus system messages and the
+/// final message regardless of size.
+#[derive(Debug, Clone, Copy)]
+pub struct SlidingWindowStrategy {
+    max_tokens: u32,
+    estimator: TokenEstimator,
+}
+
+impl SlidingWindowStrategy {
+    pub fn new(max_tokens: u32) -> Self {
+        Self {
+            max_tokens,
+            estimator: estimate_tokens,
+        }
+    }
+
+    pub fn with_estimator(mut self, estimator: TokenEstimator) -> Self {
+        self.estimator = estimator;
+        self
+    }
+}
+
+impl ContextStrategy for SlidingWindowStrategy {
+    fn name(&self) -> &'static str {
+        "sliding_window"
+    }
+
+    fn trim(&self, messages: &mut Vec<Message>) -> Vec<Message> {
+        let last = messages.len().saturating_sub(1);
+        let mut budget = messages
+            .iter()
+            .enumerate()
+            .filter(|(index, message)| message.role == Role::System || *index == last)
+            .fold(self.max_tokens, |budget, (_, message)| {
+                budget.saturating_sub((self.estimator)(message))
+            });
+
+        let mut keep = vec![true; messages.len()];
+        let mut window_closed = false;
+        for (index, message) in messages.iter().enumerate().rev() {
+            if message.role == Role::System || index == last {
+                continue;
+            }
+            let cost = (self.estimator)(message);
+            if window_closed || cost > budget {
+                window_closed = true;
+                keep[index] = false;
+            } else {
+                budget -= cost;
+            }
+        }
+
+        remove_unkept(messages, &keep)
+    }
+
+    fn estimate(&self, message: &Message) -> u32 {
+        (self.estimator)(message)
+    }
+}
+
+/// Keeps system messages, the first `head` and the last `tail` other messages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HeadTailStrategy {
+    head: usize,
+    tail: usize,
+}
+
+impl HeadTailStrategy {
+    pub fn new(head: usize, tail: usize) -> Self {
+        Self {
+            head,
+            tail: tail.max(1),
+        }
+    }
+}
+
+impl ContextStrategy for HeadTailStrategy {
+    fn name(&self) -> &'static str {
+        "head_tail"
+    }
+
+    fn trim(&self, messages: &mut Vec<Message>) -> Vec<Message> {
+        let conversational = messages
+            .iter()
+            .filter(|message| message.role != Role::System)
+            .count();
+        let tail_start = conversational.saturating_sub(self.tail);
+
+        let mut position = 0;
+        let keep = messages
+            .iter()
+            .map(|message| {
+                if message.role == Role::System {
+                    return true;
+                }
+                let kept = position < self.head || position >= tail_start;
+                position += 1;
+                kept
+            })
+            .collect::<Vec<_>>();
+
+        remove_unkept(messages, &keep)
+    }
+}
+
+/// Drops tool output messages except the most recent `keep_last`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DropToolOutputsStrategy {
+    keep_last: usize,
+}
+
+impl DropToolOutputsStrategy {
+    pub fn new(keep_last: usize) -> Self {
+        Self { keep_last }
+    }
+}
+
+impl ContextStrategy for DropToolOutputsStrategy {
+    fn name(&self) -> &'static str {
+        "drop_tool_outputs"
+    }
+
+    fn trim(&self, messages: &mut Vec<Message>) -> Vec<Message> {
+        let last = messages.len().saturating_sub(1);
+        let mut remaining = self.keep_last;
+        let mut keep = vec![true; messages.len()];
+        for (index, message) in messages.iter().enumerate().rev() {
+            if message.role != Role::Tool || index == last {
+                continue;
+            }
+            if remaining > 0 {
+                remaining -= 1;
+            } else {
+                keep[index] = false;
+            }
+        }
+
+        remove_unkept(messages, &keep)
+    }
+}
+
+fn remove_unkept(messages: &mut Vec<Message>, keep: &[bool]) -> Vec<Message> {
+    let mut removed = Vec::new();
+    let mut kept = Vec::with_capacity(messages.len());
+    for (message, keep) in messages.drain(..).zip(keep) {
+        if *keep {
+            kept.push(message);
+        } else {
+            removed.push(message);
+        }
+    }
+    *messages = kept;
+    removed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn conversation(turns: usize) -> Vec<Message> {
+        let mut messages = vec![Message::new(Role::System, "system")];
+        for index in 0..turns {
+            messages.push(Message::new(Role::User, format!("question {index}")));
+            messages.push(Message::new(Role::Assistant, format!("answer {index}")));
+        }
+        messages.push(Message::new(Role::User, "latest"));
+        messages
+    }
+
+    fn one_token(_: &Message) -> u32 {
+        1
+    }
+
+    #[test]
+    fn sliding_window_keeps_newest_messages_within_budget() {
+        let mut messages = conversation(3);
+        let removed = SlidingWindowStrategy::new(4)
+            .with_estimator(one_token)
+            .trim(&mut messages);
+
+        assert_eq!(removed.len(), 4);
+        assert_eq!(removed[0].content, "question 0");
+        let contents = messages
+            .iter()
+            .map(|message| message.content.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(contents, vec!["system", "question 2", "answer 2", "latest"]);
+    }
+
+    #[test]
+    fn sliding_window_always_keeps_system_and_final_messages() {
+        let mut messages = conversation(1);
+        let removed = SlidingWindowStrategy::new(0).trim(&mut messages);
+
+        assert_eq!(removed.len(), 2);
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0].role, Role::System);
+        assert_eq!(messages[1].content, "latest");
+    }
+
+    #[test]
+    fn head_tail_leaves_short_conversations_untouched() {
+        let mut messages = conversation(1);
+        assert!(HeadTailStrategy::new(2, 2).trim(&mut messages).is_empty());
+        assert_eq!(messages.len(), 4);
+    }
+
+    #[test]
+    fn drop_tool_outputs_keeps_only_recent_tool_messages() {
+        let mut messages = vec![
+            Message::new(Role::User, "run tools"),
+            Message::new(Role::Tool, "old output"),
+            Message::new(Role::Assistant, "checked"),
+            Message::new(Role::Tool, "new output"),
+            Message::new(Role::User, "latest"),
+        ];
+
+        let removed = DropToolOutputsStrategy::new(1).trim(&mut messages);
+
+        assert_eq!(removed, vec![Message::new(Role::Tool, "old output")]);
+        assert_eq!(messages.len(), 4);
+        assert_eq!(
+            ContextTrim::new(&DropToolOutputsStrategy::new(1), &removed, messages.len())
+                .removed_tokens,
+            estimate_tokens(&removed[0])
+        );
+    }
+
+    #[test]
+    fn context_trim_reports_tokens_with_the_strategy_estimator() {
+        let strategy = SlidingWindowStrategy::new(0).with_estimator(one_token);
+        let mut messages = conversation(2);
+        let removed = strategy.trim(&mut messages);
+
+        let trim = ContextTrim::new(&strategy, &removed, messages.len());
+        assert_eq!(trim.strategy, "sliding_window");
+        assert_eq!(trim.removed_messages, 4);
+        assert_eq!(trim.removed_tokens, 4);
+    }
+}
@@ -18,6 +18,10 @@ pub enum ChatErrorKind {
     Provider,
     Store,
     Tooling,
+    /// A guardrail blocked the turn.
+    Guardrail,
+    /// The session was changed by another turn; retrying with fresh history can succeed.
+    Conflict,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -71,6 +75,15 @@ impl ChatError {
         Self::new(ChatErrorKind::Tooling, message)
     }
 
+    pub fn guardrail(message: impl Into<String>) -> Self {
+        Self::new(ChatErrorKind::Guardrail, message)
+    }
+
+    /// A retryable error for a turn that raced another turn on the same session.
+    pub fn conflict(message: impl Into<String>) -> Self {
+        Self::new(ChatErrorKind::Conflict, message).with_retryable(true)
+    }
+
     pub fn with_phase(mut self, phase: ChatErrorPhase) -> Self {
         self.phase = Some(phase);
         self
@@ -10,16 +10,29 @@
 //! assert_eq!(request.user_input, "Summarize this patch");
 //! ```
 
+mod approval;
+mod context;
 mod error;
+mod guardrail;
+mod normalize;
 mod service;
+mod session;
 mod store;
+mod stored;
+mod summary;
+mod tree;
 mod types;
 
 pub mod prelude {
     pub use crate::{
         ChatError, ChatErrorKind, ChatErrorPhase, ChatErrorSource, ChatEvent, ChatEventStream,
-        ChatPolicy, ChatService, ChatServiceBuilder, ChatSession, ChatTurnOptions, ChatTurnRequest,
-        ChatTurnRequestBuilder, ChatTurnResult, ConversationStore, InMemoryConversationStore,
+        ChatPolicy, ChatRound, ChatRoundKind, ChatService, ChatServiceBuilder, ChatSession,
+        ChatTurnOptions, ChatTurnRequest, ChatTurnRequestBuilder, ChatTurnResult,
+        ConcurrentTurnPolicy, ContextStrategy, ContextTrim, ConversationStore, ConversationSummary,
+        ConversationTree, Guardrail, GuardrailDecision, GuardrailStage, InMemoryConversationStore,
+        MessageId, MessageMetadata, MessageNode, RegexRedactor, SessionMetadata, SessionPage,
+        SessionPageRequest, StoredMessage, SummarizationPolicy, ToolApprovalDecision,
+        ToolApprovalHandle, ToolApprovalRequest, ToolApprover,
     };
     pub use fcommon::{MetadataMap, SessionId, TraceId};
     pub use ftooling::{
@@ -28,15 +41,26 @@ pub mod prelude {
     };
 }
 
+pub use approval::{ToolApprovalDecision, ToolApprovalHandle, ToolApprovalRequest, ToolApprover};
+pub use context::{
+    ContextStrategy, ContextTrim, DropToolOutputsStrategy, HeadTailStrategy, SlidingWindowStrategy,
+    TokenEstimator, estimate_tokens,
+};
 pub use error::{ChatError, ChatErrorKind, ChatErrorPhase, ChatErrorSource};
 pub use fcommon::{MetadataMap, SessionId, TraceId};
 pub use ftooling::{
     DefaultToolRuntime, Tool, ToolError, ToolErrorKind, ToolExecutionContext, ToolExecutionResult,
     ToolRegistry, ToolRuntime,
 };
-pub use service::{ChatPolicy, ChatService, ChatServiceBuilder};
+pub use guardrail::{Guardrail, GuardrailDecision, GuardrailStage, RegexRedactor};
+pub use normalize::normalize_transcript;
+pub use service::{ChatPolicy, ChatService, ChatServiceBuilder, ConcurrentTurnPolicy};
+pub use session::{DEFAULT_SESSION_PAGE_SIZE, SessionMetadata, SessionPage, SessionPageRequest};
 pub use store::{ConversationStore, InMemoryConversationStore};
+pub use stored::{MessageMetadata, StoredMessage};
+pub use summary::{ConversationSummary, DEFAULT_SUMMARY_INSTRUCTIONS, SummarizationPolicy};
+pub use tree::{ConversationTree, MessageId, MessageNode};
 pub use types::{
-    ChatEvent, ChatEventStream, ChatSession, ChatTurnOptions, ChatTurnRequest,
-    ChatTurnRequestBuilder, ChatTurnResult,
+    ChatEvent, ChatEventStream, ChatRound, ChatRoundKind, ChatSession, ChatTurnOptions,
+    ChatTurnRequest, ChatTurnRequestBuilder, ChatTurnResult,
 };
@@ -0,0 +1,84 @@
+//! Vendor-neutral transcript normalization.
+//!
+//! History written while talking to one provider can break another: a tool-call-only reply is
+//! stored as an empty assistant message, which Anthropic rejects, and a tool round leaves two
+//! assistant messages in a row, which vendors that expect alternating turns refuse.
+//! `ChatService` normalizes the history it sends on every turn so a session can switch provider
+//! or model between turns.
+//!
+//! ```rust
+//! use fchat::normalize_transcript;
+//! use fprovider::{Message, Role};
+//!
+//! let normalized = normalize_transcript(vec![
+//!     Message::new(Role::User, "look this up"),
+//!     Message::new(Role::Assistant, ""),
+//!     Message::new(Role::Assistant, "calling the search tool"),
+//!     Message::new(Role::Assistant, "found it"),
+//! ]);
+//!
+//! assert_eq!(normalized.len(), 2);
+//! assert_eq!(normalized[1].content, "calling the search tool\n\nfound it");
+//! ```
+
+use fprovider::{Message, Role};
+
+/// Drops messages with blank content and merges consecutive assistant messages. Other
+/// messages are kept as they are.
+pub fn normalize_transcript(messages: impl IntoIterator<Item = Message>) -> Vec<Message> {
+    let mut normalized = Vec::new();
+    for message in messages {
+        push_normalized(&mut normalized, message);
+    }
+    normalized
+}
+
+/// Appends `message` to an already normalized transcript, keeping it normalized.
+pub(crate) fn push_normalized(messages: &mut Vec<Message>, message: Message) {
+    if message.content.trim().is_empty() {
+        return;
+    }
+
+    match messages.last_mut() {
+        Some(last) if last.role == Role::Assistant && message.role == Role::Assistant => {
+            last.content.push_str("\n\n");
+            last.content.push_str(&message.content);
+        }
+        _ => messages.push(message),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_consecutive_non_assistant_messages_separate() {
+        let normalized = normalize_transcript(vec![
+            Message::new(Role::System, "be brief"),
+            Message::new(Role::System, "summary"),
+            Message::new(Role::User, "first"),
+            Message::new(Role::User, "second"),
+            Message::new(Role::Tool, "{}"),
+        ]);
+
+        assert_eq!(normalized.len(), 5);
+    }
+
+    #[test]
+    fn appending_keeps_a_normalized_prefix_stable() {
+        let messages = vec![
+            Message::new(Role::User, "a"),
+            Message::new(Role::Assistant, " "),
+            Message::new(Role::Assistant, "b"),
+            Message::new(Role::Assistant, "c"),
+        ];
+        let mut incremental = normalize_transcript(messages[..2].to_vec());
+        for message in messages[2..].iter().cloned() {
+            push_normalized(&mut incremental, message);
+        }
+
+        assert_eq!(incremental, normalize_transcript(messages));
+        assert_eq!(incremental[1].content, "b\n\nc");
+    }
+}
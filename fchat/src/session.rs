@@ -0,0 +1,113 @@
+//! Session listing and metadata for conversation stores.
+//!
+//! ```rust
+//! use std::time::{Duration, SystemTime};
+//!
+//! use fchat::{SessionMetadata, SessionPageRequest};
+//!
+//! let now = SystemTime::now();
+//! let older = SessionMetadata::new("a", now - Duration::from_secs(60));
+//! let newer = SessionMetadata::new("b", now).with_title("Release notes");
+//!
+//! let page = SessionPageRequest::new(1).paginate(vec![older, newer]);
+//! assert_eq!(page.sessions[0].title.as_deref(), Some("Release notes"));
+//! assert_eq!(page.next_offset, Some(1));
+//! ```
+
+use std::time::SystemTime;
+
+use fcommon::SessionId;
+
+pub const DEFAULT_SESSION_PAGE_SIZE: usize = 50;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SessionMetadata {
+    pub session_id: SessionId,
+    pub title: Option<String>,
+    pub created_at: SystemTime,
+    /// Last time messages were appended, the session was renamed, or it was truncated.
+    pub updated_at: SystemTime,
+    /// Stored messages across every branch.
+    pub message_count: usize,
+}
+
+impl SessionMetadata {
+    /// Metadata for an empty, untitled session created at `created_at`.
+    pub fn new(session_id: impl Into<SessionId>, created_at: SystemTime) -> Self {
+        Self {
+            session_id: session_id.into(),
+            title: None,
+            created_at,
+            updated_at: created_at,
+            message_count: 0,
+        }
+    }
+
+    pub fn with_title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    pub fn with_updated_at(mut self, updated_at: SystemTime) -> Self {
+        self.updated_at = updated_at;
+        self
+    }
+
+    pub fn with_message_count(mut self, message_count: usize) -> Self {
+        self.message_count = message_count;
+        self
+    }
+}
+
+/// Offset pagination over sessions ordered by most recent update.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SessionPageRequest {
+    pub offset: usize,
+    pub limit: usize,
+}
+
+impl SessionPageRequest {
+    pub fn new(limit: usize) -> Self {
+        Self { offset: 0, limit }
+    }
+
+    pub fn with_offset(mut self, offset: usize) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    /// Orders `sessions` by `updated_at` descending (ties by id) and cuts out this page.
+    pub fn paginate(&self, mut sessions: Vec<SessionMetadata>) -> SessionPage {
+        sessions.sort_by(|left, right| {
+            right
+                .updated_at
+                .cmp(&left.updated_at)
+                .then_with(|| left.session_id.as_str().cmp(right.session_id.as_str()))
+        });
+
+        let total = sessions.len();
+        let sessions = sessions
+            .into_iter()
+            .skip(self.offset)
+            .take(self.limit)
+            .collect::<Vec<_>>();
+        let end = self.offset.saturating_add(sessions.len());
+        SessionPage {
+            sessions,
+            next_offset: (end < total).then_some(end),
+        }
+    }
+}
+
+impl Default for SessionPageRequest {
+    fn default() -> Self {
+        Self::new(DEFAULT_SESSION_PAGE_SIZE)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct SessionPage {
+    pub sessions: Vec<SessionMetadata>,
+    /// Offset of the next page, or `None` on the last page.
+    pub next_offset: Option<usize>,
+}
@@ -9,30 +9,234 @@
 
 use std::collections::HashMap;
 use std::sync::Mutex;
+use std::time::SystemTime;
 
 use fcommon::{BoxFuture, SessionId};
 use fprovider::Message;
 
-use crate::ChatError;
+use crate::{
+    ChatError, ConversationSummary, ConversationTree, MessageId, MessageMetadata, SessionMetadata,
+    SessionPage, SessionPageRequest, StoredMessage,
+};
 
 pub type ChatFuture<'a, T> = BoxFuture<'a, T>;
 
 pub trait ConversationStore: Send + Sync {
+    /// Messages on the active branch, from the root to the active leaf.
     fn load_messages<'a>(
         &'a self,
         session_id: &'a SessionId,
     ) -> ChatFuture<'a, Result<Vec<Message>, ChatError>>;
 
+    /// Appends below the active leaf and makes the last appended message the new leaf.
     fn append_messages<'a>(
         &'a self,
         session_id: &'a SessionId,
         messages: Vec<Message>,
     ) -> ChatFuture<'a, Result<(), ChatError>>;
+
+    /// Appends like `append_messages`, recording each message's metadata. Stores that keep no
+    /// metadata drop it.
+    fn append_messages_with_metadata<'a>(
+        &'a self,
+        session_id: &'a SessionId,
+        messages: Vec<(Message, MessageMetadata)>,
+    ) -> ChatFuture<'a, Result<(), ChatError>> {
+        self.append_messages(
+            session_id,
+            messages.into_iter().map(|(message, _)| message).collect(),
+        )
+    }
+
+    /// Version of the session's transcript for optimistic concurrency: it starts at 0 and
+    /// increases on every append, active leaf change and truncation, so it never repeats while
+    /// the session exists. Deleting the session resets it. `None` when the store cannot append
+    /// conditionally.
+    fn session_version<'a>(
+        &'a self,
+        _session_id: &'a SessionId,
+    ) -> ChatFuture<'a, Result<Option<u64>, ChatError>> {
+        Box::pin(async { Ok(None) })
+    }
+
+    /// Appends like `append_messages_with_metadata` only while the session is still at
+    /// `expected_version`, checked atomically with the write; otherwise fails with
+    /// `ChatErrorKind::Conflict` and stores nothing.
+    fn append_messages_if_version<'a>(
+        &'a self,
+        _session_id: &'a SessionId,
+        _expected_version: u64,
+        _messages: Vec<(Message, MessageMetadata)>,
+    ) -> ChatFuture<'a, Result<(), ChatError>> {
+        Box::pin(async {
+            Err(ChatError::store(
+                "conversation store does not support conditional appends",
+            ))
+        })
+    }
+
+    /// The active branch with each message's id and metadata.
+    fn load_stored_messages<'a>(
+        &'a self,
+        session_id: &'a SessionId,
+    ) -> ChatFuture<'a, Result<Vec<StoredMessage>, ChatError>> {
+        Box::pin(async move { Ok(self.load_tree(session_id).await?.active_stored_messages()) })
+    }
+
+    /// Every message of the session with its parent pointer, plus the active leaf.
+    fn load_tree<'a>(
+        &'a self,
+        _session_id: &'a SessionId,
+    ) -> ChatFuture<'a, Result<ConversationTree, ChatError>> {
+        Box::pin(async {
+            Err(ChatError::store(
+                "conversation store does not support branching",
+            ))
+        })
+    }
+
+    /// Moves the active leaf to an existing message; `None` makes the next append start a new
+    /// root branch. Must not remove any message.
+    fn set_active_leaf<'a>(
+        &'a self,
+        _session_id: &'a SessionId,
+        _leaf: Option<MessageId>,
+    ) -> ChatFuture<'a, Result<(), ChatError>> {
+        Box::pin(async {
+            Err(ChatError::store(
+                "conversation store does not support branching",
+            ))
+        })
+    }
+
+    /// Latest rolling summary for the session, if one was saved.
+    fn load_summary<'a>(
+        &'a self,
+        _session_id: &'a SessionId,
+    ) -> ChatFuture<'a, Result<Option<ConversationSummary>, ChatError>> {
+        Box::pin(async { Ok(None) })
+    }
+
+    /// Replaces the session's summary. Must not modify the stored messages.
+    fn save_summary<'a>(
+        &'a self,
+        _session_id: &'a SessionId,
+        _summary: ConversationSummary,
+    ) -> ChatFuture<'a, Result<(), ChatError>> {
+        Box::pin(async {
+            Err(ChatError::store(
+                "conversation store does not persist summaries",
+            ))
+        })
+    }
+
+    /// Drops the session's summary, used when the active branch no longer contains the
+    /// messages it covers.
+    fn delete_summary<'a>(
+        &'a self,
+        _session_id: &'a SessionId,
+    ) -> ChatFuture<'a, Result<(), ChatError>> {
+        Box::pin(async { Ok(()) })
+    }
+
+    /// Sessions ordered by most recent update.
+    fn list_sessions<'a>(
+        &'a self,
+        _page: SessionPageRequest,
+    ) -> ChatFuture<'a, Result<SessionPage, ChatError>> {
+        Box::pin(async { Err(unsupported_session_management()) })
+    }
+
+    fn session_metadata<'a>(
+        &'a self,
+        _session_id: &'a SessionId,
+    ) -> ChatFuture<'a, Result<Option<SessionMetadata>, ChatError>> {
+        Box::pin(async { Err(unsupported_session_management()) })
+    }
+
+    /// Sets the session title, creating an empty session when none exists.
+    fn rename_session<'a>(
+        &'a self,
+        _session_id: &'a SessionId,
+        _title: String,
+    ) -> ChatFuture<'a, Result<(), ChatError>> {
+        Box::pin(async { Err(unsupported_session_management()) })
+    }
+
+    /// Removes the session's messages, summary and metadata. Unknown sessions are ignored.
+    fn delete_session<'a>(
+        &'a self,
+        _session_id: &'a SessionId,
+    ) -> ChatFuture<'a, Result<(), ChatError>> {
+        Box::pin(async { Err(unsupported_session_management()) })
+    }
+
+    /// Keeps the first `keep_messages` messages of the active branch and removes every other
+    /// message. The session becomes a single branch and message ids are reassigned from the
+    /// start.
+    fn truncate_session<'a>(
+        &'a self,
+        _session_id: &'a SessionId,
+        _keep_messages: usize,
+    ) -> ChatFuture<'a, Result<(), ChatError>> {
+        Box::pin(async { Err(unsupported_session_management()) })
+    }
+}
+
+fn stale_session(session_id: &SessionId, expected: u64, actual: u64) -> ChatError {
+    ChatError::conflict(format!(
+        "session '{session_id}' changed during the turn: expected version {expected}, found {actual}"
+    ))
+}
+
+fn unsupported_session_management() -> ChatError {
+    ChatError::store("conversation store does not support session management")
 }
 
 #[derive(Debug, Default)]
 pub struct InMemoryConversationStore {
-    sessions: Mutex<HashMap<SessionId, Vec<Message>>>,
+    sessions: Mutex<HashMap<SessionId, StoredSession>>,
+    summaries: Mutex<HashMap<SessionId, ConversationSummary>>,
+}
+
+#[derive(Debug, Clone)]
+struct StoredSession {
+    tree: ConversationTree,
+    title: Option<String>,
+    created_at: SystemTime,
+    updated_at: SystemTime,
+    version: u64,
+}
+
+impl StoredSession {
+    fn new() -> Self {
+        let now = SystemTime::now();
+        Self {
+            tree: ConversationTree::default(),
+            title: None,
+            created_at: now,
+            updated_at: now,
+            version: 0,
+        }
+    }
+
+    fn push(&mut self, messages: Vec<(Message, MessageMetadata)>) {
+        self.updated_at = SystemTime::now();
+        self.version += 1;
+        for (message, metadata) in messages {
+            self.tree.push_active(message, metadata);
+        }
+    }
+
+    fn metadata(&self, session_id: &SessionId) -> SessionMetadata {
+        let metadata = SessionMetadata::new(session_id.clone(), self.created_at)
+            .with_updated_at(self.updated_at)
+            .with_message_count(self.tree.nodes.len());
+        match &self.title {
+            Some(title) => metadata.with_title(title.clone()),
+            None => metadata,
+        }
+    }
 }
 
 impl InMemoryConversationStore {
@@ -52,7 +256,10 @@ impl ConversationStore for InMemoryConversationStore {
                 .lock()
                 .map_err(|_| ChatError::store("conversation store lock poisoned"))?;
 
-            Ok(sessions.get(session_id).cloned().unwrap_or_default())
+            Ok(sessions
+                .get(session_id)
+                .map(|session| session.tree.active_messages())
+                .unwrap_or_default())
         })
     }
 
@@ -60,6 +267,21 @@ impl ConversationStore for InMemoryConversationStore {
         &'a self,
         session_id: &'a SessionId,
         messages: Vec<Message>,
+    ) -> ChatFuture<'a, Result<(), ChatError>> {
+        let metadata = MessageMetadata::new(SystemTime::now());
+        self.append_messages_with_metadata(
+            session_id,
+            messages
+                .into_iter()
+                .map(|message| (message, metadata.clone()))
+                .collect(),
+        )
+    }
+
+    fn append_messages_with_metadata<'a>(
+        &'a self,
+        session_id: &'a SessionId,
+        messages: Vec<(Message, MessageMetadata)>,
     ) -> ChatFuture<'a, Result<(), ChatError>> {
         Box::pin(async move {
             let mut sessions = self
@@ -69,10 +291,415 @@ impl ConversationStore for InMemoryConversationStore {
 
             sessions
                 .entry(session_id.clone())
-                .or_default()
-                .extend(messages);
+                .or_insert_with(StoredSession::new)
+                .push(messages);
+
+            Ok(())
+        })
+    }
+
+    fn session_version<'a>(
+        &'a self,
+        session_id: &'a SessionId,
+    ) -> ChatFuture<'a, Result<Option<u64>, ChatError>> {
+        Box::pin(async move {
+            let sessions = self
+                .sessions
+                .lock()
+                .map_err(|_| ChatError::store("conversation store lock poisoned"))?;
+
+            Ok(Some(
+                sessions
+                    .get(session_id)
+                    .map_or(0, |session| session.version),
+            ))
+        })
+    }
+
+    fn append_messages_if_version<'a>(
+        &'a self,
+        session_id: &'a SessionId,
+        expected_version: u64,
+        messages: Vec<(Message, MessageMetadata)>,
+    ) -> ChatFuture<'a, Result<(), ChatError>> {
+        Box::pin(async move {
+            let mut sessions = self
+                .sessions
+                .lock()
+                .map_err(|_| ChatError::store("conversation store lock poisoned"))?;
+
+            let version = sessions
+                .get(session_id)
+                .map_or(0, |session| session.version);
+            if version != expected_version {
+                return Err(stale_session(session_id, expected_version, version));
+            }
+            sessions
+                .entry(session_id.clone())
+                .or_insert_with(StoredSession::new)
+                .push(messages);
+
+            Ok(())
+        })
+    }
+
+    fn load_tree<'a>(
+        &'a self,
+        session_id: &'a SessionId,
+    ) -> ChatFuture<'a, Result<ConversationTree, ChatError>> {
+        Box::pin(async move {
+            let sessions = self
+                .sessions
+                .lock()
+                .map_err(|_| ChatError::store("conversation store lock poisoned"))?;
+
+            Ok(sessions
+                .get(session_id)
+                .map(|session| session.tree.clone())
+                .unwrap_or_default())
+        })
+    }
+
+    fn set_active_leaf<'a>(
+        &'a self,
+        session_id: &'a SessionId,
+        leaf: Option<MessageId>,
+    ) -> ChatFuture<'a, Result<(), ChatError>> {
+        Box::pin(async move {
+            let mut sessions = self
+                .sessions
+                .lock()
+                .map_err(|_| ChatError::store("conversation store lock poisoned"))?;
+
+            match (sessions.get_mut(session_id), leaf) {
+                (Some(session), Some(leaf)) if session.tree.node(&leaf).is_some() => {
+                    session.tree.active_leaf = Some(leaf);
+                    session.version += 1;
+                }
+                (Some(session), None) => {
+                    session.tree.active_leaf = None;
+                    session.version += 1;
+                }
+                (None, None) => {}
+                (_, Some(leaf)) => {
+                    return Err(ChatError::invalid_request(format!(
+                        "message '{leaf}' does not exist in session '{session_id}'"
+                    )));
+                }
+            }
+            Ok(())
+        })
+    }
+
+    fn load_summary<'a>(
+        &'a self,
+        session_id: &'a SessionId,
+    ) -> ChatFuture<'a, Result<Option<ConversationSummary>, ChatError>> {
+        Box::pin(async move {
+            let summaries = self
+                .summaries
+                .lock()
+                .map_err(|_| ChatError::store("conversation store lock poisoned"))?;
+
+            Ok(summaries.get(session_id).cloned())
+        })
+    }
+
+    fn save_summary<'a>(
+        &'a self,
+        session_id: &'a SessionId,
+        summary: ConversationSummary,
+    ) -> ChatFuture<'a, Result<(), ChatError>> {
+        Box::pin(async move {
+            let mut summaries = self
+                .summaries
+                .lock()
+                .map_err(|_| ChatError::store("conversation store lock poisoned"))?;
+
+            summaries.insert(session_id.clone(), summary);
+            Ok(())
+        })
+    }
+
+    fn delete_summary<'a>(
+        &'a self,
+        session_id: &'a SessionId,
+    ) -> ChatFuture<'a, Result<(), ChatError>> {
+        Box::pin(async move {
+            let mut summaries = self
+                .summaries
+                .lock()
+                .map_err(|_| ChatError::store("conversation store lock poisoned"))?;
+
+            summaries.remove(session_id);
+            Ok(())
+        })
+    }
+
+    fn list_sessions<'a>(
+        &'a self,
+        page: SessionPageRequest,
+    ) -> ChatFuture<'a, Result<SessionPage, ChatError>> {
+        Box::pin(async move {
+            let sessions = self
+                .sessions
+                .lock()
+                .map_err(|_| ChatError::store("conversation store lock poisoned"))?;
+
+            Ok(page.paginate(
+                sessions
+                    .iter()
+                    .map(|(session_id, session)| session.metadata(session_id))
+                    .collect(),
+            ))
+        })
+    }
+
+    fn session_metadata<'a>(
+        &'a self,
+        session_id: &'a SessionId,
+    ) -> ChatFuture<'a, Result<Option<SessionMetadata>, ChatError>> {
+        Box::pin(async move {
+            let sessions = self
+                .sessions
+                .lock()
+                .map_err(|_| ChatError::store("conversation store lock poisoned"))?;
+
+            Ok(sessions
+                .get(session_id)
+                .map(|session| session.metadata(session_id)))
+        })
+    }
+
+    fn rename_session<'a>(
+        &'a self,
+        session_id: &'a SessionId,
+        title: String,
+    ) -> ChatFuture<'a, Result<(), ChatError>> {
+        Box::pin(async move {
+            let mut sessions = self
+                .sessions
+                .lock()
+                .map_err(|_| ChatError::store("conversation store lock poisoned"))?;
+
+            let session = sessions
+                .entry(session_id.clone())
+                .or_insert_with(StoredSession::new);
+            session.title = Some(title);
+            session.updated_at = SystemTime::now();
+            Ok(())
+        })
+    }
 
+    fn delete_session<'a>(
+        &'a self,
+        session_id: &'a SessionId,
+    ) -> ChatFuture<'a, Result<(), ChatError>> {
+        Box::pin(async move {
+            self.sessions
+                .lock()
+                .map_err(|_| ChatError::store("conversation store lock poisoned"))?
+                .remove(session_id);
+            self.summaries
+                .lock()
+                .map_err(|_| ChatError::store("conversation store lock poisoned"))?
+                .remove(session_id);
             Ok(())
         })
     }
+
+    fn truncate_session<'a>(
+        &'a self,
+        session_id: &'a SessionId,
+        keep_messages: usize,
+    ) -> ChatFuture<'a, Result<(), ChatError>> {
+        Box::pin(async move {
+            {
+                let mut sessions = self
+                    .sessions
+                    .lock()
+                    .map_err(|_| ChatError::store("conversation store lock poisoned"))?;
+                let Some(session) = sessions.get_mut(session_id) else {
+                    return Ok(());
+                };
+
+                let mut kept = ConversationTree::default();
+                for stored in session
+                    .tree
+                    .active_stored_messages()
+                    .into_iter()
+                    .take(keep_messages)
+                {
+                    kept.push_active(stored.message, stored.metadata);
+                }
+                session.tree = kept;
+                session.updated_at = SystemTime::now();
+                session.version += 1;
+            }
+
+            let mut summaries = self
+                .summaries
+                .lock()
+                .map_err(|_| ChatError::store("conversation store lock poisoned"))?;
+            if summaries
+                .get(session_id)
+                .is_some_and(|summary| summary.covered_messages > keep_messages)
+            {
+                summaries.remove(session_id);
+            }
+            Ok(())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use fprovider::Role;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn in_memory_store_lists_renames_truncates_and_deletes_sessions() {
+        let store = InMemoryConversationStore::new();
+        let alpha = SessionId::from("alpha");
+        let beta = SessionId::from("beta");
+        store
+            .append_messages(
+                &alpha,
+                vec![
+                    Message::new(Role::User, "hello"),
+                    Message::new(Role::Assistant, "hi"),
+                ],
+            )
+            .await
+            .expect("append should work");
+        std::thread::sleep(Duration::from_millis(2));
+        store
+            .rename_session(&beta, "Second".to_string())
+            .await
+            .expect("rename should work");
+
+        let page = store
+            .list_sessions(SessionPageRequest::new(1))
+            .await
+            .expect("list should work");
+        assert_eq!(page.sessions[0].session_id, beta);
+        assert_eq!(page.sessions[0].title.as_deref(), Some("Second"));
+        assert_eq!(page.next_offset, Some(1));
+        let page = store
+            .list_sessions(SessionPageRequest::new(1).with_offset(1))
+            .await
+            .expect("list should work");
+        assert_eq!(page.sessions[0].session_id, alpha);
+        assert_eq!(page.sessions[0].message_count, 2);
+        assert_eq!(page.next_offset, None);
+
+        store
+            .save_summary(&alpha, ConversationSummary::new("greeting", 2))
+            .await
+            .expect("summary should save");
+        store
+            .truncate_session(&alpha, 1)
+            .await
+            .expect("truncate should work");
+        let loaded = store.load_messages(&alpha).await.expect("load should work");
+        assert_eq!(loaded.len(), 1);
+        assert!(
+            store
+                .load_summary(&alpha)
+                .await
+                .expect("summary should load")
+                .is_none()
+        );
+
+        store
+            .delete_session(&beta)
+            .await
+            .expect("delete should work");
+        assert!(
+            store
+                .session_metadata(&beta)
+                .await
+                .expect("metadata should load")
+                .is_none()
+        );
+        let page = store
+            .list_sessions(SessionPageRequest::default())
+            .await
+            .expect("list should work");
+        assert_eq!(page.sessions.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn in_memory_store_rejects_appends_from_a_stale_version() {
+        let store = InMemoryConversationStore::new();
+        let session_id = SessionId::from("racy");
+        let reply = |content: &str| {
+            vec![(
+                Message::new(Role::User, content),
+                MessageMetadata::default(),
+            )]
+        };
+
+        assert_eq!(
+            store
+                .session_version(&session_id)
+                .await
+                .expect("version should work"),
+            Some(0)
+        );
+        store
+            .append_messages_if_version(&session_id, 0, reply("first"))
+            .await
+            .expect("append from the current version should work");
+        let error = store
+            .append_messages_if_version(&session_id, 0, reply("second"))
+            .await
+            .expect_err("append from a stale version should fail");
+
+        assert_eq!(error.kind, crate::ChatErrorKind::Conflict);
+        assert!(error.is_retryable());
+        let messages = store.load_messages(&session_id).await.expect("load");
+        assert_eq!(messages, vec![Message::new(Role::User, "first")]);
+    }
+
+    #[tokio::test]
+    async fn in_memory_store_versions_do_not_repeat_after_truncation() {
+        let store = InMemoryConversationStore::new();
+        let session_id = SessionId::from("aba");
+        let reply = |content: &str| {
+            vec![(
+                Message::new(Role::User, content),
+                MessageMetadata::default(),
+            )]
+        };
+
+        store
+            .append_messages_if_version(&session_id, 0, reply("first"))
+            .await
+            .expect("first append should work");
+        let stale = store
+            .session_version(&session_id)
+            .await
+            .expect("version should work")
+            .expect("store should be versioned");
+        store
+            .truncate_session(&session_id, 0)
+            .await
+            .expect("truncate should work");
+        store
+            .append_messages(&session_id, vec![Message::new(Role::User, "other")])
+            .await
+            .expect("append should work");
+
+        let error = store
+            .append_messages_if_version(&session_id, stale, reply("second"))
+            .await
+            .expect_err("append from before the truncation should fail");
+        assert_eq!(error.kind, crate::ChatErrorKind::Conflict);
+        let messages = store.load_messages(&session_id).await.expect("load");
+        assert_eq!(messages, vec![Message::new(Role::User, "other")]);
+    }
 }
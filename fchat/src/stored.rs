@@ -0,0 +1,76 @@
+//! Stored transcript messages and the metadata recorded with them.
+//!
+//! ```rust
+//! use std::time::SystemTime;
+//!
+//! use fchat::{MessageMetadata, StoredMessage};
+//! use fprovider::{Message, ProviderId, Role, TokenUsage};
+//!
+//! let metadata = MessageMetadata::new(SystemTime::now())
+//!     .with_model(ProviderId::OpenAi, "gpt-4o-mini")
+//!     .with_usage(TokenUsage::default());
+//! let stored = StoredMessage::new("2", Message::new(Role::Assistant, "hi")).with_metadata(metadata);
+//!
+//! assert_eq!(stored.metadata.model.as_deref(), Some("gpt-4o-mini"));
+//! ```
+
+use std::time::SystemTime;
+
+use fprovider::{Message, ProviderId, TokenUsage};
+
+use crate::MessageId;
+
+/// Facts recorded when a message was stored. Fields are `None` when unknown, such as for
+/// messages written before metadata was recorded or appended without it.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct MessageMetadata {
+    pub created_at: Option<SystemTime>,
+    /// Provider that produced the message; set on assistant replies.
+    pub provider: Option<ProviderId>,
+    pub model: Option<String>,
+    /// Usage of the provider response that produced the message.
+    pub usage: Option<TokenUsage>,
+}
+
+impl MessageMetadata {
+    pub fn new(created_at: SystemTime) -> Self {
+        Self {
+            created_at: Some(created_at),
+            ..Self::default()
+        }
+    }
+
+    pub fn with_model(mut self, provider: ProviderId, model: impl Into<String>) -> Self {
+        self.provider = Some(provider);
+        self.model = Some(model.into());
+        self
+    }
+
+    pub fn with_usage(mut self, usage: TokenUsage) -> Self {
+        self.usage = Some(usage);
+        self
+    }
+}
+
+/// A message as held by a conversation store: its id, content, and metadata.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StoredMessage {
+    pub id: MessageId,
+    pub message: Message,
+    pub metadata: MessageMetadata,
+}
+
+impl StoredMessage {
+    pub fn new(id: impl Into<MessageId>, message: Message) -> Self {
+        Self {
+            id: id.into(),
+            message,
+            metadata: MessageMetadata::default(),
+        }
+    }
+
+    pub fn with_metadata(mut self, metadata: MessageMetadata) -> Self {
+        self.metadata = metadata;
+        self
+    }
+}
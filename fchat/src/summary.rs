@@ -0,0 +1,134 @@
+//! Rolling summarization of older conversation history.
+//!
+//! When the unsummarized part of a transcript grows past
+//! [`SummarizationPolicy::trigger_tokens`], `ChatService` asks the provider to condense
+//! everything except the most recent messages into a [`ConversationSummary`]. The summary is
+//! saved through [`ConversationStore::save_summary`](crate::ConversationStore::save_summary)
+//! and sent in place of the messages it covers on later turns; the stored transcript itself is
+//! never rewritten, so the original messages remain available for audit.
+//!
+//! ```rust
+//! use fchat::SummarizationPolicy;
+//!
+//! let policy = SummarizationPolicy::new(6_000)
+//!     .with_keep_recent_messages(8)
+//!     .with_model("gpt-4o-mini");
+//!
+//! assert_eq!(policy.keep_recent_messages, 8);
+//! assert_eq!(policy.model.as_deref(), Some("gpt-4o-mini"));
+//! ```
+
+use std::time::SystemTime;
+
+use fprovider::{Message, Role};
+
+pub const DEFAULT_SUMMARY_INSTRUCTIONS: &str = "Summarize the conversation below so it can replace \
+the original messages. Keep facts, decisions, open questions, and anything the user asked to \
+remember. Write plain prose without preamble.";
+
+/// A persisted summary standing in for the start of a session's transcript.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConversationSummary {
+    pub content: String,
+    /// Number of stored messages, counted from the start of the transcript, that the summary
+    /// replaces.
+    pub covered_messages: usize,
+    pub created_at: SystemTime,
+}
+
+impl ConversationSummary {
+    pub fn new(content: impl Into<String>, covered_messages: usize) -> Self {
+        Self {
+            content: content.into(),
+            covered_messages,
+            created_at: SystemTime::now(),
+        }
+    }
+
+    /// The message sent to the provider in place of the covered history.
+    pub fn to_message(&self) -> Message {
+        Message::new(
+            Role::System,
+            format!("Summary of the earlier conversation:\n{}", self.content),
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SummarizationPolicy {
+    /// Estimated tokens of summary plus unsummarized history above which a new summary is made.
+    pub trigger_tokens: u32,
+    /// Most recent messages always left out of the summary.
+    pub keep_recent_messages: usize,
+    /// Summarizer model; defaults to the session's model.
+    pub model: Option<String>,
+    pub max_summary_tokens: Option<u32>,
+    pub instructions: String,
+}
+
+impl SummarizationPolicy {
+    pub fn new(trigger_tokens: u32) -> Self {
+        Self {
+            trigger_tokens,
+            keep_recent_messages: 6,
+            model: None,
+            max_summary_tokens: None,
+            instructions: DEFAULT_SUMMARY_INSTRUCTIONS.to_string(),
+        }
+    }
+
+    pub fn with_keep_recent_messages(mut self, keep_recent_messages: usize) -> Self {
+        self.keep_recent_messages = keep_recent_messages;
+        self
+    }
+
+    pub fn with_model(mut self, model: impl Into<String>) -> Self {
+        self.model = Some(model.into());
+        self
+    }
+
+    pub fn with_max_summary_tokens(mut self, max_summary_tokens: u32) -> Self {
+        self.max_summary_tokens = Some(max_summary_tokens);
+        self
+    }
+
+    pub fn with_instructions(mut self, instructions: impl Into<String>) -> Self {
+        self.instructions = instructions.into();
+        self
+    }
+
+    /// Messages for the summarizer call, folding any previous summary into the new one.
+    pub(crate) fn summarizer_messages(
+        &self,
+        previous: Option<&ConversationSummary>,
+        messages: &[Message],
+    ) -> Vec<Message> {
+        let mut transcript = String::new();
+        if let Some(previous) = previous {
+            transcript.push_str("Earlier summary:\n");
+            transcript.push_str(&previous.content);
+            transcript.push_str("\n\n");
+        }
+        transcript.push_str("Conversation:\n");
+        for message in messages {
+            transcript.push_str(role_label(message.role));
+            transcript.push_str(": ");
+            transcript.push_str(&message.content);
+            transcript.push('\n');
+        }
+
+        vec![
+            Message::new(Role::System, self.instructions.clone()),
+            Message::new(Role::User, transcript),
+        ]
+    }
+}
+
+fn role_label(role: Role) -> &'static str {
+    match role {
+        Role::System => "system",
+        Role::User => "user",
+        Role::Assistant => "assistant",
+        Role::Tool => "tool",
+    }
+}
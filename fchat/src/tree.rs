@@ -0,0 +1,236 @@
+//! Branching conversation history.
+//!
+//! Every stored message has a [`MessageId`] and points at the message it follows, so a session
+//! is a tree rather than a list. One leaf is active at a time: `load_messages` returns the path
+//! from the root to that leaf and `append_messages` continues below it. Regenerating an answer
+//! or editing an earlier message moves the active leaf back and appends a sibling branch; the
+//! original branch stays in the tree and can be made active again.
+//!
+//! ```rust
+//! use fchat::{ConversationTree, MessageId, MessageNode};
+//! use fprovider::{Message, Role};
+//!
+//! let tree = ConversationTree::new(
+//!     vec![
+//!         MessageNode::new("1", None, Message::new(Role::User, "hello")),
+//!         MessageNode::new("2", Some("1".into()), Message::new(Role::Assistant, "hi")),
+//!         MessageNode::new("3", Some("1".into()), Message::new(Role::Assistant, "hey")),
+//!     ],
+//!     Some(MessageId::from("3")),
+//! );
+//!
+//! assert_eq!(tree.active_messages()[1].content, "hey");
+//! assert_eq!(tree.children(Some(&MessageId::from("1"))).len(), 2);
+//! assert_eq!(tree.leaves().len(), 2);
+//! ```
+
+use std::fmt::{Display, Formatter};
+
+use fprovider::Message;
+
+use crate::{MessageMetadata, StoredMessage};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MessageId(String);
+
+impl MessageId {
+    pub fn new(value: impl Into<String>) -> Self {
+        Self(value.into())
+    }
+
+    pub fn as_str(&self) -> &str {
+        self.0.as_str()
+    }
+}
+
+impl Display for MessageId {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl From<String> for MessageId {
+    fn from(value: String) -> Self {
+        Self(value)
+    }
+}
+
+impl From<&str> for MessageId {
+    fn from(value: &str) -> Self {
+        Self(value.to_string())
+    }
+}
+
+/// A stored message and the message it follows; `None` marks the first message of a branch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MessageNode {
+    pub id: MessageId,
+    pub parent_id: Option<MessageId>,
+    pub message: Message,
+    pub metadata: MessageMetadata,
+}
+
+impl MessageNode {
+    pub fn new(id: impl Into<MessageId>, parent_id: Option<MessageId>, message: Message) -> Self {
+        Self {
+            id: id.into(),
+            parent_id,
+            message,
+            metadata: MessageMetadata::default(),
+        }
+    }
+
+    pub fn with_metadata(mut self, metadata: MessageMetadata) -> Self {
+        self.metadata = metadata;
+        self
+    }
+
+    pub fn to_stored(&self) -> StoredMessage {
+        StoredMessage::new(self.id.clone(), self.message.clone())
+            .with_metadata(self.metadata.clone())
+    }
+}
+
+/// Every message of a session in append order, plus the active leaf.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ConversationTree {
+    pub nodes: Vec<MessageNode>,
+    /// `None` means the active branch is empty.
+    pub active_leaf: Option<MessageId>,
+}
+
+impl ConversationTree {
+    pub fn new(nodes: Vec<MessageNode>, active_leaf: Option<MessageId>) -> Self {
+        Self { nodes, active_leaf }
+    }
+
+    pub fn node(&self, id: &MessageId) -> Option<&MessageNode> {
+        self.nodes.iter().find(|node| &node.id == id)
+    }
+
+    /// Nodes from the root down to `id`, or an empty path when `id` is unknown.
+    pub fn path_to(&self, id: &MessageId) -> Vec<&MessageNode> {
+        let mut path = Vec::new();
+        let mut next = Some(id);
+        while let Some(id) = next {
+            // A parent cycle would otherwise never terminate.
+            if path.len() > self.nodes.len() {
+                return Vec::new();
+            }
+            let Some(node) = self.node(id) else {
+                return Vec::new();
+            };
+            path.push(node);
+            next = node.parent_id.as_ref();
+        }
+        path.reverse();
+        path
+    }
+
+    pub fn active_path(&self) -> Vec<&MessageNode> {
+        self.active_leaf
+            .as_ref()
+            .map(|leaf| self.path_to(leaf))
+            .unwrap_or_default()
+    }
+
+    pub fn active_messages(&self) -> Vec<Message> {
+        self.active_path()
+            .into_iter()
+            .map(|node| node.message.clone())
+            .collect()
+    }
+
+    /// The active branch with each message's id and metadata.
+    pub fn active_stored_messages(&self) -> Vec<StoredMessage> {
+        self.active_path()
+            .into_iter()
+            .map(MessageNode::to_stored)
+            .collect()
+    }
+
+    /// Direct children of `parent` in append order; `None` lists the roots.
+    pub fn children(&self, parent: Option<&MessageId>) -> Vec<&MessageNode> {
+        self.nodes
+            .iter()
+            .filter(|node| node.parent_id.as_ref() == parent)
+            .collect()
+    }
+
+    pub fn leaves(&self) -> Vec<&MessageNode> {
+        self.nodes
+            .iter()
+            .filter(|node| self.children(Some(&node.id)).is_empty())
+            .collect()
+    }
+
+    /// The leaf reached from `id` by always following the most recently appended child.
+    pub fn latest_leaf_under(&self, id: &MessageId) -> Option<&MessageId> {
+        let mut current = self.node(id)?;
+        while let Some(child) = self.children(Some(&current.id)).last() {
+            current = child;
+        }
+        Some(&current.id)
+    }
+
+    /// Appends below the active leaf with the next 1-based position as id and makes it the
+    /// active leaf.
+    pub(crate) fn push_active(&mut self, message: Message, metadata: MessageMetadata) {
+        let id = MessageId::new((self.nodes.len() + 1).to_string());
+        let parent_id = self.active_leaf.replace(id.clone());
+        self.nodes
+            .push(MessageNode::new(id, parent_id, message).with_metadata(metadata));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fprovider::Role;
+
+    use super::*;
+
+    fn node(id: &str, parent: Option<&str>, content: &str) -> MessageNode {
+        MessageNode::new(
+            id,
+            parent.map(MessageId::from),
+            Message::new(Role::User, content),
+        )
+    }
+
+    #[test]
+    fn latest_leaf_follows_most_recent_children() {
+        let tree = ConversationTree::new(
+            vec![
+                node("1", None, "root"),
+                node("2", Some("1"), "first"),
+                node("3", Some("2"), "first reply"),
+                node("4", Some("1"), "edited"),
+                node("5", Some("4"), "edited reply"),
+            ],
+            Some(MessageId::from("3")),
+        );
+
+        assert_eq!(
+            tree.latest_leaf_under(&MessageId::from("1")),
+            Some(&MessageId::from("5"))
+        );
+        assert_eq!(
+            tree.active_messages()
+                .iter()
+                .map(|message| message.content.as_str())
+                .collect::<Vec<_>>(),
+            vec!["root", "first", "first reply"]
+        );
+    }
+
+    #[test]
+    fn unknown_or_cyclic_paths_are_empty() {
+        let tree = ConversationTree::new(
+            vec![node("1", Some("2"), "a"), node("2", Some("1"), "b")],
+            Some(MessageId::from("1")),
+        );
+
+        assert!(tree.active_path().is_empty());
+        assert!(tree.path_to(&MessageId::from("missing")).is_empty());
+    }
+}
@@ -16,11 +16,14 @@
 //! ```
 
 use std::pin::Pin;
+use std::time::Duration;
 
 use fcommon::{GenerationOptions, SessionId};
 use fprovider::{ProviderId, StopReason, TokenUsage, ToolCall};
 use futures_core::Stream;
 
+use crate::{ContextTrim, ConversationSummary, ToolApprovalRequest};
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ChatSession {
     pub id: SessionId,
@@ -43,6 +46,14 @@ impl ChatSession {
         self.system_prompt = Some(system_prompt.into());
         self
     }
+
+    /// Moves the session to another provider and model; its history carries over on the next
+    /// turn when the service dispatches through a `ProviderRegistry`.
+    pub fn with_model(mut self, provider: ProviderId, model: impl Into<String>) -> Self {
+        self.provider = provider;
+        self.model = model.into();
+        self
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -139,14 +150,58 @@ pub struct ChatTurnResult {
     pub assistant_message: String,
     pub tool_calls: Vec<ToolCall>,
     pub stop_reason: StopReason,
+    /// Usage summed over every provider completion of the turn, including tool round trips and
+    /// the summarizer call.
     pub usage: TokenUsage,
     pub tool_round_limit_reached: bool,
+    /// History removed by context strategies before this turn was sent.
+    pub context_trims: Vec<ContextTrim>,
+    /// One entry per provider completion, in order, starting with the summarizer call when
+    /// history was summarized this turn; the last one produced `assistant_message`.
+    pub rounds: Vec<ChatRound>,
+}
+
+impl ChatTurnResult {
+    /// Provider requests sent for the turn, including retries.
+    pub fn request_count(&self) -> u32 {
+        self.rounds.iter().map(|round| round.requests).sum()
+    }
+}
+
+/// One provider completion within a turn.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChatRound {
+    pub kind: ChatRoundKind,
+    pub usage: TokenUsage,
+    /// Requests sent to get this completion; more than one when attempts were retried.
+    pub requests: u32,
+    /// Tool calls the model asked for in this completion.
+    pub tool_calls: usize,
+    /// Time from the first request attempt until the completion was fully received.
+    pub latency: Duration,
+}
+
+/// What a provider completion within a turn was for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChatRoundKind {
+    /// The summarizer call that condensed older history before the turn's first request.
+    Summarization,
+    /// A request for the assistant's reply, including tool round trips.
+    Response,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ChatEvent {
+    /// Emitted before the first provider request when a context strategy removed history.
+    ContextTrimmed(ContextTrim),
+    /// Emitted before the first provider request when older history was condensed into a new
+    /// summary this turn.
+    HistorySummarized(ConversationSummary),
     TextDelta(String),
     ToolCallDelta(ToolCall),
+    /// Emitted when a tool that requires approval is called and no `ToolApprover` is
+    /// configured. The stream waits until a decision is delivered through the request's handle.
+    ToolApprovalRequested(ToolApprovalRequest),
     ToolExecutionStarted(ToolCall),
     ToolExecutionFinished(ToolCall),
     AssistantMessageComplete(String),
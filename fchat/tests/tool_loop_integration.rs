@@ -34,7 +34,9 @@ impl ModelProvider for ToolLoopProvider {
                         input_tokens: 5,
                         output_tokens: 2,
                         total_tokens: 7,
+                        cached_input_tokens: 0,
                     },
+                    response_id: None,
                 });
             }
 
@@ -47,7 +49,9 @@ impl ModelProvider for ToolLoopProvider {
                     input_tokens: 6,
                     output_tokens: 2,
                     total_tokens: 8,
+                    cached_input_tokens: 0,
                 },
+                response_id: None,
             })
         })
     }
@@ -71,7 +75,9 @@ impl ModelProvider for ToolLoopProvider {
                         input_tokens: 5,
                         output_tokens: 2,
                         total_tokens: 7,
+                        cached_input_tokens: 0,
                     },
+                    response_id: None,
                 };
 
                 let stream = VecEventStream::new(vec![
@@ -95,7 +101,9 @@ impl ModelProvider for ToolLoopProvider {
                     input_tokens: 6,
                     output_tokens: 2,
                     total_tokens: 8,
+                    cached_input_tokens: 0,
                 },
+                response_id: None,
             };
 
             let stream = VecEventStream::new(vec![
@@ -76,6 +76,7 @@ mod tests {
                     ))],
                     stop_reason: StopReason::EndTurn,
                     usage: TokenUsage::default(),
+                    response_id: None,
                 })
             })
         }
@@ -95,6 +96,7 @@ mod tests {
                     ))],
                     stop_reason: StopReason::EndTurn,
                     usage: TokenUsage::default(),
+                    response_id: None,
                 };
                 let stream = VecEventStream::new(vec![
                     Ok(StreamEvent::TextDelta("implemented-stream".to_string())),
@@ -144,6 +146,7 @@ mod tests {
                     ))],
                     stop_reason: StopReason::EndTurn,
                     usage: TokenUsage::default(),
+                    response_id: None,
                 })
             })
         }
@@ -167,6 +170,7 @@ mod tests {
                     ))],
                     stop_reason: StopReason::EndTurn,
                     usage: TokenUsage::default(),
+                    response_id: None,
                 };
                 let stream = VecEventStream::new(vec![Ok(StreamEvent::ResponseComplete(response))]);
                 Ok(Box::pin(stream) as fprovider::BoxedEventStream<'a>)
@@ -198,6 +202,7 @@ mod tests {
                         })],
                         stop_reason: StopReason::EndTurn,
                         usage: TokenUsage::default(),
+                        response_id: None,
                     })
                 } else {
                     Ok(ModelResponse {
@@ -209,6 +214,7 @@ mod tests {
                         ))],
                         stop_reason: StopReason::EndTurn,
                         usage: TokenUsage::default(),
+                        response_id: None,
                     })
                 }
             })
@@ -229,6 +235,7 @@ mod tests {
                     ))],
                     stop_reason: StopReason::EndTurn,
                     usage: TokenUsage::default(),
+                    response_id: None,
                 };
 
                 let stream = VecEventStream::new(vec![Ok(StreamEvent::ResponseComplete(response))]);
@@ -417,6 +424,7 @@ mod tests {
                     ))],
                     stop_reason: StopReason::EndTurn,
                     usage: TokenUsage::default(),
+                    response_id: None,
                 })
             })
         }
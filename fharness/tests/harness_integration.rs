@@ -34,6 +34,7 @@ impl ModelProvider for FixedAssistantProvider {
                 ))],
                 stop_reason: StopReason::EndTurn,
                 usage: TokenUsage::default(),
+                response_id: None,
             })
         })
     }
@@ -0,0 +1,167 @@
+//! Facade builder for stacking provider middleware layers.
+//!
+//! Layers are applied in the order they are added: the first one sees each request first and
+//! each response last.
+//!
+//! ```rust
+//! use std::sync::Arc;
+//!
+//! use fiddlesticks::{ModelProvider, ProviderStackBuilder};
+//!
+//! fn wrap(provider: Arc<dyn ModelProvider>) -> Arc<dyn ModelProvider> {
+//!     ProviderStackBuilder::new(provider)
+//!         .metadata("tenant", "acme")
+//!         .redact(["hunter2"])
+//!         .map_request(|request| request.options.temperature = Some(0.2))
+//!         .build()
+//! }
+//! ```
+
+use std::sync::Arc;
+
+use fprovider::{
+    CacheLayer, HealthProbeLayer, InterceptorLayer, MapRequestLayer, MetadataLayer,
+    ProviderInterceptor, ProviderLayer, RedactionLayer, ResponseCache, StreamTimeoutLayer,
+    StreamTimeouts, apply_layers,
+};
+
+use crate::{ModelProvider, ModelRequest};
+
+pub struct ProviderStackBuilder {
+    provider: Arc<dyn ModelProvider>,
+    layers: Vec<Arc<dyn ProviderLayer>>,
+}
+
+impl ProviderStackBuilder {
+    pub fn new(provider: Arc<dyn ModelProvider>) -> Self {
+        Self {
+            provider,
+            layers: Vec::new(),
+        }
+    }
+
+    pub fn layer(mut self, layer: impl ProviderLayer + 'static) -> Self {
+        self.layers.push(Arc::new(layer));
+        self
+    }
+
+    pub fn intercept(self, interceptor: Arc<dyn ProviderInterceptor>) -> Self {
+        self.layer(InterceptorLayer::new(interceptor))
+    }
+
+    pub fn metadata(self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.layer(MetadataLayer::new().with_entry(key, value))
+    }
+
+    pub fn map_request<F>(self, rewrite: F) -> Self
+    where
+        F: Fn(&mut ModelRequest) + Send + Sync + 'static,
+    {
+        self.layer(MapRequestLayer::new(rewrite))
+    }
+
+    pub fn redact<I, S>(self, patterns: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.layer(RedactionLayer::new(patterns))
+    }
+
+    /// Serves repeated requests from `cache`; layers added after this one are skipped on a hit.
+    pub fn cache(self, cache: Arc<dyn ResponseCache>) -> Self {
+        self.layer(CacheLayer::new(cache))
+    }
+
+    /// Fails streams that miss the first-token or inter-chunk idle deadline.
+    pub fn stream_timeouts(self, timeouts: StreamTimeouts) -> Self {
+        self.layer(StreamTimeoutLayer::new(timeouts))
+    }
+
+    /// Probes health with a one-token completion against `model` instead of the adapter's probe.
+    pub fn health_probe(self, model: impl Into<String>) -> Self {
+        self.layer(HealthProbeLayer::completion(model))
+    }
+
+    pub fn build(self) -> Arc<dyn ModelProvider> {
+        apply_layers(self.provider, &self.layers)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+    use crate::{
+        BoxedEventStream, Message, ModelResponse, OutputItem, ProviderError, ProviderFuture,
+        ProviderId, Role, StopReason, TokenUsage, VecEventStream,
+    };
+
+    #[derive(Default)]
+    struct RecordingProvider {
+        requests: Mutex<Vec<ModelRequest>>,
+    }
+
+    impl ModelProvider for RecordingProvider {
+        fn id(&self) -> ProviderId {
+            ProviderId::OpenAi
+        }
+
+        fn complete<'a>(
+            &'a self,
+            request: ModelRequest,
+        ) -> ProviderFuture<'a, Result<ModelResponse, ProviderError>> {
+            Box::pin(async move {
+                self.requests
+                    .lock()
+                    .expect("requests lock")
+                    .push(request.clone());
+                Ok(ModelResponse {
+                    provider: ProviderId::OpenAi,
+                    model: request.model,
+                    output: vec![OutputItem::Message(Message::new(Role::Assistant, "ok"))],
+                    stop_reason: StopReason::EndTurn,
+                    usage: TokenUsage::default(),
+                    response_id: None,
+                })
+            })
+        }
+
+        fn stream<'a>(
+            &'a self,
+            _request: ModelRequest,
+        ) -> ProviderFuture<'a, Result<BoxedEventStream<'a>, ProviderError>> {
+            Box::pin(async move {
+                Ok(Box::pin(VecEventStream::new(Vec::new())) as BoxedEventStream<'a>)
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn builder_stacks_layers_in_order() {
+        let inner = Arc::new(RecordingProvider::default());
+        let provider = ProviderStackBuilder::new(inner.clone())
+            .map_request(|request| request.model.push_str("-outer"))
+            .map_request(|request| request.model.push_str("-inner"))
+            .metadata("tenant", "acme")
+            .redact(["hunter2"])
+            .build();
+
+        provider
+            .complete(ModelRequest::new(
+                "gpt-4o-mini",
+                vec![Message::new(Role::User, "pw hunter2")],
+            ))
+            .await
+            .expect("completion should succeed");
+
+        let requests = inner.requests.lock().expect("requests lock");
+        assert_eq!(requests[0].model, "gpt-4o-mini-outer-inner");
+        assert_eq!(requests[0].messages[0].content, "pw [REDACTED]");
+        assert_eq!(
+            requests[0].metadata.get("tenant"),
+            Some(&"acme".to_string())
+        );
+    }
+}
@@ -18,6 +18,7 @@
 //! ```
 
 mod agent;
+mod layers;
 mod macros;
 mod providers;
 
@@ -27,8 +28,14 @@ pub mod util;
 
 pub use fchat::{
     ChatError, ChatErrorKind, ChatErrorPhase, ChatErrorSource, ChatEvent, ChatEventStream,
-    ChatPolicy, ChatService, ChatServiceBuilder, ChatSession, ChatTurnOptions, ChatTurnRequest,
-    ChatTurnRequestBuilder, ChatTurnResult, ConversationStore, InMemoryConversationStore,
+    ChatPolicy, ChatRound, ChatRoundKind, ChatService, ChatServiceBuilder, ChatSession,
+    ChatTurnOptions, ChatTurnRequest, ChatTurnRequestBuilder, ChatTurnResult, ConcurrentTurnPolicy,
+    ContextStrategy, ContextTrim, ConversationStore, ConversationSummary, ConversationTree,
+    DropToolOutputsStrategy, Guardrail, GuardrailDecision, GuardrailStage, HeadTailStrategy,
+    InMemoryConversationStore, MessageId, MessageMetadata, MessageNode, RegexRedactor,
+    SessionMetadata, SessionPage, SessionPageRequest, SlidingWindowStrategy, StoredMessage,
+    SummarizationPolicy, ToolApprovalDecision, ToolApprovalHandle, ToolApprovalRequest,
+    ToolApprover, normalize_transcript,
 };
 pub use fcommon::{BoxFuture, MetadataMap, SessionId, TraceId};
 pub use fharness::{
@@ -45,13 +52,17 @@ pub use fmemory::{
     SqliteMemoryBackend, create_default_memory_backend, create_memory_backend,
 };
 pub use fprovider::{
-    BoxedEventStream, BrowserLoginSession, CredentialAccessAction, CredentialAccessEvent,
-    CredentialAccessObserver, CredentialKind, CredentialMetadata, Message, ModelEventStream,
-    ModelProvider, ModelRequest, ModelRequestBuilder, ModelResponse, NoopOperationHooks,
-    OutputItem, ProviderCredential, ProviderError, ProviderErrorKind, ProviderFuture, ProviderId,
-    ProviderOperationHooks, ProviderRegistry, RetryPolicy, Role, SecretString,
-    SecureCredentialManager, StopReason, StreamEvent, TokenUsage, ToolCall, ToolDefinition,
-    ToolResult, VecEventStream, execute_with_retry,
+    BoxedEventStream, BrowserLoginSession, CacheKey, CacheLayer, CachedResponse, CachingProvider,
+    CredentialAccessAction, CredentialAccessEvent, CredentialAccessObserver, CredentialKind,
+    CredentialMetadata, HealthProbeLayer, HealthStatus, InMemoryResponseCache, InterceptorLayer,
+    MapRequestLayer, Message, MetadataLayer, ModelEventStream, ModelInfo, ModelProvider,
+    ModelRequest, ModelRequestBuilder, ModelResponse, NoopOperationHooks, OutputItem,
+    ProviderCredential, ProviderError, ProviderErrorKind, ProviderFuture, ProviderHealth,
+    ProviderId, ProviderInterceptor, ProviderLayer, ProviderOperationHooks, ProviderRegistry,
+    RedactionLayer, ResponseCache, RetryPolicy, Role, SecretString, SecureCredentialManager,
+    SqliteResponseCache, StopReason, StreamAccumulator, StreamEvent, StreamTimeoutLayer,
+    StreamTimeouts, TokenUsage, ToolCall, ToolDefinition, ToolResult, VecEventStream, apply_layers,
+    execute_with_retry,
 };
 pub use ftooling::{
     DefaultToolRuntime, FunctionTool, NoopToolRuntimeHooks, Tool, ToolError, ToolErrorKind,
@@ -60,13 +71,15 @@ pub use ftooling::{
 };
 
 pub use agent::{AgentHarnessBuilder, AgentRuntime};
+pub use layers::ProviderStackBuilder;
 pub use providers::{
-    ProviderBuildConfig, build_provider_from_api_key, build_provider_with_config,
-    list_models_with_api_key,
+    OpenAiTransportKind, ProviderBuildConfig, build_provider_from_api_key,
+    build_provider_with_config, list_models_with_api_key,
 };
 pub use runtime::{
     RuntimeBundle, build_runtime, build_runtime_with, build_runtime_with_memory,
-    build_runtime_with_tooling, chat_service, chat_service_with_memory, in_memory_backend,
+    build_runtime_with_tooling, chat_service, chat_service_with_memory, chat_service_with_registry,
+    in_memory_backend,
 };
 pub use util::{
     assistant_message, parse_provider_id, session, streaming_turn, system_message, tool_message,
@@ -76,8 +89,14 @@ pub use util::{
 pub mod chat {
     pub use crate::{
         ChatError, ChatErrorKind, ChatErrorPhase, ChatErrorSource, ChatEvent, ChatEventStream,
-        ChatPolicy, ChatService, ChatServiceBuilder, ChatSession, ChatTurnOptions, ChatTurnRequest,
-        ChatTurnRequestBuilder, ChatTurnResult, ConversationStore, InMemoryConversationStore,
+        ChatPolicy, ChatRound, ChatRoundKind, ChatService, ChatServiceBuilder, ChatSession,
+        ChatTurnOptions, ChatTurnRequest, ChatTurnRequestBuilder, ChatTurnResult,
+        ConcurrentTurnPolicy, ContextStrategy, ContextTrim, ConversationStore, ConversationSummary,
+        ConversationTree, DropToolOutputsStrategy, Guardrail, GuardrailDecision, GuardrailStage,
+        HeadTailStrategy, InMemoryConversationStore, MessageId, MessageMetadata, MessageNode,
+        RegexRedactor, SessionMetadata, SessionPage, SessionPageRequest, SlidingWindowStrategy,
+        StoredMessage, SummarizationPolicy, ToolApprovalDecision, ToolApprovalHandle,
+        ToolApprovalRequest, ToolApprover, normalize_transcript,
     };
 }
 
@@ -102,13 +121,17 @@ pub mod memory {
 
 pub mod provider {
     pub use crate::{
-        BoxedEventStream, BrowserLoginSession, CredentialAccessAction, CredentialAccessEvent,
-        CredentialAccessObserver, CredentialKind, CredentialMetadata, Message, ModelEventStream,
+        BoxedEventStream, BrowserLoginSession, CacheKey, CacheLayer, CachedResponse,
+        CachingProvider, CredentialAccessAction, CredentialAccessEvent, CredentialAccessObserver,
+        CredentialKind, CredentialMetadata, HealthProbeLayer, HealthStatus, InMemoryResponseCache,
+        InterceptorLayer, MapRequestLayer, Message, MetadataLayer, ModelEventStream, ModelInfo,
         ModelProvider, ModelRequest, ModelRequestBuilder, ModelResponse, NoopOperationHooks,
         OutputItem, ProviderCredential, ProviderError, ProviderErrorKind, ProviderFuture,
-        ProviderId, ProviderOperationHooks, ProviderRegistry, RetryPolicy, Role, SecretString,
-        SecureCredentialManager, StopReason, StreamEvent, TokenUsage, ToolCall, ToolDefinition,
-        ToolResult, VecEventStream, execute_with_retry,
+        ProviderHealth, ProviderId, ProviderInterceptor, ProviderLayer, ProviderOperationHooks,
+        ProviderRegistry, ProviderStackBuilder, RedactionLayer, ResponseCache, RetryPolicy, Role,
+        SecretString, SecureCredentialManager, SqliteResponseCache, StopReason, StreamAccumulator,
+        StreamEvent, StreamTimeoutLayer, StreamTimeouts, TokenUsage, ToolCall, ToolDefinition,
+        ToolResult, VecEventStream, apply_layers, execute_with_retry,
     };
 }
 
@@ -79,6 +79,9 @@ macro_rules! fs_session {
     ($session_id:expr, claude, $model:expr $(,)?) => {
         $crate::ChatSession::new($session_id, $crate::ProviderId::Anthropic, $model)
     };
+    ($session_id:expr, gemini, $model:expr $(,)?) => {
+        $crate::ChatSession::new($session_id, $crate::ProviderId::Gemini, $model)
+    };
     ($session_id:expr, $provider:expr, $model:expr $(,)?) => {
         $crate::ChatSession::new($session_id, $provider, $model)
     };
@@ -106,6 +109,10 @@ macro_rules! fs_session {
         $crate::ChatSession::new($session_id, $crate::ProviderId::Anthropic, $model)
             .with_system_prompt($system_prompt)
     };
+    ($session_id:expr, gemini, $model:expr, $system_prompt:expr $(,)?) => {
+        $crate::ChatSession::new($session_id, $crate::ProviderId::Gemini, $model)
+            .with_system_prompt($system_prompt)
+    };
     ($session_id:expr, $provider:expr, $model:expr, $system_prompt:expr $(,)?) => {
         $crate::ChatSession::new($session_id, $provider, $model).with_system_prompt($system_prompt)
     };
@@ -1,8 +1,9 @@
 //! Common imports for most Fiddlesticks applications.
 
 pub use crate::{
-    AgentHarnessBuilder, AgentRuntime, ProviderBuildConfig, build_provider_from_api_key,
-    build_provider_with_config, list_models_with_api_key,
+    AgentHarnessBuilder, AgentRuntime, OpenAiTransportKind, ProviderBuildConfig,
+    ProviderStackBuilder, build_provider_from_api_key, build_provider_with_config,
+    list_models_with_api_key,
 };
 pub use crate::{
     BoxFuture, ChatError, ChatErrorKind, ChatErrorPhase, ChatErrorSource, ChatEvent,
@@ -17,7 +18,7 @@ pub use crate::{
 };
 pub use crate::{
     assistant_message, build_runtime, build_runtime_with, build_runtime_with_memory,
-    build_runtime_with_tooling, chat_service, chat_service_with_memory,
+    build_runtime_with_tooling, chat_service, chat_service_with_memory, chat_service_with_registry,
     create_default_memory_backend, create_memory_backend, in_memory_backend, parse_provider_id,
     session, streaming_turn, system_message, tool_message, turn, user_message,
 };
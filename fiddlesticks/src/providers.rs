@@ -3,13 +3,17 @@
 //! ```rust
 //! use std::time::Duration;
 //!
-//! use fiddlesticks::{ProviderBuildConfig, ProviderId};
+//! use fiddlesticks::{OpenAiTransportKind, ProviderBuildConfig, ProviderId, StreamTimeouts};
 //!
 //! let config = ProviderBuildConfig::new(ProviderId::OpenAi, "test-key")
-//!     .with_timeout(Duration::from_secs(30));
+//!     .with_timeout(Duration::from_secs(30))
+//!     .with_openai_transport(OpenAiTransportKind::ResponsesHttp)
+//!     .with_stream_timeouts(StreamTimeouts::new().with_idle(Duration::from_secs(15)));
 //!
 //! assert_eq!(config.provider_id, ProviderId::OpenAi);
 //! assert_eq!(config.timeout, Duration::from_secs(30));
+//! assert_eq!(config.openai_transport, OpenAiTransportKind::ResponsesHttp);
+//! assert_eq!(config.stream_timeouts.idle, Some(Duration::from_secs(15)));
 //! ```
 
 use std::sync::Arc;
@@ -17,13 +21,34 @@ use std::time::Duration;
 
 use reqwest::Client;
 
-use crate::{ModelProvider, ProviderError, ProviderId, SecretString, SecureCredentialManager};
+use crate::{
+    ModelInfo, ModelProvider, ProviderError, ProviderId, ProviderLayer, SecretString,
+    SecureCredentialManager, StreamTimeoutLayer, StreamTimeouts,
+};
+
+/// Wire protocol used by the OpenAI provider.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OpenAiTransportKind {
+    /// Responses API over a persistent WebSocket.
+    #[default]
+    ResponsesWebSocket,
+    /// Responses API over plain HTTP with SSE streaming, for environments that block WebSockets.
+    ResponsesHttp,
+    /// Legacy `/v1/chat/completions` endpoint.
+    ChatCompletions,
+}
 
 #[derive(Debug, Clone)]
 pub struct ProviderBuildConfig {
     pub provider_id: ProviderId,
     pub api_key: SecretString,
+    /// Bounds connecting and each read from the connection, not the whole request, so long
+    /// streams that keep producing data are never cut off.
     pub timeout: Duration,
+    /// Only used when `provider_id` is [`ProviderId::OpenAi`].
+    pub openai_transport: OpenAiTransportKind,
+    /// Per-stream deadlines; unlike `timeout`, these do not cut off long healthy streams.
+    pub stream_timeouts: StreamTimeouts,
 }
 
 impl ProviderBuildConfig {
@@ -32,6 +57,8 @@ impl ProviderBuildConfig {
             provider_id,
             api_key: SecretString::new(api_key),
             timeout: Duration::from_secs(90),
+            openai_transport: OpenAiTransportKind::default(),
+            stream_timeouts: StreamTimeouts::default(),
         }
     }
 
@@ -39,6 +66,16 @@ impl ProviderBuildConfig {
         self.timeout = timeout;
         self
     }
+
+    pub fn with_openai_transport(mut self, transport: OpenAiTransportKind) -> Self {
+        self.openai_transport = transport;
+        self
+    }
+
+    pub fn with_stream_timeouts(mut self, stream_timeouts: StreamTimeouts) -> Self {
+        self.stream_timeouts = stream_timeouts;
+        self
+    }
 }
 
 pub fn build_provider_from_api_key(
@@ -72,44 +109,59 @@ pub fn build_provider_with_config(
 
     let credentials = Arc::new(SecureCredentialManager::new());
     let http = Client::builder()
-        .timeout(config.timeout)
+        .connect_timeout(config.timeout)
+        .read_timeout(config.timeout)
         .build()
         .map_err(|err| ProviderError::transport(err.to_string()))?;
 
-    match config.provider_id {
-        ProviderId::OpenAi => build_openai_provider(credentials, api_key, http),
+    let provider = match config.provider_id {
+        ProviderId::OpenAi => {
+            build_openai_provider(credentials, api_key, http, config.openai_transport)
+        }
         ProviderId::Anthropic => build_anthropic_provider(credentials, api_key, http),
         ProviderId::OpenCodeZen => build_zen_provider(credentials, api_key, http),
         ProviderId::Ollama => build_ollama_provider(credentials, http),
+        ProviderId::Gemini => build_gemini_provider(credentials, api_key, http),
+    }?;
+
+    if config.stream_timeouts.is_enabled() {
+        return Ok(StreamTimeoutLayer::new(config.stream_timeouts).layer(provider));
     }
+    Ok(provider)
 }
 
+/// Lists the models visible to `api_key` by building the provider and calling
+/// [`ModelProvider::list_models`].
 pub async fn list_models_with_api_key(
     provider_id: ProviderId,
     api_key: impl Into<String>,
-) -> Result<Vec<String>, ProviderError> {
-    let api_key = api_key.into();
-    match provider_id {
-        ProviderId::OpenCodeZen => list_zen_models(api_key).await,
-        ProviderId::Ollama => list_ollama_models().await,
-        ProviderId::OpenAi | ProviderId::Anthropic => Err(ProviderError::invalid_request(
-            "model listing is currently supported for OpenCode Zen and Ollama only",
-        )),
-    }
+) -> Result<Vec<ModelInfo>, ProviderError> {
+    build_provider_from_api_key(provider_id, api_key)?
+        .list_models()
+        .await
 }
 
 #[cfg(feature = "provider-openai")]
 fn build_openai_provider(
     credentials: Arc<SecureCredentialManager>,
     api_key: String,
-    _http: Client,
+    http: Client,
+    transport: OpenAiTransportKind,
 ) -> Result<Arc<dyn ModelProvider>, ProviderError> {
+    use fprovider::adapters::openai::{
+        OpenAiHttpTransport, OpenAiProvider, OpenAiResponsesHttpTransport,
+        OpenAiResponsesWebSocketTransport, OpenAiTransport,
+    };
+
     credentials.set_openai_api_key(api_key)?;
-    let transport = Arc::new(fprovider::adapters::openai::OpenAiResponsesWebSocketTransport::new());
-    Ok(Arc::new(fprovider::adapters::openai::OpenAiProvider::new(
-        credentials,
-        transport,
-    )))
+    let transport: Arc<dyn OpenAiTransport> = match transport {
+        OpenAiTransportKind::ResponsesWebSocket => {
+            Arc::new(OpenAiResponsesWebSocketTransport::new())
+        }
+        OpenAiTransportKind::ResponsesHttp => Arc::new(OpenAiResponsesHttpTransport::new(http)),
+        OpenAiTransportKind::ChatCompletions => Arc::new(OpenAiHttpTransport::new(http)),
+    };
+    Ok(Arc::new(OpenAiProvider::new(credentials, transport)))
 }
 
 #[cfg(not(feature = "provider-openai"))]
@@ -117,6 +169,7 @@ fn build_openai_provider(
     _credentials: Arc<SecureCredentialManager>,
     _api_key: String,
     _http: Client,
+    _transport: OpenAiTransportKind,
 ) -> Result<Arc<dyn ModelProvider>, ProviderError> {
     Err(ProviderError::invalid_request(
         "provider-openai feature is not enabled on fiddlesticks",
@@ -196,26 +249,28 @@ fn build_ollama_provider(
     ))
 }
 
-#[cfg(feature = "provider-opencode-zen")]
-async fn list_zen_models(api_key: String) -> Result<Vec<String>, ProviderError> {
-    fprovider::adapters::opencode_zen::list_zen_models_with_api_key(api_key).await
-}
-
-#[cfg(not(feature = "provider-opencode-zen"))]
-async fn list_zen_models(_api_key: String) -> Result<Vec<String>, ProviderError> {
-    Err(ProviderError::invalid_request(
-        "provider-opencode-zen feature is not enabled on fiddlesticks",
-    ))
-}
-
-#[cfg(feature = "provider-ollama")]
-async fn list_ollama_models() -> Result<Vec<String>, ProviderError> {
-    fprovider::adapters::ollama::list_ollama_models().await
+#[cfg(feature = "provider-gemini")]
+fn build_gemini_provider(
+    credentials: Arc<SecureCredentialManager>,
+    api_key: String,
+    http: Client,
+) -> Result<Arc<dyn ModelProvider>, ProviderError> {
+    credentials.set_gemini_api_key(api_key)?;
+    let transport =
+        Arc::new(fprovider::adapters::gemini::GeminiProvider::default_http_transport(http));
+    Ok(Arc::new(fprovider::adapters::gemini::GeminiProvider::new(
+        credentials,
+        transport,
+    )))
 }
 
-#[cfg(not(feature = "provider-ollama"))]
-async fn list_ollama_models() -> Result<Vec<String>, ProviderError> {
+#[cfg(not(feature = "provider-gemini"))]
+fn build_gemini_provider(
+    _credentials: Arc<SecureCredentialManager>,
+    _api_key: String,
+    _http: Client,
+) -> Result<Arc<dyn ModelProvider>, ProviderError> {
     Err(ProviderError::invalid_request(
-        "provider-ollama feature is not enabled on fiddlesticks",
+        "provider-gemini feature is not enabled on fiddlesticks",
     ))
 }
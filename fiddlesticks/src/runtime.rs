@@ -16,7 +16,8 @@ use std::sync::Arc;
 
 use crate::{
     ChatService, Harness, HarnessError, InMemoryMemoryBackend, MemoryBackend,
-    MemoryConversationStore, ModelProvider, ToolRuntime, create_default_memory_backend,
+    MemoryConversationStore, ModelProvider, ProviderRegistry, ToolRuntime,
+    create_default_memory_backend,
 };
 
 #[derive(Clone)]
@@ -34,6 +35,11 @@ pub fn chat_service(provider: Arc<dyn ModelProvider>) -> ChatService {
     ChatService::builder(provider).build()
 }
 
+/// Dispatches each turn to the registered provider named by the session.
+pub fn chat_service_with_registry(registry: Arc<ProviderRegistry>) -> ChatService {
+    ChatService::builder_from_registry(registry).build()
+}
+
 pub fn chat_service_with_memory(
     provider: Arc<dyn ModelProvider>,
     memory: Arc<dyn MemoryBackend>,
@@ -119,6 +125,7 @@ mod tests {
                     output: vec![OutputItem::Message(Message::new(Role::Assistant, "done"))],
                     stop_reason: StopReason::EndTurn,
                     usage: TokenUsage::default(),
+                    response_id: None,
                 })
             })
         }
@@ -135,6 +142,7 @@ mod tests {
                     output: vec![OutputItem::Message(Message::new(Role::Assistant, "done"))],
                     stop_reason: StopReason::EndTurn,
                     usage: TokenUsage::default(),
+                    response_id: None,
                 };
                 let stream = VecEventStream::new(vec![Ok(StreamEvent::ResponseComplete(response))]);
                 Ok(Box::pin(stream) as crate::BoxedEventStream<'a>)
@@ -53,6 +53,7 @@ pub fn parse_provider_id(value: &str) -> Option<ProviderId> {
         "openai" => Some(ProviderId::OpenAi),
         "anthropic" | "claude" => Some(ProviderId::Anthropic),
         "ollama" | "local" => Some(ProviderId::Ollama),
+        "gemini" | "google" => Some(ProviderId::Gemini),
         _ => None,
     }
 }
@@ -71,6 +72,8 @@ mod tests {
         assert_eq!(parse_provider_id("claude"), Some(ProviderId::Anthropic));
         assert_eq!(parse_provider_id("ollama"), Some(ProviderId::Ollama));
         assert_eq!(parse_provider_id("local"), Some(ProviderId::Ollama));
+        assert_eq!(parse_provider_id("gemini"), Some(ProviderId::Gemini));
+        assert_eq!(parse_provider_id("Google"), Some(ProviderId::Gemini));
         assert_eq!(parse_provider_id("unknown"), None);
     }
 
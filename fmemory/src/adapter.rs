@@ -13,12 +13,15 @@
 
 use std::sync::Arc;
 
-use fchat::{ChatError, ChatErrorPhase, ConversationStore};
+use fchat::{
+    ChatError, ChatErrorPhase, ConversationStore, ConversationTree, MessageId, MessageMetadata,
+    SessionMetadata, SessionPage, SessionPageRequest, StoredMessage,
+};
 use fcommon::{BoxFuture, SessionId};
 use fprovider::Message;
 
 use crate::backend::MemoryBackend;
-use crate::error::MemoryError;
+use crate::error::{MemoryError, MemoryErrorKind};
 
 #[derive(Clone)]
 pub struct MemoryConversationStore {
@@ -41,10 +44,12 @@ impl ConversationStore for MemoryConversationStore {
         session_id: &'a SessionId,
     ) -> BoxFuture<'a, Result<Vec<Message>, ChatError>> {
         Box::pin(async move {
-            self.backend
+            let stored = self
+                .backend
                 .load_transcript_messages(session_id)
                 .await
-                .map_err(memory_error_to_chat_error)
+                .map_err(memory_error_to_chat_error)?;
+            Ok(stored.into_iter().map(|stored| stored.message).collect())
         })
     }
 
@@ -60,8 +65,150 @@ impl ConversationStore for MemoryConversationStore {
                 .map_err(memory_error_to_chat_error)
         })
     }
+
+    fn append_messages_with_metadata<'a>(
+        &'a self,
+        session_id: &'a SessionId,
+        messages: Vec<(Message, MessageMetadata)>,
+    ) -> BoxFuture<'a, Result<(), ChatError>> {
+        Box::pin(async move {
+            self.backend
+                .append_transcript_messages_with_metadata(session_id, messages)
+                .await
+                .map_err(memory_error_to_chat_error)
+        })
+    }
+
+    fn session_version<'a>(
+        &'a self,
+        session_id: &'a SessionId,
+    ) -> BoxFuture<'a, Result<Option<u64>, ChatError>> {
+        Box::pin(async move {
+            self.backend
+                .transcript_version(session_id)
+                .await
+                .map_err(memory_error_to_chat_error)
+        })
+    }
+
+    fn append_messages_if_version<'a>(
+        &'a self,
+        session_id: &'a SessionId,
+        expected_version: u64,
+        messages: Vec<(Message, MessageMetadata)>,
+    ) -> BoxFuture<'a, Result<(), ChatError>> {
+        Box::pin(async move {
+            self.backend
+                .append_transcript_messages_if_version(session_id, expected_version, messages)
+                .await
+                .map_err(memory_error_to_chat_error)
+        })
+    }
+
+    fn load_stored_messages<'a>(
+        &'a self,
+        session_id: &'a SessionId,
+    ) -> BoxFuture<'a, Result<Vec<StoredMessage>, ChatError>> {
+        Box::pin(async move {
+            self.backend
+                .load_transcript_messages(session_id)
+                .await
+                .map_err(memory_error_to_chat_error)
+        })
+    }
+
+    fn load_tree<'a>(
+        &'a self,
+        session_id: &'a SessionId,
+    ) -> BoxFuture<'a, Result<ConversationTree, ChatError>> {
+        Box::pin(async move {
+            self.backend
+                .load_transcript_tree(session_id)
+                .await
+                .map_err(memory_error_to_chat_error)
+        })
+    }
+
+    fn set_active_leaf<'a>(
+        &'a self,
+        session_id: &'a SessionId,
+        leaf: Option<MessageId>,
+    ) -> BoxFuture<'a, Result<(), ChatError>> {
+        Box::pin(async move {
+            self.backend
+                .set_transcript_active_leaf(session_id, leaf)
+                .await
+                .map_err(memory_error_to_chat_error)
+        })
+    }
+
+    fn list_sessions<'a>(
+        &'a self,
+        page: SessionPageRequest,
+    ) -> BoxFuture<'a, Result<SessionPage, ChatError>> {
+        Box::pin(async move {
+            self.backend
+                .list_sessions(page)
+                .await
+                .map_err(memory_error_to_chat_error)
+        })
+    }
+
+    fn session_metadata<'a>(
+        &'a self,
+        session_id: &'a SessionId,
+    ) -> BoxFuture<'a, Result<Option<SessionMetadata>, ChatError>> {
+        Box::pin(async move {
+            self.backend
+                .session_metadata(session_id)
+                .await
+                .map_err(memory_error_to_chat_error)
+        })
+    }
+
+    fn rename_session<'a>(
+        &'a self,
+        session_id: &'a SessionId,
+        title: String,
+    ) -> BoxFuture<'a, Result<(), ChatError>> {
+        Box::pin(async move {
+            self.backend
+                .rename_session(session_id, title)
+                .await
+                .map_err(memory_error_to_chat_error)
+        })
+    }
+
+    fn delete_session<'a>(
+        &'a self,
+        session_id: &'a SessionId,
+    ) -> BoxFuture<'a, Result<(), ChatError>> {
+        Box::pin(async move {
+            self.backend
+                .delete_session(session_id)
+                .await
+                .map_err(memory_error_to_chat_error)
+        })
+    }
+
+    fn truncate_session<'a>(
+        &'a self,
+        session_id: &'a SessionId,
+        keep_messages: usize,
+    ) -> BoxFuture<'a, Result<(), ChatError>> {
+        Box::pin(async move {
+            self.backend
+                .truncate_transcript(session_id, keep_messages)
+                .await
+                .map_err(memory_error_to_chat_error)
+        })
+    }
 }
 
 fn memory_error_to_chat_error(error: MemoryError) -> ChatError {
-    ChatError::store(error.message).with_phase(ChatErrorPhase::Storage)
+    let chat_error = match error.kind {
+        MemoryErrorKind::Conflict => ChatError::conflict(error.message),
+        _ => ChatError::store(error.message),
+    };
+    chat_error.with_phase(ChatErrorPhase::Storage)
 }
@@ -13,11 +13,20 @@
 use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
 
+use fchat::{
+    ConversationTree, MessageId, MessageMetadata, SessionMetadata, SessionPage, SessionPageRequest,
+    StoredMessage,
+};
 use fcommon::{BoxFuture, SessionId};
 use fprovider::Message;
 
 use crate::backends::sqlite::default_sqlite_path;
+use crate::backends::transcript::{
+    ConversationRecord, PositionedTranscript, TranscriptEntry, active_stored_messages, build_tree,
+    resolve_leaf, take_active,
+};
 use crate::error::MemoryError;
 use crate::types::{BootstrapState, FeatureRecord, ProgressEntry, RunCheckpoint, SessionManifest};
 
@@ -76,16 +85,140 @@ pub trait MemoryBackend: Send + Sync {
         checkpoint: RunCheckpoint,
     ) -> BoxFuture<'a, Result<(), MemoryError>>;
 
+    /// Messages on the active transcript branch, from the root to the active leaf, with their
+    /// ids and metadata.
     fn load_transcript_messages<'a>(
         &'a self,
         session_id: &'a SessionId,
-    ) -> BoxFuture<'a, Result<Vec<Message>, MemoryError>>;
+    ) -> BoxFuture<'a, Result<Vec<StoredMessage>, MemoryError>>;
 
+    /// Appends below the active leaf and makes the last appended message the new leaf.
     fn append_transcript_messages<'a>(
         &'a self,
         session_id: &'a SessionId,
         messages: Vec<Message>,
     ) -> BoxFuture<'a, Result<(), MemoryError>>;
+
+    /// Appends like `append_transcript_messages`, storing each message's metadata. Backends
+    /// that keep no metadata drop it.
+    fn append_transcript_messages_with_metadata<'a>(
+        &'a self,
+        session_id: &'a SessionId,
+        messages: Vec<(Message, MessageMetadata)>,
+    ) -> BoxFuture<'a, Result<(), MemoryError>> {
+        self.append_transcript_messages(
+            session_id,
+            messages.into_iter().map(|(message, _)| message).collect(),
+        )
+    }
+
+    /// Version of the session's transcript: 0 for a new session, increased by every append,
+    /// active leaf change and truncation, and reset when the session is deleted. `None` when
+    /// the backend cannot append conditionally.
+    fn transcript_version<'a>(
+        &'a self,
+        _session_id: &'a SessionId,
+    ) -> BoxFuture<'a, Result<Option<u64>, MemoryError>> {
+        Box::pin(async { Ok(None) })
+    }
+
+    /// Appends like `append_transcript_messages_with_metadata` only while the transcript is
+    /// still at `expected_version`, checked in the same transaction as the write; otherwise
+    /// fails with `MemoryErrorKind::Conflict` and stores nothing.
+    fn append_transcript_messages_if_version<'a>(
+        &'a self,
+        _session_id: &'a SessionId,
+        _expected_version: u64,
+        _messages: Vec<(Message, MessageMetadata)>,
+    ) -> BoxFuture<'a, Result<(), MemoryError>> {
+        Box::pin(async {
+            Err(MemoryError::invalid_request(
+                "memory backend does not support conditional appends",
+            ))
+        })
+    }
+
+    fn load_transcript_tree<'a>(
+        &'a self,
+        _session_id: &'a SessionId,
+    ) -> BoxFuture<'a, Result<ConversationTree, MemoryError>> {
+        Box::pin(async {
+            Err(MemoryError::invalid_request(
+                "memory backend does not support transcript branching",
+            ))
+        })
+    }
+
+    /// Moves the active leaf; `None` makes the next append start a new root branch.
+    fn set_transcript_active_leaf<'a>(
+        &'a self,
+        _session_id: &'a SessionId,
+        _leaf: Option<MessageId>,
+    ) -> BoxFuture<'a, Result<(), MemoryError>> {
+        Box::pin(async {
+            Err(MemoryError::invalid_request(
+                "memory backend does not support transcript branching",
+            ))
+        })
+    }
+
+    /// Sessions that hold a transcript or a title, ordered by most recent update.
+    fn list_sessions<'a>(
+        &'a self,
+        _page: SessionPageRequest,
+    ) -> BoxFuture<'a, Result<SessionPage, MemoryError>> {
+        Box::pin(async { Err(unsupported_session_management()) })
+    }
+
+    fn session_metadata<'a>(
+        &'a self,
+        _session_id: &'a SessionId,
+    ) -> BoxFuture<'a, Result<Option<SessionMetadata>, MemoryError>> {
+        Box::pin(async { Err(unsupported_session_management()) })
+    }
+
+    fn rename_session<'a>(
+        &'a self,
+        _session_id: &'a SessionId,
+        _title: String,
+    ) -> BoxFuture<'a, Result<(), MemoryError>> {
+        Box::pin(async { Err(unsupported_session_management()) })
+    }
+
+    /// Removes every record of the session, including harness state.
+    fn delete_session<'a>(
+        &'a self,
+        _session_id: &'a SessionId,
+    ) -> BoxFuture<'a, Result<(), MemoryError>> {
+        Box::pin(async { Err(unsupported_session_management()) })
+    }
+
+    /// Keeps the first `keep_messages` messages of the active branch and removes the rest.
+    fn truncate_transcript<'a>(
+        &'a self,
+        _session_id: &'a SessionId,
+        _keep_messages: usize,
+    ) -> BoxFuture<'a, Result<(), MemoryError>> {
+        Box::pin(async { Err(unsupported_session_management()) })
+    }
+}
+
+/// Fails with a conflict when another writer changed the transcript since `expected` was read.
+pub(crate) fn check_transcript_version(
+    session_id: &SessionId,
+    expected: u64,
+    actual: u64,
+) -> Result<(), MemoryError> {
+    if expected == actual {
+        return Ok(());
+    }
+    Err(MemoryError::conflict(format!(
+        "transcript of session '{session_id}' changed: expected version {expected}, found {actual}"
+    )))
+}
+
+fn unsupported_session_management() -> MemoryError {
+    MemoryError::invalid_request("memory backend does not support session management")
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -156,7 +289,29 @@ struct SessionState {
     feature_list: Vec<FeatureRecord>,
     progress: Vec<ProgressEntry>,
     checkpoints: Vec<RunCheckpoint>,
-    transcript: Vec<Message>,
+    transcript: PositionedTranscript,
+    transcript_leaf: Option<usize>,
+    transcript_version: u64,
+    conversation: Option<ConversationRecord>,
+}
+
+impl SessionState {
+    fn transcript_parents(&self) -> Vec<Option<usize>> {
+        self.transcript.iter().map(|entry| entry.parent).collect()
+    }
+
+    fn push_transcript(&mut self, messages: Vec<(Message, MessageMetadata)>) {
+        ConversationRecord::touch(&mut self.conversation);
+        self.transcript_version += 1;
+        for (message, metadata) in messages {
+            let parent = self.transcript_leaf.replace(self.transcript.len());
+            self.transcript.push(TranscriptEntry {
+                parent,
+                message,
+                metadata,
+            });
+        }
+    }
 }
 
 impl InMemoryMemoryBackend {
@@ -335,7 +490,7 @@ impl MemoryBackend for InMemoryMemoryBackend {
     fn load_transcript_messages<'a>(
         &'a self,
         session_id: &'a SessionId,
-    ) -> BoxFuture<'a, Result<Vec<Message>, MemoryError>> {
+    ) -> BoxFuture<'a, Result<Vec<StoredMessage>, MemoryError>> {
         Box::pin(async move {
             let sessions = self
                 .sessions
@@ -344,7 +499,9 @@ impl MemoryBackend for InMemoryMemoryBackend {
 
             Ok(sessions
                 .get(session_id)
-                .map(|state| state.transcript.clone())
+                .map(|state| {
+                    active_stored_messages(state.transcript.clone(), state.transcript_leaf)
+                })
                 .unwrap_or_default())
         })
     }
@@ -353,6 +510,21 @@ impl MemoryBackend for InMemoryMemoryBackend {
         &'a self,
         session_id: &'a SessionId,
         messages: Vec<Message>,
+    ) -> BoxFuture<'a, Result<(), MemoryError>> {
+        let metadata = MessageMetadata::new(SystemTime::now());
+        self.append_transcript_messages_with_metadata(
+            session_id,
+            messages
+                .into_iter()
+                .map(|message| (message, metadata.clone()))
+                .collect(),
+        )
+    }
+
+    fn append_transcript_messages_with_metadata<'a>(
+        &'a self,
+        session_id: &'a SessionId,
+        messages: Vec<(Message, MessageMetadata)>,
     ) -> BoxFuture<'a, Result<(), MemoryError>> {
         Box::pin(async move {
             let mut sessions = self
@@ -363,9 +535,191 @@ impl MemoryBackend for InMemoryMemoryBackend {
             sessions
                 .entry(session_id.clone())
                 .or_default()
-                .transcript
-                .extend(messages);
+                .push_transcript(messages);
+            Ok(())
+        })
+    }
+
+    fn transcript_version<'a>(
+        &'a self,
+        session_id: &'a SessionId,
+    ) -> BoxFuture<'a, Result<Option<u64>, MemoryError>> {
+        Box::pin(async move {
+            let sessions = self
+                .sessions
+                .lock()
+                .map_err(|_| MemoryError::storage("memory backend lock poisoned"))?;
+
+            Ok(Some(
+                sessions
+                    .get(session_id)
+                    .map_or(0, |state| state.transcript_version),
+            ))
+        })
+    }
+
+    fn append_transcript_messages_if_version<'a>(
+        &'a self,
+        session_id: &'a SessionId,
+        expected_version: u64,
+        messages: Vec<(Message, MessageMetadata)>,
+    ) -> BoxFuture<'a, Result<(), MemoryError>> {
+        Box::pin(async move {
+            let mut sessions = self
+                .sessions
+                .lock()
+                .map_err(|_| MemoryError::storage("memory backend lock poisoned"))?;
+
+            let version = sessions
+                .get(session_id)
+                .map_or(0, |state| state.transcript_version);
+            check_transcript_version(session_id, expected_version, version)?;
+            sessions
+                .entry(session_id.clone())
+                .or_default()
+                .push_transcript(messages);
+            Ok(())
+        })
+    }
 
+    fn load_transcript_tree<'a>(
+        &'a self,
+        session_id: &'a SessionId,
+    ) -> BoxFuture<'a, Result<ConversationTree, MemoryError>> {
+        Box::pin(async move {
+            let sessions = self
+                .sessions
+                .lock()
+                .map_err(|_| MemoryError::storage("memory backend lock poisoned"))?;
+
+            Ok(sessions
+                .get(session_id)
+                .map(|state| build_tree(state.transcript.clone(), state.transcript_leaf))
+                .unwrap_or_default())
+        })
+    }
+
+    fn set_transcript_active_leaf<'a>(
+        &'a self,
+        session_id: &'a SessionId,
+        leaf: Option<MessageId>,
+    ) -> BoxFuture<'a, Result<(), MemoryError>> {
+        Box::pin(async move {
+            let mut sessions = self
+                .sessions
+                .lock()
+                .map_err(|_| MemoryError::storage("memory backend lock poisoned"))?;
+
+            let state = sessions.entry(session_id.clone()).or_default();
+            state.transcript_leaf = resolve_leaf(leaf.as_ref(), state.transcript.len())?;
+            state.transcript_version += 1;
+            Ok(())
+        })
+    }
+
+    fn list_sessions<'a>(
+        &'a self,
+        page: SessionPageRequest,
+    ) -> BoxFuture<'a, Result<SessionPage, MemoryError>> {
+        Box::pin(async move {
+            let sessions = self
+                .sessions
+                .lock()
+                .map_err(|_| MemoryError::storage("memory backend lock poisoned"))?;
+
+            Ok(page.paginate(
+                sessions
+                    .iter()
+                    .filter_map(|(session_id, state)| {
+                        state
+                            .conversation
+                            .as_ref()
+                            .map(|record| record.metadata(session_id, state.transcript.len()))
+                    })
+                    .collect(),
+            ))
+        })
+    }
+
+    fn session_metadata<'a>(
+        &'a self,
+        session_id: &'a SessionId,
+    ) -> BoxFuture<'a, Result<Option<SessionMetadata>, MemoryError>> {
+        Box::pin(async move {
+            let sessions = self
+                .sessions
+                .lock()
+                .map_err(|_| MemoryError::storage("memory backend lock poisoned"))?;
+
+            Ok(sessions.get(session_id).and_then(|state| {
+                state
+                    .conversation
+                    .as_ref()
+                    .map(|record| record.metadata(session_id, state.transcript.len()))
+            }))
+        })
+    }
+
+    fn rename_session<'a>(
+        &'a self,
+        session_id: &'a SessionId,
+        title: String,
+    ) -> BoxFuture<'a, Result<(), MemoryError>> {
+        Box::pin(async move {
+            let mut sessions = self
+                .sessions
+                .lock()
+                .map_err(|_| MemoryError::storage("memory backend lock poisoned"))?;
+
+            let state = sessions.entry(session_id.clone()).or_default();
+            ConversationRecord::touch(&mut state.conversation).title = Some(title);
+            Ok(())
+        })
+    }
+
+    fn delete_session<'a>(
+        &'a self,
+        session_id: &'a SessionId,
+    ) -> BoxFuture<'a, Result<(), MemoryError>> {
+        Box::pin(async move {
+            let mut sessions = self
+                .sessions
+                .lock()
+                .map_err(|_| MemoryError::storage("memory backend lock poisoned"))?;
+
+            sessions.remove(session_id);
+            Ok(())
+        })
+    }
+
+    fn truncate_transcript<'a>(
+        &'a self,
+        session_id: &'a SessionId,
+        keep_messages: usize,
+    ) -> BoxFuture<'a, Result<(), MemoryError>> {
+        Box::pin(async move {
+            let mut sessions = self
+                .sessions
+                .lock()
+                .map_err(|_| MemoryError::storage("memory backend lock poisoned"))?;
+
+            let Some(state) = sessions.get_mut(session_id) else {
+                return Ok(());
+            };
+            let parents = state.transcript_parents();
+            let kept = take_active(
+                std::mem::take(&mut state.transcript),
+                &parents,
+                state.transcript_leaf,
+                keep_messages,
+            );
+            state.transcript_leaf = None;
+            for mut entry in kept {
+                entry.parent = state.transcript_leaf.replace(state.transcript.len());
+                state.transcript.push(entry);
+            }
+            state.transcript_version += 1;
+            ConversationRecord::touch(&mut state.conversation);
             Ok(())
         })
     }
@@ -4,11 +4,19 @@ use std::path::{Path, PathBuf};
 use std::sync::Mutex;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
+use fchat::{
+    ConversationTree, MessageId, MessageMetadata, SessionMetadata, SessionPage, SessionPageRequest,
+    StoredMessage,
+};
 use fcommon::SessionId;
-use fprovider::{Message, Role};
+use fprovider::{Message, Role, TokenUsage};
 use serde::{Deserialize, Serialize};
 
-use crate::backend::MemoryBackend;
+use crate::backend::{MemoryBackend, check_transcript_version};
+use crate::backends::transcript::{
+    ConversationRecord, PositionedTranscript, TranscriptEntry, active_stored_messages, build_tree,
+    linear_parents, parse_provider, resolve_leaf, take_active,
+};
 use crate::error::MemoryError;
 use crate::types::{
     BootstrapState, FeatureRecord, InitCommand, InitPlan, InitShell, InitShellScript, InitStep,
@@ -48,9 +56,18 @@ impl FilesystemMemoryBackend {
         let bytes = fs::read(&path).map_err(|error| {
             MemoryError::storage(format!("failed to read session state file: {error}"))
         })?;
-        let state = serde_json::from_slice::<PersistedState>(&bytes).map_err(|error| {
+        let mut state = serde_json::from_slice::<PersistedState>(&bytes).map_err(|error| {
             MemoryError::storage(format!("failed to deserialize session state: {error}"))
         })?;
+        state.upgrade_transcript();
+        if state.conversation.is_none() && !state.transcript.is_empty() {
+            let modified = fs::metadata(&path)
+                .and_then(|metadata| metadata.modified())
+                .map_err(|error| {
+                    MemoryError::storage(format!("failed to read session state metadata: {error}"))
+                })?;
+            state.backfill_conversation(modified)?;
+        }
         Ok(Some(state))
     }
 
@@ -235,7 +252,7 @@ impl MemoryBackend for FilesystemMemoryBackend {
     fn load_transcript_messages<'a>(
         &'a self,
         session_id: &'a SessionId,
-    ) -> fcommon::BoxFuture<'a, Result<Vec<Message>, MemoryError>> {
+    ) -> fcommon::BoxFuture<'a, Result<Vec<StoredMessage>, MemoryError>> {
         Box::pin(async move {
             let _guard = self
                 .lock
@@ -244,11 +261,8 @@ impl MemoryBackend for FilesystemMemoryBackend {
             let Some(state) = self.load_state(session_id)? else {
                 return Ok(Vec::new());
             };
-            state
-                .transcript
-                .into_iter()
-                .map(PersistedMessage::into_message)
-                .collect()
+            let (entries, leaf) = state.into_positioned_transcript()?;
+            Ok(active_stored_messages(entries, leaf))
         })
     }
 
@@ -256,6 +270,21 @@ impl MemoryBackend for FilesystemMemoryBackend {
         &'a self,
         session_id: &'a SessionId,
         messages: Vec<Message>,
+    ) -> fcommon::BoxFuture<'a, Result<(), MemoryError>> {
+        let metadata = MessageMetadata::new(SystemTime::now());
+        self.append_transcript_messages_with_metadata(
+            session_id,
+            messages
+                .into_iter()
+                .map(|message| (message, metadata.clone()))
+                .collect(),
+        )
+    }
+
+    fn append_transcript_messages_with_metadata<'a>(
+        &'a self,
+        session_id: &'a SessionId,
+        messages: Vec<(Message, MessageMetadata)>,
     ) -> fcommon::BoxFuture<'a, Result<(), MemoryError>> {
         Box::pin(async move {
             let _guard = self
@@ -263,11 +292,186 @@ impl MemoryBackend for FilesystemMemoryBackend {
                 .lock()
                 .map_err(|_| MemoryError::storage("filesystem backend lock poisoned"))?;
             let mut state = self.load_state(session_id)?.unwrap_or_default();
-            let persisted: Vec<PersistedMessage> = messages
-                .into_iter()
-                .map(PersistedMessage::from_message)
-                .collect();
-            state.transcript.extend(persisted);
+            state.append_transcript(messages)?;
+            self.save_state(session_id, &state)
+        })
+    }
+
+    fn transcript_version<'a>(
+        &'a self,
+        session_id: &'a SessionId,
+    ) -> fcommon::BoxFuture<'a, Result<Option<u64>, MemoryError>> {
+        Box::pin(async move {
+            let _guard = self
+                .lock
+                .lock()
+                .map_err(|_| MemoryError::storage("filesystem backend lock poisoned"))?;
+            Ok(Some(
+                self.load_state(session_id)?
+                    .map_or(0, |state| state.transcript_version),
+            ))
+        })
+    }
+
+    fn append_transcript_messages_if_version<'a>(
+        &'a self,
+        session_id: &'a SessionId,
+        expected_version: u64,
+        messages: Vec<(Message, MessageMetadata)>,
+    ) -> fcommon::BoxFuture<'a, Result<(), MemoryError>> {
+        Box::pin(async move {
+            let _guard = self
+                .lock
+                .lock()
+                .map_err(|_| MemoryError::storage("filesystem backend lock poisoned"))?;
+            let mut state = self.load_state(session_id)?.unwrap_or_default();
+            check_transcript_version(session_id, expected_version, state.transcript_version)?;
+            state.append_transcript(messages)?;
+            self.save_state(session_id, &state)
+        })
+    }
+
+    fn load_transcript_tree<'a>(
+        &'a self,
+        session_id: &'a SessionId,
+    ) -> fcommon::BoxFuture<'a, Result<ConversationTree, MemoryError>> {
+        Box::pin(async move {
+            let _guard = self
+                .lock
+                .lock()
+                .map_err(|_| MemoryError::storage("filesystem backend lock poisoned"))?;
+            let Some(state) = self.load_state(session_id)? else {
+                return Ok(ConversationTree::default());
+            };
+            state.into_transcript_tree()
+        })
+    }
+
+    fn set_transcript_active_leaf<'a>(
+        &'a self,
+        session_id: &'a SessionId,
+        leaf: Option<MessageId>,
+    ) -> fcommon::BoxFuture<'a, Result<(), MemoryError>> {
+        Box::pin(async move {
+            let _guard = self
+                .lock
+                .lock()
+                .map_err(|_| MemoryError::storage("filesystem backend lock poisoned"))?;
+            let mut state = self.load_state(session_id)?.unwrap_or_default();
+            state.upgrade_transcript();
+            state.transcript_leaf = resolve_leaf(leaf.as_ref(), state.transcript.len())?;
+            state.transcript_version += 1;
+            self.save_state(session_id, &state)
+        })
+    }
+
+    fn list_sessions<'a>(
+        &'a self,
+        page: SessionPageRequest,
+    ) -> fcommon::BoxFuture<'a, Result<SessionPage, MemoryError>> {
+        Box::pin(async move {
+            let _guard = self
+                .lock
+                .lock()
+                .map_err(|_| MemoryError::storage("filesystem backend lock poisoned"))?;
+            let entries = fs::read_dir(self.root.join("sessions")).map_err(|error| {
+                MemoryError::storage(format!("failed to list session state files: {error}"))
+            })?;
+            let mut sessions = Vec::new();
+            for entry in entries {
+                let path = entry
+                    .map_err(|error| {
+                        MemoryError::storage(format!("failed to read session state entry: {error}"))
+                    })?
+                    .path();
+                let Some(session_id) = path
+                    .file_stem()
+                    .and_then(|stem| stem.to_str())
+                    .filter(|_| {
+                        path.extension()
+                            .is_some_and(|extension| extension == "json")
+                    })
+                    .and_then(hex_decode)
+                    .map(SessionId::from)
+                else {
+                    continue;
+                };
+                if let Some(metadata) = self
+                    .load_state(&session_id)?
+                    .and_then(|state| state.session_metadata(&session_id).transpose())
+                {
+                    sessions.push(metadata?);
+                }
+            }
+            Ok(page.paginate(sessions))
+        })
+    }
+
+    fn session_metadata<'a>(
+        &'a self,
+        session_id: &'a SessionId,
+    ) -> fcommon::BoxFuture<'a, Result<Option<SessionMetadata>, MemoryError>> {
+        Box::pin(async move {
+            let _guard = self
+                .lock
+                .lock()
+                .map_err(|_| MemoryError::storage("filesystem backend lock poisoned"))?;
+            match self.load_state(session_id)? {
+                Some(state) => state.session_metadata(session_id),
+                None => Ok(None),
+            }
+        })
+    }
+
+    fn rename_session<'a>(
+        &'a self,
+        session_id: &'a SessionId,
+        title: String,
+    ) -> fcommon::BoxFuture<'a, Result<(), MemoryError>> {
+        Box::pin(async move {
+            let _guard = self
+                .lock
+                .lock()
+                .map_err(|_| MemoryError::storage("filesystem backend lock poisoned"))?;
+            let mut state = self.load_state(session_id)?.unwrap_or_default();
+            state.touch_conversation(Some(title))?;
+            self.save_state(session_id, &state)
+        })
+    }
+
+    fn delete_session<'a>(
+        &'a self,
+        session_id: &'a SessionId,
+    ) -> fcommon::BoxFuture<'a, Result<(), MemoryError>> {
+        Box::pin(async move {
+            let _guard = self
+                .lock
+                .lock()
+                .map_err(|_| MemoryError::storage("filesystem backend lock poisoned"))?;
+            let path = self.session_path(session_id);
+            if path.exists() {
+                fs::remove_file(&path).map_err(|error| {
+                    MemoryError::storage(format!("failed to delete session state file: {error}"))
+                })?;
+            }
+            Ok(())
+        })
+    }
+
+    fn truncate_transcript<'a>(
+        &'a self,
+        session_id: &'a SessionId,
+        keep_messages: usize,
+    ) -> fcommon::BoxFuture<'a, Result<(), MemoryError>> {
+        Box::pin(async move {
+            let _guard = self
+                .lock
+                .lock()
+                .map_err(|_| MemoryError::storage("filesystem backend lock poisoned"))?;
+            let Some(mut state) = self.load_state(session_id)? else {
+                return Ok(());
+            };
+            state.truncate_transcript(keep_messages)?;
             self.save_state(session_id, &state)
         })
     }
@@ -280,9 +484,124 @@ struct PersistedState {
     recent_progress: Vec<PersistedProgressEntry>,
     checkpoints: Vec<PersistedRunCheckpoint>,
     transcript: Vec<PersistedMessage>,
+    #[serde(default)]
+    transcript_leaf: Option<usize>,
+    /// Unset in state written before branching, whose messages carry no parent links.
+    #[serde(default)]
+    transcript_branching: bool,
+    /// Increased by every transcript change; absent in state written before it was tracked.
+    #[serde(default)]
+    transcript_version: u64,
+    #[serde(default)]
+    conversation: Option<PersistedConversation>,
 }
 
 impl PersistedState {
+    fn upgrade_transcript(&mut self) {
+        if self.transcript_branching {
+            return;
+        }
+        let parents = linear_parents(self.transcript.len());
+        for (message, parent) in self.transcript.iter_mut().zip(parents) {
+            message.parent = parent;
+        }
+        self.transcript_leaf = self.transcript.len().checked_sub(1);
+        self.transcript_branching = true;
+    }
+
+    fn backfill_conversation(&mut self, timestamp: SystemTime) -> Result<(), MemoryError> {
+        self.conversation = Some(PersistedConversation::from_record(ConversationRecord {
+            title: None,
+            created_at: timestamp,
+            updated_at: timestamp,
+        })?);
+        Ok(())
+    }
+
+    /// Marks the session updated now, replacing the title when one is given.
+    fn touch_conversation(&mut self, title: Option<String>) -> Result<(), MemoryError> {
+        let mut record = self
+            .conversation
+            .take()
+            .map(PersistedConversation::into_record)
+            .transpose()?;
+        let touched = ConversationRecord::touch(&mut record);
+        if title.is_some() {
+            touched.title = title;
+        }
+        self.conversation = record.map(PersistedConversation::from_record).transpose()?;
+        Ok(())
+    }
+
+    fn session_metadata(
+        &self,
+        session_id: &SessionId,
+    ) -> Result<Option<SessionMetadata>, MemoryError> {
+        self.conversation
+            .clone()
+            .map(|conversation| {
+                Ok(conversation
+                    .into_record()?
+                    .metadata(session_id, self.transcript.len()))
+            })
+            .transpose()
+    }
+
+    fn append_transcript(
+        &mut self,
+        messages: Vec<(Message, MessageMetadata)>,
+    ) -> Result<(), MemoryError> {
+        self.upgrade_transcript();
+        for (message, metadata) in messages {
+            let mut persisted = PersistedMessage::from_entry(message, &metadata)?;
+            persisted.parent = self.transcript_leaf.replace(self.transcript.len());
+            self.transcript.push(persisted);
+        }
+        self.transcript_version += 1;
+        self.touch_conversation(None)
+    }
+
+    fn truncate_transcript(&mut self, keep_messages: usize) -> Result<(), MemoryError> {
+        self.upgrade_transcript();
+        let parents = self.transcript_parents();
+        let kept = take_active(
+            std::mem::take(&mut self.transcript),
+            &parents,
+            self.transcript_leaf,
+            keep_messages,
+        );
+        self.transcript_leaf = None;
+        for mut message in kept {
+            message.parent = self.transcript_leaf.replace(self.transcript.len());
+            self.transcript.push(message);
+        }
+        self.transcript_version += 1;
+        self.touch_conversation(None)
+    }
+
+    fn transcript_parents(&self) -> Vec<Option<usize>> {
+        self.transcript
+            .iter()
+            .map(|message| message.parent)
+            .collect()
+    }
+
+    fn into_positioned_transcript(
+        self,
+    ) -> Result<(PositionedTranscript, Option<usize>), MemoryError> {
+        let entries = self
+            .transcript
+            .into_iter()
+            .map(PersistedMessage::into_entry)
+            .collect::<Result<Vec<_>, MemoryError>>()?;
+        Ok((entries, self.transcript_leaf))
+    }
+
+    fn into_transcript_tree(self) -> Result<ConversationTree, MemoryError> {
+        let (entries, leaf) = self.into_positioned_transcript()?;
+        Ok(build_tree(entries, leaf))
+    }
+
     fn into_bootstrap_state(self, session_id: &SessionId) -> Result<BootstrapState, MemoryError> {
         let manifest = self
             .manifest
@@ -473,6 +792,37 @@ impl PersistedProgressEntry {
     }
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct PersistedConversation {
+    title: Option<String>,
+    created_at_secs: i64,
+    created_at_nanos: i64,
+    updated_at_secs: i64,
+    updated_at_nanos: i64,
+}
+
+impl PersistedConversation {
+    fn from_record(record: ConversationRecord) -> Result<Self, MemoryError> {
+        let (created_at_secs, created_at_nanos) = encode_system_time(record.created_at)?;
+        let (updated_at_secs, updated_at_nanos) = encode_system_time(record.updated_at)?;
+        Ok(Self {
+            title: record.title,
+            created_at_secs,
+            created_at_nanos,
+            updated_at_secs,
+            updated_at_nanos,
+        })
+    }
+
+    fn into_record(self) -> Result<ConversationRecord, MemoryError> {
+        Ok(ConversationRecord {
+            title: self.title,
+            created_at: decode_system_time(self.created_at_secs, self.created_at_nanos)?,
+            updated_at: decode_system_time(self.updated_at_secs, self.updated_at_nanos)?,
+        })
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct PersistedRunCheckpoint {
     run_id: String,
@@ -531,24 +881,84 @@ impl PersistedRunCheckpoint {
 struct PersistedMessage {
     role: String,
     content: String,
+    #[serde(default)]
+    parent: Option<usize>,
+    #[serde(default)]
+    created_at_secs: Option<i64>,
+    #[serde(default)]
+    created_at_nanos: Option<i64>,
+    #[serde(default)]
+    provider: Option<String>,
+    #[serde(default)]
+    model: Option<String>,
+    #[serde(default)]
+    usage: Option<PersistedTokenUsage>,
 }
 
 impl PersistedMessage {
-    fn from_message(message: Message) -> Self {
-        Self {
+    fn from_entry(message: Message, metadata: &MessageMetadata) -> Result<Self, MemoryError> {
+        let created_at = metadata.created_at.map(encode_system_time).transpose()?;
+        Ok(Self {
             role: role_to_string(message.role),
             content: message.content,
-        }
+            parent: None,
+            created_at_secs: created_at.map(|(secs, _)| secs),
+            created_at_nanos: created_at.map(|(_, nanos)| nanos),
+            provider: metadata.provider.map(|provider| provider.to_string()),
+            model: metadata.model.clone(),
+            usage: metadata.usage.map(PersistedTokenUsage::from_usage),
+        })
     }
 
-    fn into_message(self) -> Result<Message, MemoryError> {
-        Ok(Message {
-            role: role_from_str(&self.role)?,
-            content: self.content,
+    fn into_entry(self) -> Result<TranscriptEntry, MemoryError> {
+        let created_at = match (self.created_at_secs, self.created_at_nanos) {
+            (Some(secs), Some(nanos)) => Some(decode_system_time(secs, nanos)?),
+            _ => None,
+        };
+        Ok(TranscriptEntry {
+            parent: self.parent,
+            message: Message {
+                role: role_from_str(&self.role)?,
+                content: self.content,
+            },
+            metadata: MessageMetadata {
+                created_at,
+                provider: self.provider.as_deref().map(parse_provider).transpose()?,
+                model: self.model,
+                usage: self.usage.map(PersistedTokenUsage::into_usage),
+            },
         })
     }
 }
 
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+struct PersistedTokenUsage {
+    input_tokens: u32,
+    output_tokens: u32,
+    total_tokens: u32,
+    cached_input_tokens: u32,
+}
+
+impl PersistedTokenUsage {
+    fn from_usage(usage: TokenUsage) -> Self {
+        Self {
+            input_tokens: usage.input_tokens,
+            output_tokens: usage.output_tokens,
+            total_tokens: usage.total_tokens,
+            cached_input_tokens: usage.cached_input_tokens,
+        }
+    }
+
+    fn into_usage(self) -> TokenUsage {
+        TokenUsage {
+            input_tokens: self.input_tokens,
+            output_tokens: self.output_tokens,
+            total_tokens: self.total_tokens,
+            cached_input_tokens: self.cached_input_tokens,
+        }
+    }
+}
+
 fn write_atomic(path: &Path, bytes: &[u8]) -> Result<(), MemoryError> {
     let Some(parent) = path.parent() else {
         return Err(MemoryError::storage(
@@ -667,6 +1077,17 @@ fn hex_encode(input: &[u8]) -> String {
     output
 }
 
+fn hex_decode(input: &str) -> Option<String> {
+    if !input.len().is_multiple_of(2) {
+        return None;
+    }
+    let bytes = (0..input.len())
+        .step_by(2)
+        .map(|index| u8::from_str_radix(input.get(index..index + 2)?, 16).ok())
+        .collect::<Option<Vec<_>>>()?;
+    String::from_utf8(bytes).ok()
+}
+
 fn nibble_to_hex(nibble: u8) -> char {
     match nibble {
         0..=9 => (b'0' + nibble) as char,
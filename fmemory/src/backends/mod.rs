@@ -1,3 +1,4 @@
 pub mod filesystem;
 pub mod postgres;
 pub mod sqlite;
+pub(crate) mod transcript;
@@ -1,12 +1,20 @@
 use std::collections::HashMap;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
+use fchat::{
+    ConversationTree, MessageId, MessageMetadata, SessionMetadata, SessionPage, SessionPageRequest,
+    StoredMessage,
+};
 use fcommon::SessionId;
-use fprovider::{Message, Role};
+use fprovider::{Message, Role, TokenUsage};
 use serde::{Deserialize, Serialize};
-use tokio_postgres::NoTls;
+use tokio_postgres::{Client, GenericClient, NoTls, Transaction};
 
-use crate::backend::MemoryBackend;
+use crate::backend::{MemoryBackend, check_transcript_version};
+use crate::backends::transcript::{
+    ConversationRecord, PositionedTranscript, TranscriptEntry, active_stored_messages, build_tree,
+    linear_parents, parse_provider, resolve_leaf, take_active,
+};
 use crate::error::MemoryError;
 use crate::types::{
     BootstrapState, FeatureRecord, InitCommand, InitPlan, InitShell, InitShellScript, InitStep,
@@ -47,7 +55,7 @@ impl PostgresMemoryBackend {
         Ok(Self { config })
     }
 
-    async fn connect_client(&self) -> Result<tokio_postgres::Client, MemoryError> {
+    async fn connect_client(&self) -> Result<Client, MemoryError> {
         let mut config = tokio_postgres::Config::new();
         config.host(&self.config.host);
         config.port(self.config.port);
@@ -84,7 +92,7 @@ impl PostgresMemoryBackend {
 
     async fn load_state(
         &self,
-        client: &tokio_postgres::Client,
+        client: &impl GenericClient,
         session_id: &SessionId,
     ) -> Result<Option<PersistedState>, MemoryError> {
         let row = client
@@ -102,17 +110,22 @@ impl PostgresMemoryBackend {
         };
 
         let value = row.get::<usize, serde_json::Value>(0);
-        let state = serde_json::from_value::<PersistedState>(value).map_err(|error| {
+        let mut state = serde_json::from_value::<PersistedState>(value).map_err(|error| {
             MemoryError::storage(format!(
                 "failed to deserialize postgres session state: {error}"
             ))
         })?;
+        state.upgrade_transcript();
+        // State written before session metadata existed carries no timestamps.
+        if state.conversation.is_none() && !state.transcript.is_empty() {
+            state.backfill_conversation(UNIX_EPOCH)?;
+        }
         Ok(Some(state))
     }
 
     async fn save_state(
         &self,
-        client: &tokio_postgres::Client,
+        client: &impl GenericClient,
         session_id: &SessionId,
         state: &PersistedState,
     ) -> Result<(), MemoryError> {
@@ -137,6 +150,34 @@ impl PostgresMemoryBackend {
             })?;
         Ok(())
     }
+
+    /// Starts a transaction holding the session's write lock. Every read-modify-write of
+    /// `state_json` runs under it, so concurrent writers cannot overwrite each other's changes.
+    async fn begin_locked<'c>(
+        client: &'c mut Client,
+        session_id: &SessionId,
+    ) -> Result<Transaction<'c>, MemoryError> {
+        let tx = client
+            .transaction()
+            .await
+            .map_err(|error| MemoryError::storage(format!("failed to begin tx: {error}")))?;
+
+        // A row lock cannot cover a session whose row does not exist yet, so writers
+        // serialize on an advisory lock keyed by the session id instead.
+        tx.execute(
+            "SELECT pg_advisory_xact_lock(hashtext($1))",
+            &[&session_id.as_str()],
+        )
+        .await
+        .map_err(|error| MemoryError::storage(format!("failed to lock session state: {error}")))?;
+        Ok(tx)
+    }
+
+    async fn commit(tx: Transaction<'_>) -> Result<(), MemoryError> {
+        tx.commit()
+            .await
+            .map_err(|error| MemoryError::storage(format!("failed to commit tx: {error}")))
+    }
 }
 
 impl MemoryBackend for PostgresMemoryBackend {
@@ -164,31 +205,8 @@ impl MemoryBackend for PostgresMemoryBackend {
     ) -> fcommon::BoxFuture<'a, Result<bool, MemoryError>> {
         Box::pin(async move {
             let mut client = self.connect_client().await?;
-            let tx = client
-                .transaction()
-                .await
-                .map_err(|error| MemoryError::storage(format!("failed to begin tx: {error}")))?;
-
-            let existing = tx
-                .query_opt(
-                    "SELECT state_json FROM fmemory_session_state WHERE session_id = $1 FOR UPDATE",
-                    &[&session_id.as_str()],
-                )
-                .await
-                .map_err(|error| {
-                    MemoryError::storage(format!("failed to lock session state row: {error}"))
-                })?;
-
-            let mut state = if let Some(row) = existing {
-                let value = row.get::<usize, serde_json::Value>(0);
-                serde_json::from_value::<PersistedState>(value).map_err(|error| {
-                    MemoryError::storage(format!(
-                        "failed to deserialize existing session state: {error}"
-                    ))
-                })?
-            } else {
-                PersistedState::default()
-            };
+            let tx = Self::begin_locked(&mut client, session_id).await?;
+            let mut state = self.load_state(&tx, session_id).await?.unwrap_or_default();
 
             if state.manifest.is_some() {
                 tx.rollback().await.map_err(|error| {
@@ -213,28 +231,8 @@ impl MemoryBackend for PostgresMemoryBackend {
                     .push(PersistedRunCheckpoint::from_checkpoint(checkpoint)?);
             }
 
-            let value = serde_json::to_value(&state).map_err(|error| {
-                MemoryError::storage(format!("failed to serialize session state: {error}"))
-            })?;
-            tx.execute(
-                "
-                INSERT INTO fmemory_session_state (session_id, state_json)
-                VALUES ($1, $2)
-                ON CONFLICT (session_id)
-                DO UPDATE SET state_json = EXCLUDED.state_json
-                ",
-                &[&session_id.as_str(), &value],
-            )
-            .await
-            .map_err(|error| {
-                MemoryError::storage(format!(
-                    "failed to write initialized session state: {error}"
-                ))
-            })?;
-
-            tx.commit()
-                .await
-                .map_err(|error| MemoryError::storage(format!("failed to commit tx: {error}")))?;
+            self.save_state(&tx, session_id, &state).await?;
+            Self::commit(tx).await?;
             Ok(true)
         })
     }
@@ -258,13 +256,12 @@ impl MemoryBackend for PostgresMemoryBackend {
         manifest: SessionManifest,
     ) -> fcommon::BoxFuture<'a, Result<(), MemoryError>> {
         Box::pin(async move {
-            let client = self.connect_client().await?;
-            let mut state = self
-                .load_state(&client, session_id)
-                .await?
-                .unwrap_or_default();
+            let mut client = self.connect_client().await?;
+            let tx = Self::begin_locked(&mut client, session_id).await?;
+            let mut state = self.load_state(&tx, session_id).await?.unwrap_or_default();
             state.manifest = Some(PersistedManifest::from_manifest(manifest)?);
-            self.save_state(&client, session_id, &state).await
+            self.save_state(&tx, session_id, &state).await?;
+            Self::commit(tx).await
         })
     }
 
@@ -274,15 +271,14 @@ impl MemoryBackend for PostgresMemoryBackend {
         entry: ProgressEntry,
     ) -> fcommon::BoxFuture<'a, Result<(), MemoryError>> {
         Box::pin(async move {
-            let client = self.connect_client().await?;
-            let mut state = self
-                .load_state(&client, session_id)
-                .await?
-                .unwrap_or_default();
+            let mut client = self.connect_client().await?;
+            let tx = Self::begin_locked(&mut client, session_id).await?;
+            let mut state = self.load_state(&tx, session_id).await?.unwrap_or_default();
             state
                 .recent_progress
                 .push(PersistedProgressEntry::from_entry(entry)?);
-            self.save_state(&client, session_id, &state).await
+            self.save_state(&tx, session_id, &state).await?;
+            Self::commit(tx).await
         })
     }
 
@@ -292,16 +288,15 @@ impl MemoryBackend for PostgresMemoryBackend {
         features: Vec<FeatureRecord>,
     ) -> fcommon::BoxFuture<'a, Result<(), MemoryError>> {
         Box::pin(async move {
-            let client = self.connect_client().await?;
-            let mut state = self
-                .load_state(&client, session_id)
-                .await?
-                .unwrap_or_default();
+            let mut client = self.connect_client().await?;
+            let tx = Self::begin_locked(&mut client, session_id).await?;
+            let mut state = self.load_state(&tx, session_id).await?.unwrap_or_default();
             state.feature_list = features
                 .into_iter()
                 .map(PersistedFeatureRecord::from_feature_record)
                 .collect();
-            self.save_state(&client, session_id, &state).await
+            self.save_state(&tx, session_id, &state).await?;
+            Self::commit(tx).await
         })
     }
 
@@ -312,14 +307,13 @@ impl MemoryBackend for PostgresMemoryBackend {
         passes: bool,
     ) -> fcommon::BoxFuture<'a, Result<(), MemoryError>> {
         Box::pin(async move {
-            let client = self.connect_client().await?;
-            let mut state = self
-                .load_state(&client, session_id)
-                .await?
-                .unwrap_or_default();
+            let mut client = self.connect_client().await?;
+            let tx = Self::begin_locked(&mut client, session_id).await?;
+            let mut state = self.load_state(&tx, session_id).await?.unwrap_or_default();
             if let Some(feature) = state.feature_list.iter_mut().find(|f| f.id == feature_id) {
                 feature.passes = passes;
-                return self.save_state(&client, session_id, &state).await;
+                self.save_state(&tx, session_id, &state).await?;
+                return Self::commit(tx).await;
             }
             Err(MemoryError::not_found(format!(
                 "feature '{feature_id}' not found"
@@ -333,32 +327,28 @@ impl MemoryBackend for PostgresMemoryBackend {
         checkpoint: RunCheckpoint,
     ) -> fcommon::BoxFuture<'a, Result<(), MemoryError>> {
         Box::pin(async move {
-            let client = self.connect_client().await?;
-            let mut state = self
-                .load_state(&client, session_id)
-                .await?
-                .unwrap_or_default();
+            let mut client = self.connect_client().await?;
+            let tx = Self::begin_locked(&mut client, session_id).await?;
+            let mut state = self.load_state(&tx, session_id).await?.unwrap_or_default();
             state
                 .checkpoints
                 .push(PersistedRunCheckpoint::from_checkpoint(checkpoint)?);
-            self.save_state(&client, session_id, &state).await
+            self.save_state(&tx, session_id, &state).await?;
+            Self::commit(tx).await
         })
     }
 
     fn load_transcript_messages<'a>(
         &'a self,
         session_id: &'a SessionId,
-    ) -> fcommon::BoxFuture<'a, Result<Vec<Message>, MemoryError>> {
+    ) -> fcommon::BoxFuture<'a, Result<Vec<StoredMessage>, MemoryError>> {
         Box::pin(async move {
             let client = self.connect_client().await?;
             let Some(state) = self.load_state(&client, session_id).await? else {
                 return Ok(Vec::new());
             };
-            state
-                .transcript
-                .into_iter()
-                .map(PersistedMessage::into_message)
-                .collect()
+            let (entries, leaf) = state.into_positioned_transcript()?;
+            Ok(active_stored_messages(entries, leaf))
         })
     }
 
@@ -366,20 +356,214 @@ impl MemoryBackend for PostgresMemoryBackend {
         &'a self,
         session_id: &'a SessionId,
         messages: Vec<Message>,
+    ) -> fcommon::BoxFuture<'a, Result<(), MemoryError>> {
+        let metadata = MessageMetadata::new(SystemTime::now());
+        self.append_transcript_messages_with_metadata(
+            session_id,
+            messages
+                .into_iter()
+                .map(|message| (message, metadata.clone()))
+                .collect(),
+        )
+    }
+
+    fn append_transcript_messages_with_metadata<'a>(
+        &'a self,
+        session_id: &'a SessionId,
+        messages: Vec<(Message, MessageMetadata)>,
     ) -> fcommon::BoxFuture<'a, Result<(), MemoryError>> {
+        Box::pin(async move {
+            let mut client = self.connect_client().await?;
+            let tx = Self::begin_locked(&mut client, session_id).await?;
+            let mut state = self.load_state(&tx, session_id).await?.unwrap_or_default();
+            state.append_transcript(messages)?;
+            self.save_state(&tx, session_id, &state).await?;
+            Self::commit(tx).await
+        })
+    }
+
+    fn transcript_version<'a>(
+        &'a self,
+        session_id: &'a SessionId,
+    ) -> fcommon::BoxFuture<'a, Result<Option<u64>, MemoryError>> {
         Box::pin(async move {
             let client = self.connect_client().await?;
-            let mut state = self
-                .load_state(&client, session_id)
-                .await?
-                .unwrap_or_default();
-            state.transcript.extend(
-                messages
-                    .into_iter()
-                    .map(PersistedMessage::from_message)
-                    .collect::<Vec<_>>(),
-            );
-            self.save_state(&client, session_id, &state).await
+            Ok(Some(
+                self.load_state(&client, session_id)
+                    .await?
+                    .map_or(0, |state| state.transcript_version),
+            ))
+        })
+    }
+
+    fn append_transcript_messages_if_version<'a>(
+        &'a self,
+        session_id: &'a SessionId,
+        expected_version: u64,
+        messages: Vec<(Message, MessageMetadata)>,
+    ) -> fcommon::BoxFuture<'a, Result<(), MemoryError>> {
+        Box::pin(async move {
+            let mut client = self.connect_client().await?;
+            let tx = Self::begin_locked(&mut client, session_id).await?;
+            let mut state = self.load_state(&tx, session_id).await?.unwrap_or_default();
+            check_transcript_version(session_id, expected_version, state.transcript_version)?;
+            state.append_transcript(messages)?;
+            self.save_state(&tx, session_id, &state).await?;
+            Self::commit(tx).await
+        })
+    }
+
+    fn load_transcript_tree<'a>(
+        &'a self,
+        session_id: &'a SessionId,
+    ) -> fcommon::BoxFuture<'a, Result<ConversationTree, MemoryError>> {
+        Box::pin(async move {
+            let client = self.connect_client().await?;
+            let Some(state) = self.load_state(&client, session_id).await? else {
+                return Ok(ConversationTree::default());
+            };
+            state.into_transcript_tree()
+        })
+    }
+
+    fn set_transcript_active_leaf<'a>(
+        &'a self,
+        session_id: &'a SessionId,
+        leaf: Option<MessageId>,
+    ) -> fcommon::BoxFuture<'a, Result<(), MemoryError>> {
+        Box::pin(async move {
+            let mut client = self.connect_client().await?;
+            let tx = Self::begin_locked(&mut client, session_id).await?;
+            let mut state = self.load_state(&tx, session_id).await?.unwrap_or_default();
+            state.upgrade_transcript();
+            state.transcript_leaf = resolve_leaf(leaf.as_ref(), state.transcript.len())?;
+            state.transcript_version += 1;
+            self.save_state(&tx, session_id, &state).await?;
+            Self::commit(tx).await
+        })
+    }
+
+    fn list_sessions<'a>(
+        &'a self,
+        page: SessionPageRequest,
+    ) -> fcommon::BoxFuture<'a, Result<SessionPage, MemoryError>> {
+        Box::pin(async move {
+            let client = self.connect_client().await?;
+            // One extra row tells whether another page follows.
+            let limit = i64::try_from(page.limit.saturating_add(1)).unwrap_or(i64::MAX);
+            let offset = i64::try_from(page.offset).unwrap_or(i64::MAX);
+            // Same order as `SessionPageRequest::paginate`. Sessions written before metadata
+            // existed have no `conversation` and sort as last updated at the epoch.
+            let rows = client
+                .query(
+                    "
+                    SELECT
+                        session_id,
+                        state_json->'conversation'->>'title',
+                        COALESCE((state_json->'conversation'->>'created_at_secs')::BIGINT, 0),
+                        COALESCE((state_json->'conversation'->>'created_at_nanos')::BIGINT, 0),
+                        COALESCE((state_json->'conversation'->>'updated_at_secs')::BIGINT, 0)
+                            AS updated_at_secs,
+                        COALESCE((state_json->'conversation'->>'updated_at_nanos')::BIGINT, 0)
+                            AS updated_at_nanos,
+                        jsonb_array_length(state_json->'transcript')::BIGINT AS message_count
+                    FROM fmemory_session_state
+                    WHERE jsonb_typeof(state_json->'conversation') = 'object'
+                        OR jsonb_array_length(state_json->'transcript') > 0
+                    ORDER BY updated_at_secs DESC, updated_at_nanos DESC, session_id COLLATE \"C\" ASC
+                    LIMIT $1 OFFSET $2
+                    ",
+                    &[&limit, &offset],
+                )
+                .await
+                .map_err(|error| {
+                    MemoryError::storage(format!("failed to list session states: {error}"))
+                })?;
+
+            let mut sessions = Vec::with_capacity(rows.len());
+            for row in rows {
+                let record = ConversationRecord {
+                    title: row.get::<usize, Option<String>>(1),
+                    created_at: decode_system_time(row.get(2), row.get(3))?,
+                    updated_at: decode_system_time(row.get(4), row.get(5))?,
+                };
+                sessions.push(record.metadata(
+                    &SessionId::from(row.get::<usize, String>(0)),
+                    usize::try_from(row.get::<usize, i64>(6)).unwrap_or_default(),
+                ));
+            }
+
+            let has_more = sessions.len() > page.limit;
+            sessions.truncate(page.limit);
+            let next_offset = has_more.then(|| page.offset + sessions.len());
+            Ok(SessionPage {
+                sessions,
+                next_offset,
+            })
+        })
+    }
+
+    fn session_metadata<'a>(
+        &'a self,
+        session_id: &'a SessionId,
+    ) -> fcommon::BoxFuture<'a, Result<Option<SessionMetadata>, MemoryError>> {
+        Box::pin(async move {
+            let client = self.connect_client().await?;
+            match self.load_state(&client, session_id).await? {
+                Some(state) => state.session_metadata(session_id),
+                None => Ok(None),
+            }
+        })
+    }
+
+    fn rename_session<'a>(
+        &'a self,
+        session_id: &'a SessionId,
+        title: String,
+    ) -> fcommon::BoxFuture<'a, Result<(), MemoryError>> {
+        Box::pin(async move {
+            let mut client = self.connect_client().await?;
+            let tx = Self::begin_locked(&mut client, session_id).await?;
+            let mut state = self.load_state(&tx, session_id).await?.unwrap_or_default();
+            state.touch_conversation(Some(title))?;
+            self.save_state(&tx, session_id, &state).await?;
+            Self::commit(tx).await
+        })
+    }
+
+    fn delete_session<'a>(
+        &'a self,
+        session_id: &'a SessionId,
+    ) -> fcommon::BoxFuture<'a, Result<(), MemoryError>> {
+        Box::pin(async move {
+            let mut client = self.connect_client().await?;
+            let tx = Self::begin_locked(&mut client, session_id).await?;
+            tx.execute(
+                "DELETE FROM fmemory_session_state WHERE session_id = $1",
+                &[&session_id.as_str()],
+            )
+            .await
+            .map_err(|error| {
+                MemoryError::storage(format!("failed to delete session state: {error}"))
+            })?;
+            Self::commit(tx).await
+        })
+    }
+
+    fn truncate_transcript<'a>(
+        &'a self,
+        session_id: &'a SessionId,
+        keep_messages: usize,
+    ) -> fcommon::BoxFuture<'a, Result<(), MemoryError>> {
+        Box::pin(async move {
+            let mut client = self.connect_client().await?;
+            let tx = Self::begin_locked(&mut client, session_id).await?;
+            let Some(mut state) = self.load_state(&tx, session_id).await? else {
+                return Ok(());
+            };
+            state.truncate_transcript(keep_messages)?;
+            self.save_state(&tx, session_id, &state).await?;
+            Self::commit(tx).await
         })
     }
 }
@@ -391,9 +575,124 @@ struct PersistedState {
     recent_progress: Vec<PersistedProgressEntry>,
     checkpoints: Vec<PersistedRunCheckpoint>,
     transcript: Vec<PersistedMessage>,
+    #[serde(default)]
+    transcript_leaf: Option<usize>,
+    /// Unset in state written before branching, whose messages carry no parent links.
+    #[serde(default)]
+    transcript_branching: bool,
+    /// Increased by every transcript change; absent in state written before it was tracked.
+    #[serde(default)]
+    transcript_version: u64,
+    #[serde(default)]
+    conversation: Option<PersistedConversation>,
 }
 
 impl PersistedState {
+    fn upgrade_transcript(&mut self) {
+        if self.transcript_branching {
+            return;
+        }
+        let parents = linear_parents(self.transcript.len());
+        for (message, parent) in self.transcript.iter_mut().zip(parents) {
+            message.parent = parent;
+        }
+        self.transcript_leaf = self.transcript.len().checked_sub(1);
+        self.transcript_branching = true;
+    }
+
+    fn backfill_conversation(&mut self, timestamp: SystemTime) -> Result<(), MemoryError> {
+        self.conversation = Some(PersistedConversation::from_record(ConversationRecord {
+            title: None,
+            created_at: timestamp,
+            updated_at: timestamp,
+        })?);
+        Ok(())
+    }
+
+    /// Marks the session updated now, replacing the title when one is given.
+    fn touch_conversation(&mut self, title: Option<String>) -> Result<(), MemoryError> {
+        let mut record = self
+            .conversation
+            .take()
+            .map(PersistedConversation::into_record)
+            .transpose()?;
+        let touched = ConversationRecord::touch(&mut record);
+        if title.is_some() {
+            touched.title = title;
+        }
+        self.conversation = record.map(PersistedConversation::from_record).transpose()?;
+        Ok(())
+    }
+
+    fn session_metadata(
+        &self,
+        session_id: &SessionId,
+    ) -> Result<Option<SessionMetadata>, MemoryError> {
+        self.conversation
+            .clone()
+            .map(|conversation| {
+                Ok(conversation
+                    .into_record()?
+                    .metadata(session_id, self.transcript.len()))
+            })
+            .transpose()
+    }
+
+    fn append_transcript(
+        &mut self,
+        messages: Vec<(Message, MessageMetadata)>,
+    ) -> Result<(), MemoryError> {
+        self.upgrade_transcript();
+        for (message, metadata) in messages {
+            let mut persisted = PersistedMessage::from_entry(message, &metadata)?;
+            persisted.parent = self.transcript_leaf.replace(self.transcript.len());
+            self.transcript.push(persisted);
+        }
+        self.transcript_version += 1;
+        self.touch_conversation(None)
+    }
+
+    fn truncate_transcript(&mut self, keep_messages: usize) -> Result<(), MemoryError> {
+        self.upgrade_transcript();
+        let parents = self.transcript_parents();
+        let kept = take_active(
+            std::mem::take(&mut self.transcript),
+            &parents,
+            self.transcript_leaf,
+            keep_messages,
+        );
+        self.transcript_leaf = None;
+        for mut message in kept {
+            message.parent = self.transcript_leaf.replace(self.transcript.len());
+            self.transcript.push(message);
+        }
+        self.transcript_version += 1;
+        self.touch_conversation(None)
+    }
+
+    fn transcript_parents(&self) -> Vec<Option<usize>> {
+        self.transcript
+            .iter()
+            .map(|message| message.parent)
+            .collect()
+    }
+
+    fn into_positioned_transcript(
+        self,
+    ) -> Result<(PositionedTranscript, Option<usize>), MemoryError> {
+        let entries = self
+            .transcript
+            .into_iter()
+            .map(PersistedMessage::into_entry)
+            .collect::<Result<Vec<_>, MemoryError>>()?;
+        Ok((entries, self.transcript_leaf))
+    }
+
+    fn into_transcript_tree(self) -> Result<ConversationTree, MemoryError> {
+        let (entries, leaf) = self.into_positioned_transcript()?;
+        Ok(build_tree(entries, leaf))
+    }
+
     fn into_bootstrap_state(self, session_id: &SessionId) -> Result<BootstrapState, MemoryError> {
         let manifest = self
             .manifest
@@ -584,6 +883,37 @@ impl PersistedProgressEntry {
     }
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct PersistedConversation {
+    title: Option<String>,
+    created_at_secs: i64,
+    created_at_nanos: i64,
+    updated_at_secs: i64,
+    updated_at_nanos: i64,
+}
+
+impl PersistedConversation {
+    fn from_record(record: ConversationRecord) -> Result<Self, MemoryError> {
+        let (created_at_secs, created_at_nanos) = encode_system_time(record.created_at)?;
+        let (updated_at_secs, updated_at_nanos) = encode_system_time(record.updated_at)?;
+        Ok(Self {
+            title: record.title,
+            created_at_secs,
+            created_at_nanos,
+            updated_at_secs,
+            updated_at_nanos,
+        })
+    }
+
+    fn into_record(self) -> Result<ConversationRecord, MemoryError> {
+        Ok(ConversationRecord {
+            title: self.title,
+            created_at: decode_system_time(self.created_at_secs, self.created_at_nanos)?,
+            updated_at: decode_system_time(self.updated_at_secs, self.updated_at_nanos)?,
+        })
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct PersistedRunCheckpoint {
     run_id: String,
@@ -642,24 +972,84 @@ impl PersistedRunCheckpoint {
 struct PersistedMessage {
     role: String,
     content: String,
+    #[serde(default)]
+    parent: Option<usize>,
+    #[serde(default)]
+    created_at_secs: Option<i64>,
+    #[serde(default)]
+    created_at_nanos: Option<i64>,
+    #[serde(default)]
+    provider: Option<String>,
+    #[serde(default)]
+    model: Option<String>,
+    #[serde(default)]
+    usage: Option<PersistedTokenUsage>,
 }
 
 impl PersistedMessage {
-    fn from_message(message: Message) -> Self {
-        Self {
+    fn from_entry(message: Message, metadata: &MessageMetadata) -> Result<Self, MemoryError> {
+        let created_at = metadata.created_at.map(encode_system_time).transpose()?;
+        Ok(Self {
             role: role_to_string(message.role),
             content: message.content,
-        }
+            parent: None,
+            created_at_secs: created_at.map(|(secs, _)| secs),
+            created_at_nanos: created_at.map(|(_, nanos)| nanos),
+            provider: metadata.provider.map(|provider| provider.to_string()),
+            model: metadata.model.clone(),
+            usage: metadata.usage.map(PersistedTokenUsage::from_usage),
+        })
     }
 
-    fn into_message(self) -> Result<Message, MemoryError> {
-        Ok(Message {
-            role: role_from_str(&self.role)?,
-            content: self.content,
+    fn into_entry(self) -> Result<TranscriptEntry, MemoryError> {
+        let created_at = match (self.created_at_secs, self.created_at_nanos) {
+            (Some(secs), Some(nanos)) => Some(decode_system_time(secs, nanos)?),
+            _ => None,
+        };
+        Ok(TranscriptEntry {
+            parent: self.parent,
+            message: Message {
+                role: role_from_str(&self.role)?,
+                content: self.content,
+            },
+            metadata: MessageMetadata {
+                created_at,
+                provider: self.provider.as_deref().map(parse_provider).transpose()?,
+                model: self.model,
+                usage: self.usage.map(PersistedTokenUsage::into_usage),
+            },
         })
     }
 }
 
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+struct PersistedTokenUsage {
+    input_tokens: u32,
+    output_tokens: u32,
+    total_tokens: u32,
+    cached_input_tokens: u32,
+}
+
+impl PersistedTokenUsage {
+    fn from_usage(usage: TokenUsage) -> Self {
+        Self {
+            input_tokens: usage.input_tokens,
+            output_tokens: usage.output_tokens,
+            total_tokens: usage.total_tokens,
+            cached_input_tokens: usage.cached_input_tokens,
+        }
+    }
+
+    fn into_usage(self) -> TokenUsage {
+        TokenUsage {
+            input_tokens: self.input_tokens,
+            output_tokens: self.output_tokens,
+            total_tokens: self.total_tokens,
+            cached_input_tokens: self.cached_input_tokens,
+        }
+    }
+}
+
 fn encode_system_time(value: SystemTime) -> Result<(i64, i64), MemoryError> {
     let duration = value.duration_since(UNIX_EPOCH).map_err(|error| {
         MemoryError::invalid_request(format!("timestamp predates unix epoch: {error}"))
@@ -3,17 +3,28 @@ use std::path::{Path, PathBuf};
 use std::sync::Mutex;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
+use fchat::{
+    ConversationTree, MessageId, MessageMetadata, SessionMetadata, SessionPage, SessionPageRequest,
+    StoredMessage,
+};
 use fcommon::{BoxFuture, SessionId};
-use fprovider::{Message, Role};
-use rusqlite::{Connection, OptionalExtension, params};
+use fprovider::{Message, Role, TokenUsage};
+use rusqlite::{Connection, OptionalExtension, TransactionBehavior, params};
 
-use crate::backend::MemoryBackend;
+use crate::backend::{MemoryBackend, check_transcript_version};
+use crate::backends::transcript::{
+    ConversationRecord, PositionedTranscript, TranscriptEntry, active_stored_messages, build_tree,
+    parse_provider, resolve_leaf, take_active,
+};
 use crate::error::MemoryError;
 use crate::types::{
     BootstrapState, FeatureRecord, InitCommand, InitPlan, InitShell, InitShellScript, InitStep,
     ProgressEntry, RunCheckpoint, RunStatus, SessionManifest,
 };
 
+/// A transcript row: row id, parent row id, message, and metadata.
+type TranscriptRow = (i64, Option<i64>, Message, MessageMetadata);
+
 #[derive(Debug)]
 pub struct SqliteMemoryBackend {
     connection: Mutex<Connection>,
@@ -146,20 +157,490 @@ impl SqliteMemoryBackend {
                 id INTEGER PRIMARY KEY AUTOINCREMENT,
                 session_id TEXT NOT NULL,
                 role TEXT NOT NULL,
-                content TEXT NOT NULL
+                content TEXT NOT NULL,
+                parent_id INTEGER,
+                created_at_secs INTEGER,
+                created_at_nanos INTEGER,
+                provider TEXT,
+                model TEXT,
+                input_tokens INTEGER,
+                output_tokens INTEGER,
+                total_tokens INTEGER,
+                cached_input_tokens INTEGER
             );
 
             CREATE INDEX IF NOT EXISTS idx_transcript_session_id
             ON transcript_messages(session_id, id);
+
+            CREATE TABLE IF NOT EXISTS transcript_branches (
+                session_id TEXT PRIMARY KEY,
+                active_leaf_id INTEGER,
+                version INTEGER NOT NULL DEFAULT 0
+            );
+
+            CREATE TABLE IF NOT EXISTS transcript_sessions (
+                session_id TEXT PRIMARY KEY,
+                title TEXT,
+                created_at_secs INTEGER NOT NULL,
+                created_at_nanos INTEGER NOT NULL,
+                updated_at_secs INTEGER NOT NULL,
+                updated_at_nanos INTEGER NOT NULL
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_transcript_sessions_updated
+            ON transcript_sessions(updated_at_secs, updated_at_nanos);
             ",
         )
         .map_err(|error| {
             MemoryError::storage(format!("failed to initialize sqlite schema: {error}"))
         })?;
 
+        Self::migrate_transcript_parents(&conn)?;
+        Self::migrate_transcript_metadata(&conn)?;
+        Self::migrate_transcript_version(&conn)?;
+        Self::backfill_transcript_sessions(&conn)
+    }
+
+    /// Gives transcripts written before session metadata existed a session row stamped with
+    /// the time of the upgrade.
+    fn backfill_transcript_sessions(conn: &Connection) -> Result<(), MemoryError> {
+        let (secs, nanos) = encode_system_time(SystemTime::now())?;
+        conn.execute(
+            "
+            INSERT OR IGNORE INTO transcript_sessions (
+                session_id, title, created_at_secs, created_at_nanos, updated_at_secs,
+                updated_at_nanos
+            )
+            SELECT DISTINCT session_id, NULL, ?1, ?2, ?1, ?2
+            FROM transcript_messages
+            ",
+            params![secs, nanos],
+        )
+        .map_err(|error| {
+            MemoryError::storage(format!("failed to backfill transcript sessions: {error}"))
+        })?;
+        Ok(())
+    }
+
+    /// Marks the session updated now, creating its row on first use and replacing the title
+    /// when one is given.
+    fn touch_transcript_session(
+        conn: &Connection,
+        session_id: &SessionId,
+        title: Option<&str>,
+    ) -> Result<(), MemoryError> {
+        let (secs, nanos) = encode_system_time(SystemTime::now())?;
+        conn.execute(
+            "
+            INSERT INTO transcript_sessions (
+                session_id, title, created_at_secs, created_at_nanos, updated_at_secs,
+                updated_at_nanos
+            )
+            VALUES (?1, ?2, ?3, ?4, ?3, ?4)
+            ON CONFLICT(session_id) DO UPDATE SET
+                title = COALESCE(excluded.title, transcript_sessions.title),
+                updated_at_secs = excluded.updated_at_secs,
+                updated_at_nanos = excluded.updated_at_nanos
+            ",
+            params![session_id.as_str(), title, secs, nanos],
+        )
+        .map_err(|error| {
+            MemoryError::storage(format!("failed to update transcript session: {error}"))
+        })?;
+        Ok(())
+    }
+
+    /// Session rows matching `filter`, which may reference `?1`..`?n` from `values`.
+    fn query_transcript_sessions(
+        conn: &Connection,
+        filter: &str,
+        values: &[&dyn rusqlite::ToSql],
+    ) -> Result<Vec<SessionMetadata>, MemoryError> {
+        let mut stmt = conn
+            .prepare(&format!(
+                "
+                SELECT
+                    sessions.session_id,
+                    sessions.title,
+                    sessions.created_at_secs,
+                    sessions.created_at_nanos,
+                    sessions.updated_at_secs,
+                    sessions.updated_at_nanos,
+                    (
+                        SELECT COUNT(*)
+                        FROM transcript_messages
+                        WHERE transcript_messages.session_id = sessions.session_id
+                    )
+                FROM transcript_sessions AS sessions
+                {filter}
+                "
+            ))
+            .map_err(|error| {
+                MemoryError::storage(format!("failed to prepare session query: {error}"))
+            })?;
+        let rows = stmt
+            .query_map(values, |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, Option<String>>(1)?,
+                    row.get::<_, i64>(2)?,
+                    row.get::<_, i64>(3)?,
+                    row.get::<_, i64>(4)?,
+                    row.get::<_, i64>(5)?,
+                    row.get::<_, i64>(6)?,
+                ))
+            })
+            .map_err(|error| {
+                MemoryError::storage(format!("failed to query session rows: {error}"))
+            })?;
+
+        let mut sessions = Vec::new();
+        for row in rows {
+            let (
+                session_id,
+                title,
+                created_secs,
+                created_nanos,
+                updated_secs,
+                updated_nanos,
+                count,
+            ) = row.map_err(|error| {
+                MemoryError::storage(format!("failed to read session row: {error}"))
+            })?;
+            let record = ConversationRecord {
+                title,
+                created_at: decode_system_time(created_secs, created_nanos)?,
+                updated_at: decode_system_time(updated_secs, updated_nanos)?,
+            };
+            sessions.push(record.metadata(
+                &SessionId::from(session_id),
+                usize::try_from(count).unwrap_or_default(),
+            ));
+        }
+        Ok(sessions)
+    }
+
+    /// Adds parent links to databases created before branching, chaining each session's
+    /// messages in insertion order.
+    fn migrate_transcript_parents(conn: &Connection) -> Result<(), MemoryError> {
+        let has_parent_column = conn
+            .prepare(
+                "SELECT 1 FROM pragma_table_info('transcript_messages') WHERE name = 'parent_id'",
+            )
+            .and_then(|mut stmt| stmt.exists([]))
+            .map_err(|error| {
+                MemoryError::storage(format!("failed to inspect transcript schema: {error}"))
+            })?;
+        if has_parent_column {
+            return Ok(());
+        }
+
+        conn.execute_batch(
+            "
+            ALTER TABLE transcript_messages ADD COLUMN parent_id INTEGER;
+
+            UPDATE transcript_messages
+            SET parent_id = (
+                SELECT MAX(previous.id)
+                FROM transcript_messages AS previous
+                WHERE previous.session_id = transcript_messages.session_id
+                  AND previous.id < transcript_messages.id
+            );
+            ",
+        )
+        .map_err(|error| {
+            MemoryError::storage(format!("failed to migrate transcript parents: {error}"))
+        })
+    }
+
+    /// Adds the per-message metadata columns to databases created before they existed; rows
+    /// already stored keep `NULL` metadata.
+    fn migrate_transcript_metadata(conn: &Connection) -> Result<(), MemoryError> {
+        for (column, column_type) in [
+            ("created_at_secs", "INTEGER"),
+            ("created_at_nanos", "INTEGER"),
+            ("provider", "TEXT"),
+            ("model", "TEXT"),
+            ("input_tokens", "INTEGER"),
+            ("output_tokens", "INTEGER"),
+            ("total_tokens", "INTEGER"),
+            ("cached_input_tokens", "INTEGER"),
+        ] {
+            let exists = conn
+                .prepare("SELECT 1 FROM pragma_table_info('transcript_messages') WHERE name = ?1")
+                .and_then(|mut stmt| stmt.exists(params![column]))
+                .map_err(|error| {
+                    MemoryError::storage(format!("failed to inspect transcript schema: {error}"))
+                })?;
+            if exists {
+                continue;
+            }
+            conn.execute(
+                &format!("ALTER TABLE transcript_messages ADD COLUMN {column} {column_type}"),
+                [],
+            )
+            .map_err(|error| {
+                MemoryError::storage(format!("failed to migrate transcript metadata: {error}"))
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Adds the transcript version to databases created before it existed; sessions already
+    /// stored start at version 0.
+    fn migrate_transcript_version(conn: &Connection) -> Result<(), MemoryError> {
+        let has_version_column = conn
+            .prepare(
+                "SELECT 1 FROM pragma_table_info('transcript_branches') WHERE name = 'version'",
+            )
+            .and_then(|mut stmt| stmt.exists([]))
+            .map_err(|error| {
+                MemoryError::storage(format!("failed to inspect transcript schema: {error}"))
+            })?;
+        if has_version_column {
+            return Ok(());
+        }
+
+        conn.execute(
+            "ALTER TABLE transcript_branches ADD COLUMN version INTEGER NOT NULL DEFAULT 0",
+            [],
+        )
+        .map_err(|error| {
+            MemoryError::storage(format!("failed to migrate transcript version: {error}"))
+        })?;
+        Ok(())
+    }
+
+    /// Every transcript row of the session as `(row id, parent row id, message, metadata)`.
+    fn load_transcript_rows(
+        conn: &Connection,
+        session_id: &SessionId,
+    ) -> Result<Vec<TranscriptRow>, MemoryError> {
+        let mut stmt = conn
+            .prepare(
+                "
+                SELECT
+                    id,
+                    parent_id,
+                    role,
+                    content,
+                    created_at_secs,
+                    created_at_nanos,
+                    provider,
+                    model,
+                    input_tokens,
+                    output_tokens,
+                    total_tokens,
+                    cached_input_tokens
+                FROM transcript_messages
+                WHERE session_id = ?1
+                ORDER BY id ASC
+                ",
+            )
+            .map_err(|error| {
+                MemoryError::storage(format!("failed to prepare transcript query: {error}"))
+            })?;
+        let rows = stmt
+            .query_map(params![session_id.as_str()], |row| {
+                Ok((
+                    row.get::<_, i64>(0)?,
+                    row.get::<_, Option<i64>>(1)?,
+                    row.get::<_, String>(2)?,
+                    row.get::<_, String>(3)?,
+                    row.get::<_, Option<i64>>(4)?,
+                    row.get::<_, Option<i64>>(5)?,
+                    row.get::<_, Option<String>>(6)?,
+                    row.get::<_, Option<String>>(7)?,
+                    [
+                        row.get::<_, Option<i64>>(8)?,
+                        row.get::<_, Option<i64>>(9)?,
+                        row.get::<_, Option<i64>>(10)?,
+                        row.get::<_, Option<i64>>(11)?,
+                    ],
+                ))
+            })
+            .map_err(|error| {
+                MemoryError::storage(format!("failed to query transcript rows: {error}"))
+            })?;
+        let mut messages = Vec::new();
+        for row in rows {
+            let (id, parent_id, role, content, created_secs, created_nanos, provider, model, usage) =
+                row.map_err(|error| {
+                    MemoryError::storage(format!("failed to read transcript row: {error}"))
+                })?;
+            let created_at = match (created_secs, created_nanos) {
+                (Some(secs), Some(nanos)) => Some(decode_system_time(secs, nanos)?),
+                _ => None,
+            };
+            messages.push((
+                id,
+                parent_id,
+                Message {
+                    role: role_from_str(&role)?,
+                    content,
+                },
+                MessageMetadata {
+                    created_at,
+                    provider: provider.as_deref().map(parse_provider).transpose()?,
+                    model,
+                    usage: decode_token_usage(usage)?,
+                },
+            ));
+        }
+        Ok(messages)
+    }
+
+    /// Row id of the active leaf. Sessions that never moved their leaf use the newest message.
+    fn active_transcript_leaf(
+        conn: &Connection,
+        session_id: &SessionId,
+    ) -> Result<Option<i64>, MemoryError> {
+        let explicit = conn
+            .query_row(
+                "SELECT active_leaf_id FROM transcript_branches WHERE session_id = ?1",
+                params![session_id.as_str()],
+                |row| row.get::<_, Option<i64>>(0),
+            )
+            .optional()
+            .map_err(|error| {
+                MemoryError::storage(format!("failed to query transcript leaf: {error}"))
+            })?;
+        if let Some(leaf) = explicit {
+            return Ok(leaf);
+        }
+
+        conn.query_row(
+            "SELECT MAX(id) FROM transcript_messages WHERE session_id = ?1",
+            params![session_id.as_str()],
+            |row| row.get::<_, Option<i64>>(0),
+        )
+        .map_err(|error| MemoryError::storage(format!("failed to query transcript leaf: {error}")))
+    }
+
+    /// Stores the active leaf and increases the transcript version; every transcript change
+    /// goes through here.
+    fn save_transcript_leaf(
+        conn: &Connection,
+        session_id: &SessionId,
+        leaf: Option<i64>,
+    ) -> Result<(), MemoryError> {
+        conn.execute(
+            "
+            INSERT INTO transcript_branches (session_id, active_leaf_id, version)
+            VALUES (?1, ?2, 1)
+            ON CONFLICT(session_id) DO UPDATE SET
+                active_leaf_id = excluded.active_leaf_id,
+                version = transcript_branches.version + 1
+            ",
+            params![session_id.as_str(), leaf],
+        )
+        .map_err(|error| {
+            MemoryError::storage(format!("failed to save transcript leaf: {error}"))
+        })?;
         Ok(())
     }
 
+    /// Inserts below the active leaf and makes the last inserted message the new leaf.
+    fn insert_transcript_messages(
+        conn: &Connection,
+        session_id: &SessionId,
+        messages: Vec<(Message, MessageMetadata)>,
+    ) -> Result<(), MemoryError> {
+        let mut leaf = Self::active_transcript_leaf(conn, session_id)?;
+        for (message, metadata) in messages {
+            let created_at = metadata.created_at.map(encode_system_time).transpose()?;
+            let usage = metadata.usage.map(|usage| {
+                [
+                    usage.input_tokens,
+                    usage.output_tokens,
+                    usage.total_tokens,
+                    usage.cached_input_tokens,
+                ]
+            });
+            conn.execute(
+                "
+                INSERT INTO transcript_messages (
+                    session_id,
+                    role,
+                    content,
+                    parent_id,
+                    created_at_secs,
+                    created_at_nanos,
+                    provider,
+                    model,
+                    input_tokens,
+                    output_tokens,
+                    total_tokens,
+                    cached_input_tokens
+                )
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)
+                ",
+                params![
+                    session_id.as_str(),
+                    role_to_str(message.role),
+                    message.content,
+                    leaf,
+                    created_at.map(|(secs, _)| secs),
+                    created_at.map(|(_, nanos)| nanos),
+                    metadata.provider.map(|provider| provider.to_string()),
+                    metadata.model,
+                    usage.map(|usage| usage[0]),
+                    usage.map(|usage| usage[1]),
+                    usage.map(|usage| usage[2]),
+                    usage.map(|usage| usage[3]),
+                ],
+            )
+            .map_err(|error| {
+                MemoryError::storage(format!("failed to append transcript message: {error}"))
+            })?;
+            leaf = Some(conn.last_insert_rowid());
+        }
+        Self::save_transcript_leaf(conn, session_id, leaf)?;
+        Self::touch_transcript_session(conn, session_id, None)
+    }
+
+    fn load_transcript_version(
+        conn: &Connection,
+        session_id: &SessionId,
+    ) -> Result<u64, MemoryError> {
+        let version = conn
+            .query_row(
+                "SELECT version FROM transcript_branches WHERE session_id = ?1",
+                params![session_id.as_str()],
+                |row| row.get::<_, i64>(0),
+            )
+            .optional()
+            .map_err(|error| {
+                MemoryError::storage(format!("failed to query transcript version: {error}"))
+            })?;
+        Ok(version.map_or(0, |version| u64::try_from(version).unwrap_or_default()))
+    }
+
+    /// Transcript rows re-addressed by position, with the active leaf's position.
+    fn load_positioned_transcript(
+        conn: &Connection,
+        session_id: &SessionId,
+    ) -> Result<(PositionedTranscript, Option<usize>), MemoryError> {
+        let rows = Self::load_transcript_rows(conn, session_id)?;
+        let leaf = Self::active_transcript_leaf(conn, session_id)?;
+        let positions = rows
+            .iter()
+            .enumerate()
+            .map(|(position, (id, _, _, _))| (*id, position))
+            .collect::<HashMap<_, _>>();
+
+        let leaf = leaf.and_then(|leaf| positions.get(&leaf).copied());
+        let entries = rows
+            .into_iter()
+            .map(|(_, parent_id, message, metadata)| TranscriptEntry {
+                parent: parent_id.and_then(|parent_id| positions.get(&parent_id).copied()),
+                message,
+                metadata,
+            })
+            .collect();
+        Ok((entries, leaf))
+    }
+
     fn save_manifest_rows(
         conn: &Connection,
         session_id: &SessionId,
@@ -931,39 +1412,11 @@ impl MemoryBackend for SqliteMemoryBackend {
     fn load_transcript_messages<'a>(
         &'a self,
         session_id: &'a SessionId,
-    ) -> BoxFuture<'a, Result<Vec<Message>, MemoryError>> {
+    ) -> BoxFuture<'a, Result<Vec<StoredMessage>, MemoryError>> {
         Box::pin(async move {
             let conn = self.connection()?;
-            let mut stmt = conn
-                .prepare(
-                    "
-                    SELECT role, content
-                    FROM transcript_messages
-                    WHERE session_id = ?1
-                    ORDER BY id ASC
-                    ",
-                )
-                .map_err(|error| {
-                    MemoryError::storage(format!("failed to prepare transcript query: {error}"))
-                })?;
-            let rows = stmt
-                .query_map(params![session_id.as_str()], |row| {
-                    Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
-                })
-                .map_err(|error| {
-                    MemoryError::storage(format!("failed to query transcript rows: {error}"))
-                })?;
-            let mut messages = Vec::new();
-            for row in rows {
-                let (role, content) = row.map_err(|error| {
-                    MemoryError::storage(format!("failed to read transcript row: {error}"))
-                })?;
-                messages.push(Message {
-                    role: role_from_str(&role)?,
-                    content,
-                });
-            }
-            Ok(messages)
+            let (entries, leaf) = Self::load_positioned_transcript(&conn, session_id)?;
+            Ok(active_stored_messages(entries, leaf))
         })
     }
 
@@ -971,28 +1424,224 @@ impl MemoryBackend for SqliteMemoryBackend {
         &'a self,
         session_id: &'a SessionId,
         messages: Vec<Message>,
+    ) -> BoxFuture<'a, Result<(), MemoryError>> {
+        let metadata = MessageMetadata::new(SystemTime::now());
+        self.append_transcript_messages_with_metadata(
+            session_id,
+            messages
+                .into_iter()
+                .map(|message| (message, metadata.clone()))
+                .collect(),
+        )
+    }
+
+    fn append_transcript_messages_with_metadata<'a>(
+        &'a self,
+        session_id: &'a SessionId,
+        messages: Vec<(Message, MessageMetadata)>,
+    ) -> BoxFuture<'a, Result<(), MemoryError>> {
+        Box::pin(async move {
+            let conn = self.connection()?;
+            Self::insert_transcript_messages(&conn, session_id, messages)
+        })
+    }
+
+    fn transcript_version<'a>(
+        &'a self,
+        session_id: &'a SessionId,
+    ) -> BoxFuture<'a, Result<Option<u64>, MemoryError>> {
+        Box::pin(async move {
+            let conn = self.connection()?;
+            Self::load_transcript_version(&conn, session_id).map(Some)
+        })
+    }
+
+    fn append_transcript_messages_if_version<'a>(
+        &'a self,
+        session_id: &'a SessionId,
+        expected_version: u64,
+        messages: Vec<(Message, MessageMetadata)>,
+    ) -> BoxFuture<'a, Result<(), MemoryError>> {
+        Box::pin(async move {
+            let mut conn = self.connection()?;
+            // IMMEDIATE takes the write lock up front, so another process cannot append
+            // between the version check and the insert.
+            let transaction = conn
+                .transaction_with_behavior(TransactionBehavior::Immediate)
+                .map_err(|error| {
+                    MemoryError::storage(format!("failed to begin transcript append: {error}"))
+                })?;
+            let version = Self::load_transcript_version(&transaction, session_id)?;
+            check_transcript_version(session_id, expected_version, version)?;
+            Self::insert_transcript_messages(&transaction, session_id, messages)?;
+            transaction.commit().map_err(|error| {
+                MemoryError::storage(format!("failed to commit transcript append: {error}"))
+            })
+        })
+    }
+
+    fn load_transcript_tree<'a>(
+        &'a self,
+        session_id: &'a SessionId,
+    ) -> BoxFuture<'a, Result<ConversationTree, MemoryError>> {
+        Box::pin(async move {
+            let conn = self.connection()?;
+            let (entries, leaf) = Self::load_positioned_transcript(&conn, session_id)?;
+            Ok(build_tree(entries, leaf))
+        })
+    }
+
+    fn set_transcript_active_leaf<'a>(
+        &'a self,
+        session_id: &'a SessionId,
+        leaf: Option<MessageId>,
     ) -> BoxFuture<'a, Result<(), MemoryError>> {
         Box::pin(async move {
             let conn = self.connection()?;
-            for message in messages {
+            let rows = Self::load_transcript_rows(&conn, session_id)?;
+            let leaf = resolve_leaf(leaf.as_ref(), rows.len())?.map(|position| rows[position].0);
+            Self::save_transcript_leaf(&conn, session_id, leaf)
+        })
+    }
+
+    fn list_sessions<'a>(
+        &'a self,
+        page: SessionPageRequest,
+    ) -> BoxFuture<'a, Result<SessionPage, MemoryError>> {
+        Box::pin(async move {
+            let conn = self.connection()?;
+            // One extra row tells whether another page follows.
+            let limit = i64::try_from(page.limit.saturating_add(1)).unwrap_or(i64::MAX);
+            let offset = i64::try_from(page.offset).unwrap_or(i64::MAX);
+            let mut sessions = Self::query_transcript_sessions(
+                &conn,
+                "
+                ORDER BY updated_at_secs DESC, updated_at_nanos DESC, session_id ASC
+                LIMIT ?1 OFFSET ?2
+                ",
+                &[&limit, &offset],
+            )?;
+
+            let has_more = sessions.len() > page.limit;
+            sessions.truncate(page.limit);
+            let next_offset = has_more.then(|| page.offset + sessions.len());
+            Ok(SessionPage {
+                sessions,
+                next_offset,
+            })
+        })
+    }
+
+    fn session_metadata<'a>(
+        &'a self,
+        session_id: &'a SessionId,
+    ) -> BoxFuture<'a, Result<Option<SessionMetadata>, MemoryError>> {
+        Box::pin(async move {
+            let conn = self.connection()?;
+            Ok(Self::query_transcript_sessions(
+                &conn,
+                "WHERE session_id = ?1",
+                &[&session_id.as_str()],
+            )?
+            .pop())
+        })
+    }
+
+    fn rename_session<'a>(
+        &'a self,
+        session_id: &'a SessionId,
+        title: String,
+    ) -> BoxFuture<'a, Result<(), MemoryError>> {
+        Box::pin(async move {
+            let conn = self.connection()?;
+            Self::touch_transcript_session(&conn, session_id, Some(&title))
+        })
+    }
+
+    fn delete_session<'a>(
+        &'a self,
+        session_id: &'a SessionId,
+    ) -> BoxFuture<'a, Result<(), MemoryError>> {
+        Box::pin(async move {
+            let conn = self.connection()?;
+            for table in [
+                "sessions",
+                "session_metadata",
+                "init_plan_steps",
+                "features",
+                "progress_entries",
+                "run_checkpoints",
+                "transcript_messages",
+                "transcript_branches",
+                "transcript_sessions",
+            ] {
                 conn.execute(
-                    "
-                    INSERT INTO transcript_messages (session_id, role, content)
-                    VALUES (?1, ?2, ?3)
-                    ",
-                    params![
-                        session_id.as_str(),
-                        role_to_str(message.role),
-                        message.content
-                    ],
+                    &format!("DELETE FROM {table} WHERE session_id = ?1"),
+                    params![session_id.as_str()],
                 )
                 .map_err(|error| {
-                    MemoryError::storage(format!("failed to append transcript message: {error}"))
+                    MemoryError::storage(format!("failed to delete session rows: {error}"))
                 })?;
             }
             Ok(())
         })
     }
+
+    fn truncate_transcript<'a>(
+        &'a self,
+        session_id: &'a SessionId,
+        keep_messages: usize,
+    ) -> BoxFuture<'a, Result<(), MemoryError>> {
+        Box::pin(async move {
+            let conn = self.connection()?;
+            let rows = Self::load_transcript_rows(&conn, session_id)?;
+            if rows.is_empty() {
+                return Ok(());
+            }
+            let leaf = Self::active_transcript_leaf(&conn, session_id)?;
+            let positions = rows
+                .iter()
+                .enumerate()
+                .map(|(position, (id, _, _, _))| (*id, position))
+                .collect::<HashMap<_, _>>();
+            let parents = rows
+                .iter()
+                .map(|(_, parent_id, _, _)| {
+                    parent_id.and_then(|parent| positions.get(&parent).copied())
+                })
+                .collect::<Vec<_>>();
+            let ids = rows.iter().map(|(id, _, _, _)| *id).collect::<Vec<_>>();
+            let leaf = leaf.and_then(|leaf| positions.get(&leaf).copied());
+            let kept = take_active(ids.clone(), &parents, leaf, keep_messages);
+
+            // The kept prefix already links each message to the one before it.
+            for id in ids.into_iter().filter(|id| !kept.contains(id)) {
+                conn.execute("DELETE FROM transcript_messages WHERE id = ?1", params![id])
+                    .map_err(|error| {
+                        MemoryError::storage(format!("failed to truncate transcript: {error}"))
+                    })?;
+            }
+            Self::save_transcript_leaf(&conn, session_id, kept.last().copied())?;
+            Self::touch_transcript_session(&conn, session_id, None)
+        })
+    }
+}
+
+/// Token counts stored as `input, output, total, cached input`; `NULL` input means no usage.
+fn decode_token_usage(values: [Option<i64>; 4]) -> Result<Option<TokenUsage>, MemoryError> {
+    let [Some(input), output, total, cached] = values else {
+        return Ok(None);
+    };
+    let count = |value: Option<i64>| {
+        u32::try_from(value.unwrap_or_default())
+            .map_err(|error| MemoryError::storage(format!("invalid stored token count: {error}")))
+    };
+    Ok(Some(TokenUsage {
+        input_tokens: count(Some(input))?,
+        output_tokens: count(output)?,
+        total_tokens: count(total)?,
+        cached_input_tokens: count(cached)?,
+    }))
 }
 
 fn encode_system_time(value: SystemTime) -> Result<(i64, i64), MemoryError> {
@@ -0,0 +1,165 @@
+//! Transcript tree helpers for backends that address messages by their append position.
+//!
+//! Message ids are the 1-based append position, and each message stores the position of its
+//! parent. Transcripts written before branching existed carry no parent links and are read as a
+//! single chain ending at the last message.
+
+use std::time::SystemTime;
+
+use fchat::{
+    ConversationTree, MessageId, MessageMetadata, MessageNode, SessionMetadata, StoredMessage,
+};
+use fcommon::SessionId;
+use fprovider::{Message, ProviderId};
+
+use crate::error::MemoryError;
+
+/// A stored message with the position of its parent.
+#[derive(Debug, Clone)]
+pub(crate) struct TranscriptEntry {
+    pub(crate) parent: Option<usize>,
+    pub(crate) message: Message,
+    pub(crate) metadata: MessageMetadata,
+}
+
+/// Messages in append order.
+pub(crate) type PositionedTranscript = Vec<TranscriptEntry>;
+
+pub(crate) fn index_message_id(index: usize) -> MessageId {
+    MessageId::new((index + 1).to_string())
+}
+
+/// Resolves a requested leaf to a position, rejecting ids outside the transcript.
+pub(crate) fn resolve_leaf(
+    leaf: Option<&MessageId>,
+    len: usize,
+) -> Result<Option<usize>, MemoryError> {
+    let Some(leaf) = leaf else {
+        return Ok(None);
+    };
+
+    match leaf.as_str().parse::<usize>() {
+        Ok(position) if (1..=len).contains(&position) => Ok(Some(position - 1)),
+        _ => Err(MemoryError::not_found(format!(
+            "transcript message '{leaf}' does not exist"
+        ))),
+    }
+}
+
+/// Positions on the path from the root to `leaf`.
+pub(crate) fn active_indices(parents: &[Option<usize>], leaf: Option<usize>) -> Vec<usize> {
+    let mut path = Vec::new();
+    let mut next = leaf;
+    while let Some(index) = next {
+        if index >= parents.len() || path.len() > parents.len() {
+            return Vec::new();
+        }
+        path.push(index);
+        next = parents[index];
+    }
+    path.reverse();
+    path
+}
+
+/// The first `keep` items on the active path, in path order.
+pub(crate) fn take_active<T>(
+    items: Vec<T>,
+    parents: &[Option<usize>],
+    leaf: Option<usize>,
+    keep: usize,
+) -> Vec<T> {
+    let mut items = items.into_iter().map(Some).collect::<Vec<_>>();
+    active_indices(parents, leaf)
+        .into_iter()
+        .take(keep)
+        .filter_map(|index| items.get_mut(index).and_then(Option::take))
+        .collect()
+}
+
+pub(crate) fn build_tree(
+    entries: impl IntoIterator<Item = TranscriptEntry>,
+    active_leaf: Option<usize>,
+) -> ConversationTree {
+    let nodes = entries
+        .into_iter()
+        .enumerate()
+        .map(|(index, entry)| {
+            MessageNode::new(
+                index_message_id(index),
+                entry.parent.map(index_message_id),
+                entry.message,
+            )
+            .with_metadata(entry.metadata)
+        })
+        .collect();
+    ConversationTree::new(nodes, active_leaf.map(index_message_id))
+}
+
+/// The active branch of `entries` with position ids.
+pub(crate) fn active_stored_messages(
+    entries: PositionedTranscript,
+    leaf: Option<usize>,
+) -> Vec<StoredMessage> {
+    let parents = entries.iter().map(|entry| entry.parent).collect::<Vec<_>>();
+    take_active(
+        entries.into_iter().enumerate().collect(),
+        &parents,
+        leaf,
+        usize::MAX,
+    )
+    .into_iter()
+    .map(|(index, entry)| {
+        StoredMessage::new(index_message_id(index), entry.message).with_metadata(entry.metadata)
+    })
+    .collect()
+}
+
+/// Parent links for a pre-branching transcript of `len` messages: each follows the previous.
+pub(crate) fn linear_parents(len: usize) -> impl Iterator<Item = Option<usize>> {
+    (0..len).map(|index| index.checked_sub(1))
+}
+
+pub(crate) fn parse_provider(value: &str) -> Result<ProviderId, MemoryError> {
+    [
+        ProviderId::OpenCodeZen,
+        ProviderId::OpenAi,
+        ProviderId::Anthropic,
+        ProviderId::Ollama,
+        ProviderId::Gemini,
+    ]
+    .into_iter()
+    .find(|provider| provider.to_string() == value)
+    .ok_or_else(|| MemoryError::storage(format!("unknown transcript provider '{value}'")))
+}
+
+/// Title and timestamps of a session that has held a transcript or a title.
+#[derive(Debug, Clone)]
+pub(crate) struct ConversationRecord {
+    pub(crate) title: Option<String>,
+    pub(crate) created_at: SystemTime,
+    pub(crate) updated_at: SystemTime,
+}
+
+impl ConversationRecord {
+    /// Marks `record` as updated now, creating it on first use.
+    pub(crate) fn touch(record: &mut Option<Self>) -> &mut Self {
+        let now = SystemTime::now();
+        let record = record.get_or_insert_with(|| Self {
+            title: None,
+            created_at: now,
+            updated_at: now,
+        });
+        record.updated_at = now;
+        record
+    }
+
+    pub(crate) fn metadata(&self, session_id: &SessionId, message_count: usize) -> SessionMetadata {
+        let metadata = SessionMetadata::new(session_id.clone(), self.created_at)
+            .with_updated_at(self.updated_at)
+            .with_message_count(message_count);
+        match &self.title {
+            Some(title) => metadata.with_title(title.clone()),
+            None => metadata,
+        }
+    }
+}
@@ -16,6 +16,8 @@ pub enum MemoryErrorKind {
     Storage,
     NotFound,
     InvalidRequest,
+    /// A conditional write found the transcript changed since it was read.
+    Conflict,
     Other,
 }
 
@@ -45,6 +47,10 @@ impl MemoryError {
         Self::new(MemoryErrorKind::InvalidRequest, message)
     }
 
+    pub fn conflict(message: impl Into<String>) -> Self {
+        Self::new(MemoryErrorKind::Conflict, message)
+    }
+
     pub fn other(message: impl Into<String>) -> Self {
         Self::new(MemoryErrorKind::Other, message)
     }
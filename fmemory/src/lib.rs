@@ -45,9 +45,12 @@ pub use types::{
 mod tests {
     use std::sync::Arc;
 
-    use fchat::ConversationStore;
+    use fchat::{
+        ChatErrorKind, ChatErrorPhase, ConversationStore, MessageId, MessageMetadata,
+        SessionPageRequest,
+    };
     use fcommon::SessionId;
-    use fprovider::{Message, Role};
+    use fprovider::{Message, ProviderId, Role, TokenUsage};
 
     use crate::types::{FeatureRecord, ProgressEntry, RunCheckpoint, SessionManifest};
     use crate::{
@@ -153,6 +156,423 @@ mod tests {
         assert_eq!(loaded[1].role, Role::Assistant);
     }
 
+    async fn assert_branching(store: MemoryConversationStore) {
+        let session_id = SessionId::from("session-branching");
+        store
+            .append_messages(
+                &session_id,
+                vec![
+                    Message::new(Role::User, "hello"),
+                    Message::new(Role::Assistant, "first answer"),
+                ],
+            )
+            .await
+            .expect("append should work");
+        store
+            .set_active_leaf(&session_id, Some(MessageId::from("1")))
+            .await
+            .expect("leaf should move");
+        store
+            .append_messages(
+                &session_id,
+                vec![Message::new(Role::Assistant, "second answer")],
+            )
+            .await
+            .expect("append should work");
+
+        let tree = store
+            .load_tree(&session_id)
+            .await
+            .expect("tree should load");
+        assert_eq!(tree.nodes.len(), 3);
+        assert_eq!(tree.children(Some(&MessageId::from("1"))).len(), 2);
+        let loaded = store
+            .load_messages(&session_id)
+            .await
+            .expect("load should work");
+        assert_eq!(loaded[1].content, "second answer");
+
+        store
+            .set_active_leaf(&session_id, Some(MessageId::from("2")))
+            .await
+            .expect("leaf should move");
+        let loaded = store
+            .load_messages(&session_id)
+            .await
+            .expect("load should work");
+        assert_eq!(loaded.len(), 2);
+        assert_eq!(loaded[1].content, "first answer");
+        assert!(
+            store
+                .set_active_leaf(&session_id, Some(MessageId::from("9")))
+                .await
+                .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn backends_branch_transcripts_through_the_conversation_store() {
+        assert_branching(MemoryConversationStore::new(Arc::new(
+            InMemoryMemoryBackend::new(),
+        )))
+        .await;
+        assert_branching(MemoryConversationStore::new(Arc::new(
+            SqliteMemoryBackend::new_in_memory().expect("sqlite backend should initialize"),
+        )))
+        .await;
+
+        let root = temp_dir("branching");
+        assert_branching(MemoryConversationStore::new(Arc::new(
+            FilesystemMemoryBackend::new(&root).expect("fs backend should initialize"),
+        )))
+        .await;
+        std::fs::remove_dir_all(&root).expect("temporary directory should be removable");
+    }
+
+    async fn assert_session_management(store: MemoryConversationStore) {
+        let alpha = SessionId::from("alpha");
+        let beta = SessionId::from("beta");
+        store
+            .append_messages(
+                &alpha,
+                vec![
+                    Message::new(Role::User, "hello"),
+                    Message::new(Role::Assistant, "first answer"),
+                ],
+            )
+            .await
+            .expect("append should work");
+        store
+            .set_active_leaf(&alpha, Some(MessageId::from("1")))
+            .await
+            .expect("leaf should move");
+        store
+            .append_messages(&alpha, vec![Message::new(Role::Assistant, "second answer")])
+            .await
+            .expect("append should work");
+        std::thread::sleep(std::time::Duration::from_millis(2));
+        store
+            .rename_session(&beta, "Second".to_string())
+            .await
+            .expect("rename should work");
+
+        let page = store
+            .list_sessions(SessionPageRequest::new(1))
+            .await
+            .expect("list should work");
+        assert_eq!(page.sessions.len(), 1);
+        assert_eq!(page.sessions[0].session_id, beta);
+        assert_eq!(page.sessions[0].title.as_deref(), Some("Second"));
+        assert_eq!(page.next_offset, Some(1));
+        let page = store
+            .list_sessions(SessionPageRequest::new(1).with_offset(1))
+            .await
+            .expect("list should work");
+        assert_eq!(page.sessions[0].session_id, alpha);
+        assert_eq!(page.sessions[0].message_count, 3);
+        assert_eq!(page.next_offset, None);
+
+        store
+            .truncate_session(&alpha, 1)
+            .await
+            .expect("truncate should work");
+        let tree = store.load_tree(&alpha).await.expect("tree should load");
+        assert_eq!(tree.nodes.len(), 1);
+        assert_eq!(tree.active_leaf, Some(MessageId::from("1")));
+        store
+            .append_messages(&alpha, vec![Message::new(Role::Assistant, "again")])
+            .await
+            .expect("append should work");
+        let loaded = store.load_messages(&alpha).await.expect("load should work");
+        assert_eq!(
+            loaded
+                .iter()
+                .map(|message| message.content.as_str())
+                .collect::<Vec<_>>(),
+            vec!["hello", "again"]
+        );
+
+        store
+            .delete_session(&beta)
+            .await
+            .expect("delete should work");
+        assert!(
+            store
+                .session_metadata(&beta)
+                .await
+                .expect("metadata should load")
+                .is_none()
+        );
+        let page = store
+            .list_sessions(SessionPageRequest::default())
+            .await
+            .expect("list should work");
+        assert_eq!(page.sessions.len(), 1);
+        assert_eq!(page.sessions[0].message_count, 2);
+    }
+
+    #[tokio::test]
+    async fn backends_list_rename_truncate_and_delete_sessions() {
+        assert_session_management(MemoryConversationStore::new(Arc::new(
+            InMemoryMemoryBackend::new(),
+        )))
+        .await;
+        assert_session_management(MemoryConversationStore::new(Arc::new(
+            SqliteMemoryBackend::new_in_memory().expect("sqlite backend should initialize"),
+        )))
+        .await;
+
+        let root = temp_dir("sessions");
+        assert_session_management(MemoryConversationStore::new(Arc::new(
+            FilesystemMemoryBackend::new(&root).expect("fs backend should initialize"),
+        )))
+        .await;
+        std::fs::remove_dir_all(&root).expect("temporary directory should be removable");
+    }
+
+    async fn assert_message_metadata(backend: Arc<dyn MemoryBackend>) {
+        let session_id = SessionId::from("session-metadata");
+        let created_at = std::time::UNIX_EPOCH + std::time::Duration::new(1_700_000_000, 42);
+        let usage = TokenUsage {
+            input_tokens: 12,
+            output_tokens: 3,
+            total_tokens: 15,
+            cached_input_tokens: 8,
+        };
+        backend
+            .append_transcript_messages_with_metadata(
+                &session_id,
+                vec![
+                    (
+                        Message::new(Role::User, "hello"),
+                        MessageMetadata::new(created_at),
+                    ),
+                    (
+                        Message::new(Role::Assistant, "hi"),
+                        MessageMetadata::new(created_at)
+                            .with_model(ProviderId::Anthropic, "claude-sonnet")
+                            .with_usage(usage),
+                    ),
+                ],
+            )
+            .await
+            .expect("append should work");
+        backend
+            .append_transcript_messages(&session_id, vec![Message::new(Role::User, "again")])
+            .await
+            .expect("append should work");
+
+        let transcript = backend
+            .load_transcript_messages(&session_id)
+            .await
+            .expect("transcript should load");
+        assert_eq!(transcript.len(), 3);
+        assert_eq!(transcript[0].metadata, MessageMetadata::new(created_at));
+        assert_eq!(transcript[1].id, MessageId::from("2"));
+        assert_eq!(transcript[1].metadata.provider, Some(ProviderId::Anthropic));
+        assert_eq!(
+            transcript[1].metadata.model.as_deref(),
+            Some("claude-sonnet")
+        );
+        assert_eq!(transcript[1].metadata.usage, Some(usage));
+        assert!(transcript[2].metadata.created_at.is_some());
+        assert_eq!(transcript[2].metadata.usage, None);
+
+        let tree = backend
+            .load_transcript_tree(&session_id)
+            .await
+            .expect("tree should load");
+        assert_eq!(tree.nodes[1].metadata, transcript[1].metadata);
+    }
+
+    #[tokio::test]
+    async fn backends_store_message_metadata() {
+        assert_message_metadata(Arc::new(InMemoryMemoryBackend::new())).await;
+        assert_message_metadata(Arc::new(
+            SqliteMemoryBackend::new_in_memory().expect("sqlite backend should initialize"),
+        ))
+        .await;
+
+        let root = temp_dir("metadata");
+        assert_message_metadata(Arc::new(
+            FilesystemMemoryBackend::new(&root).expect("fs backend should initialize"),
+        ))
+        .await;
+        std::fs::remove_dir_all(&root).expect("temporary directory should be removable");
+    }
+
+    async fn assert_conditional_appends(store: MemoryConversationStore) {
+        let session_id = SessionId::from("session-conditional");
+        let message = |content: &str| {
+            vec![(
+                Message::new(Role::User, content),
+                MessageMetadata::default(),
+            )]
+        };
+
+        let version = store
+            .session_version(&session_id)
+            .await
+            .expect("version should load")
+            .expect("store should be versioned");
+        assert_eq!(version, 0);
+        store
+            .append_messages_if_version(&session_id, version, message("first"))
+            .await
+            .expect("append at the current version should work");
+
+        let error = store
+            .append_messages_if_version(&session_id, version, message("stale"))
+            .await
+            .expect_err("append at a stale version should fail");
+        assert_eq!(error.kind, ChatErrorKind::Conflict);
+        assert_eq!(error.phase, Some(ChatErrorPhase::Storage));
+        assert!(error.is_retryable());
+
+        let version = store
+            .session_version(&session_id)
+            .await
+            .expect("version should load")
+            .expect("store should be versioned");
+        store
+            .append_messages_if_version(&session_id, version, message("second"))
+            .await
+            .expect("append at the refreshed version should work");
+        let messages = store
+            .load_messages(&session_id)
+            .await
+            .expect("messages should load");
+        assert_eq!(
+            messages,
+            vec![
+                Message::new(Role::User, "first"),
+                Message::new(Role::User, "second"),
+            ]
+        );
+
+        // Truncating and appending back to the same message count still changes the version.
+        let stale = store
+            .session_version(&session_id)
+            .await
+            .expect("version should load")
+            .expect("store should be versioned");
+        store
+            .truncate_session(&session_id, 1)
+            .await
+            .expect("truncate should work");
+        store
+            .append_messages(&session_id, vec![Message::new(Role::User, "other")])
+            .await
+            .expect("append should work");
+        let error = store
+            .append_messages_if_version(&session_id, stale, message("stale"))
+            .await
+            .expect_err("append from before the truncation should fail");
+        assert_eq!(error.kind, ChatErrorKind::Conflict);
+
+        let stale = store
+            .session_version(&session_id)
+            .await
+            .expect("version should load")
+            .expect("store should be versioned");
+        let tree = store
+            .load_tree(&session_id)
+            .await
+            .expect("tree should load");
+        store
+            .set_active_leaf(&session_id, tree.active_leaf.clone())
+            .await
+            .expect("moving the leaf should work");
+        let error = store
+            .append_messages_if_version(&session_id, stale, message("stale"))
+            .await
+            .expect_err("append from before the leaf moved should fail");
+        assert_eq!(error.kind, ChatErrorKind::Conflict);
+    }
+
+    #[tokio::test]
+    async fn backends_reject_appends_from_a_stale_session_version() {
+        assert_conditional_appends(MemoryConversationStore::new(Arc::new(
+            InMemoryMemoryBackend::new(),
+        )))
+        .await;
+        assert_conditional_appends(MemoryConversationStore::new(Arc::new(
+            SqliteMemoryBackend::new_in_memory().expect("sqlite backend should initialize"),
+        )))
+        .await;
+
+        let root = temp_dir("conditional");
+        assert_conditional_appends(MemoryConversationStore::new(Arc::new(
+            FilesystemMemoryBackend::new(&root).expect("fs backend should initialize"),
+        )))
+        .await;
+        std::fs::remove_dir_all(&root).expect("temporary directory should be removable");
+    }
+
+    #[tokio::test]
+    async fn transcripts_written_before_branching_load_as_a_single_chain() {
+        let root = temp_dir("legacy");
+        let sqlite_path = root.join("legacy.db");
+        std::fs::create_dir_all(root.join("sessions")).expect("root should be creatable");
+        {
+            let conn = rusqlite::Connection::open(&sqlite_path).expect("sqlite should open");
+            conn.execute_batch(
+                "
+                CREATE TABLE transcript_messages (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    session_id TEXT NOT NULL,
+                    role TEXT NOT NULL,
+                    content TEXT NOT NULL
+                );
+                INSERT INTO transcript_messages (session_id, role, content)
+                VALUES ('legacy', 'user', 'hello'), ('legacy', 'assistant', 'hi');
+                ",
+            )
+            .expect("legacy schema should be created");
+        }
+        std::fs::write(
+            root.join("sessions").join("6c6567616379.json"),
+            r#"{"manifest":null,"feature_list":[],"recent_progress":[],"checkpoints":[],
+            "transcript":[{"role":"user","content":"hello"},{"role":"assistant","content":"hi"}]}"#,
+        )
+        .expect("legacy state should be written");
+
+        let session_id = SessionId::from("legacy");
+        let backends: Vec<Arc<dyn MemoryBackend>> = vec![
+            Arc::new(SqliteMemoryBackend::new(&sqlite_path).expect("sqlite should migrate")),
+            Arc::new(FilesystemMemoryBackend::new(&root).expect("fs backend should initialize")),
+        ];
+        for backend in backends {
+            let metadata = backend
+                .session_metadata(&session_id)
+                .await
+                .expect("metadata should load")
+                .expect("legacy session should be listed");
+            assert_eq!(metadata.message_count, 2);
+
+            let tree = backend
+                .load_transcript_tree(&session_id)
+                .await
+                .expect("tree should load");
+            assert_eq!(tree.active_leaf, Some(MessageId::from("2")));
+            assert_eq!(tree.nodes[1].parent_id, Some(MessageId::from("1")));
+
+            backend
+                .append_transcript_messages(&session_id, vec![Message::new(Role::User, "again")])
+                .await
+                .expect("append should work");
+            let transcript = backend
+                .load_transcript_messages(&session_id)
+                .await
+                .expect("transcript should load");
+            assert_eq!(transcript.len(), 3);
+            assert_eq!(transcript[0].metadata, MessageMetadata::default());
+            assert!(transcript[2].metadata.created_at.is_some());
+        }
+
+        std::fs::remove_dir_all(&root).expect("temporary directory should be removable");
+    }
+
     #[tokio::test]
     async fn update_feature_pass_fails_for_unknown_feature() {
         let backend = InMemoryMemoryBackend::new();
@@ -313,8 +733,8 @@ mod tests {
             .await
             .expect("transcript should load");
         assert_eq!(transcript.len(), 2);
-        assert_eq!(transcript[0].role, Role::User);
-        assert_eq!(transcript[1].role, Role::Assistant);
+        assert_eq!(transcript[0].message.role, Role::User);
+        assert_eq!(transcript[1].message.role, Role::Assistant);
     }
 
     #[tokio::test]
@@ -383,8 +803,8 @@ mod tests {
             .await
             .expect("transcript should load");
         assert_eq!(transcript.len(), 2);
-        assert_eq!(transcript[0].role, Role::User);
-        assert_eq!(transcript[1].role, Role::Assistant);
+        assert_eq!(transcript[0].message.role, Role::User);
+        assert_eq!(transcript[1].message.role, Role::Assistant);
 
         std::fs::remove_dir_all(&root).expect("temporary directory should be removable");
     }
@@ -492,7 +912,46 @@ mod tests {
             .await
             .expect("transcript should load");
         assert_eq!(transcript.len(), 2);
-        assert_eq!(transcript[0].role, Role::User);
-        assert_eq!(transcript[1].role, Role::Assistant);
+        assert_eq!(transcript[0].message.role, Role::User);
+        assert_eq!(transcript[1].message.role, Role::Assistant);
+
+        // Concurrent writers to one session must not overwrite each other's changes.
+        let backend = Arc::new(backend);
+        let writers = (0..8)
+            .map(|index| {
+                let backend = Arc::clone(&backend);
+                let session_id = session_id.clone();
+                tokio::spawn(async move {
+                    backend
+                        .append_progress_entry(
+                            &session_id,
+                            ProgressEntry::new("run-postgres-1", format!("writer {index}")),
+                        )
+                        .await
+                        .expect("progress should append");
+                    backend
+                        .append_transcript_messages(
+                            &session_id,
+                            vec![Message::new(Role::User, format!("writer {index}"))],
+                        )
+                        .await
+                        .expect("transcript should append");
+                })
+            })
+            .collect::<Vec<_>>();
+        for writer in writers {
+            writer.await.expect("writer should finish");
+        }
+
+        let bootstrap = backend
+            .load_bootstrap_state(&session_id)
+            .await
+            .expect("bootstrap should load");
+        assert_eq!(bootstrap.recent_progress.len(), 9);
+        let transcript = backend
+            .load_transcript_messages(&session_id)
+            .await
+            .expect("transcript should load");
+        assert_eq!(transcript.len(), 10);
     }
 }
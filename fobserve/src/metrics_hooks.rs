@@ -88,6 +88,23 @@ impl ProviderOperationHooks for MetricsObservabilityHooks {
         )
         .record(attempts as f64);
     }
+    fn on_cache_hit(&self, provider: ProviderId, operation: &str) {
+        metrics::counter!(
+            "fiddlesticks_provider_cache_hit_total",
+            "provider" => provider.to_string(),
+            "operation" => operation.to_string()
+        )
+        .increment(1);
+    }
+
+    fn on_cache_miss(&self, provider: ProviderId, operation: &str) {
+        metrics::counter!(
+            "fiddlesticks_provider_cache_miss_total",
+            "provider" => provider.to_string(),
+            "operation" => operation.to_string()
+        )
+        .increment(1);
+    }
 }
 
 impl ToolRuntimeHooks for MetricsObservabilityHooks {
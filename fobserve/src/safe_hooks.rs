@@ -68,6 +68,17 @@ where
             self.inner.on_failure(provider, operation, attempts, error)
         }));
     }
+    fn on_cache_hit(&self, provider: ProviderId, operation: &str) {
+        let _ = catch_unwind(AssertUnwindSafe(|| {
+            self.inner.on_cache_hit(provider, operation)
+        }));
+    }
+
+    fn on_cache_miss(&self, provider: ProviderId, operation: &str) {
+        let _ = catch_unwind(AssertUnwindSafe(|| {
+            self.inner.on_cache_miss(provider, operation)
+        }));
+    }
 }
 
 pub struct SafeToolHooks<H> {
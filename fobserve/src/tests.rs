@@ -3,7 +3,10 @@ use std::time::Duration;
 
 use fcommon::SessionId;
 use fharness::{HarnessError, HarnessPhase, HarnessRuntimeHooks};
-use fprovider::{ProviderError, ProviderId, ProviderOperationHooks, ToolCall};
+use fprovider::{
+    Message, ModelRequest, ProviderError, ProviderId, ProviderInterceptor, ProviderOperationHooks,
+    Role, ToolCall,
+};
 use ftooling::{ToolError, ToolExecutionContext, ToolExecutionResult, ToolRuntimeHooks};
 
 use crate::{
@@ -40,6 +43,13 @@ fn tracing_hooks_smoke_test_all_callbacks() {
     );
     hooks.on_success(ProviderId::OpenAi, "complete", 2);
     hooks.on_failure(ProviderId::OpenAi, "complete", 2, &provider_error);
+    hooks.on_cache_hit(ProviderId::OpenAi, "complete");
+    hooks.on_cache_miss(ProviderId::OpenAi, "stream");
+    hooks.on_request(
+        ProviderId::OpenAi,
+        &mut ModelRequest::new("gpt-4o-mini", vec![Message::new(Role::User, "hi")]),
+    );
+    hooks.on_error(ProviderId::OpenAi, &provider_error);
 
     hooks.on_execution_start(&sample_tool_call(), &sample_tool_context());
     hooks.on_execution_success(
@@ -92,6 +102,8 @@ fn metrics_hooks_smoke_test_all_callbacks() {
     );
     hooks.on_success(ProviderId::OpenAi, "complete", 2);
     hooks.on_failure(ProviderId::OpenAi, "complete", 2, &provider_error);
+    hooks.on_cache_hit(ProviderId::OpenAi, "complete");
+    hooks.on_cache_miss(ProviderId::OpenAi, "stream");
 
     hooks.on_execution_start(&sample_tool_call(), &sample_tool_context());
     hooks.on_execution_success(
@@ -167,6 +179,14 @@ impl ProviderOperationHooks for RecordingProviderHooks {
     ) {
         self.events.lock().expect("events lock").push("failure");
     }
+
+    fn on_cache_hit(&self, _provider: ProviderId, _operation: &str) {
+        self.events.lock().expect("events lock").push("cache_hit");
+    }
+
+    fn on_cache_miss(&self, _provider: ProviderId, _operation: &str) {
+        self.events.lock().expect("events lock").push("cache_miss");
+    }
 }
 
 #[derive(Default, Clone)]
@@ -263,6 +283,14 @@ impl ProviderOperationHooks for PanicProviderHooks {
     ) {
         panic!("failure panic");
     }
+
+    fn on_cache_hit(&self, _provider: ProviderId, _operation: &str) {
+        panic!("cache_hit panic");
+    }
+
+    fn on_cache_miss(&self, _provider: ProviderId, _operation: &str) {
+        panic!("cache_miss panic");
+    }
 }
 
 struct PanicToolHooks;
@@ -339,8 +367,10 @@ fn safe_provider_hooks_delegate_when_inner_succeeds() {
     );
     hooks.on_success(ProviderId::OpenAi, "complete", 2);
     hooks.on_failure(ProviderId::OpenAi, "complete", 2, &provider_error);
+    hooks.on_cache_hit(ProviderId::OpenAi, "complete");
+    hooks.on_cache_miss(ProviderId::OpenAi, "complete");
 
-    assert_eq!(events.lock().expect("events lock").len(), 4);
+    assert_eq!(events.lock().expect("events lock").len(), 6);
 }
 
 #[test]
@@ -411,6 +441,8 @@ fn safe_provider_hooks_swallow_panics() {
     );
     hooks.on_success(ProviderId::OpenAi, "complete", 2);
     hooks.on_failure(ProviderId::OpenAi, "complete", 2, &provider_error);
+    hooks.on_cache_hit(ProviderId::OpenAi, "complete");
+    hooks.on_cache_miss(ProviderId::OpenAi, "complete");
 }
 
 #[test]
@@ -13,7 +13,10 @@
 use std::time::Duration;
 
 use fharness::{HarnessError, HarnessPhase, HarnessRuntimeHooks};
-use fprovider::{ProviderError, ProviderId, ProviderOperationHooks};
+use fprovider::{
+    ModelRequest, ModelResponse, ProviderError, ProviderId, ProviderInterceptor,
+    ProviderOperationHooks,
+};
 use ftooling::{ToolError, ToolExecutionContext, ToolExecutionResult, ToolRuntimeHooks};
 
 #[derive(Debug, Clone, Copy, Default)]
@@ -79,6 +82,62 @@ impl ProviderOperationHooks for TracingObservabilityHooks {
             error = %error
         );
     }
+
+    fn on_cache_hit(&self, provider: ProviderId, operation: &str) {
+        tracing::debug!(
+            phase = "provider",
+            event = "cache_hit",
+            provider = %provider,
+            operation
+        );
+    }
+
+    fn on_cache_miss(&self, provider: ProviderId, operation: &str) {
+        tracing::debug!(
+            phase = "provider",
+            event = "cache_miss",
+            provider = %provider,
+            operation
+        );
+    }
+}
+
+/// Logs request/response shape only; message content is never recorded.
+impl ProviderInterceptor for TracingObservabilityHooks {
+    fn on_request(&self, provider: ProviderId, request: &mut ModelRequest) {
+        tracing::debug!(
+            phase = "provider",
+            event = "request",
+            provider = %provider,
+            model = request.model,
+            messages = request.messages.len(),
+            tools = request.tools.len(),
+            stream = request.options.stream
+        );
+    }
+
+    fn on_response(&self, provider: ProviderId, response: &mut ModelResponse) {
+        tracing::debug!(
+            phase = "provider",
+            event = "response",
+            provider = %provider,
+            model = response.model,
+            stop_reason = ?response.stop_reason,
+            input_tokens = response.usage.input_tokens,
+            output_tokens = response.usage.output_tokens
+        );
+    }
+
+    fn on_error(&self, provider: ProviderId, error: &ProviderError) {
+        tracing::warn!(
+            phase = "provider",
+            event = "error",
+            provider = %provider,
+            error_kind = ?error.kind,
+            retryable = error.retryable,
+            error = %error
+        );
+    }
 }
 
 impl ToolRuntimeHooks for TracingObservabilityHooks {
@@ -1,6 +1,7 @@
 //! Anthropic provider implemented over OpenAI-compatible transport.
 
 use std::sync::Arc;
+use std::time::Instant;
 
 use async_stream::try_stream;
 use futures_util::StreamExt;
@@ -11,8 +12,9 @@ use crate::adapters::openai::{
     OpenAiTransport,
 };
 use crate::{
-    BoxedEventStream, Message, ModelProvider, ModelRequest, ModelResponse, ProviderError,
-    ProviderFuture, ProviderId, Role, SecureCredentialManager, StreamEvent,
+    BoxedEventStream, Message, ModelInfo, ModelProvider, ModelRequest, ModelResponse,
+    ProviderError, ProviderFuture, ProviderHealth, ProviderId, Role, SecureCredentialManager,
+    StreamEvent, health::model_listing_health,
 };
 
 pub const ANTHROPIC_BASE_URL: &str = "https://api.anthropic.com/v1";
@@ -42,7 +44,9 @@ impl AnthropicProvider {
     }
 
     pub fn default_http_transport(client: Client) -> OpenAiHttpTransport {
-        OpenAiHttpTransport::new(client).with_base_url(ANTHROPIC_BASE_URL)
+        OpenAiHttpTransport::new(client)
+            .with_base_url(ANTHROPIC_BASE_URL)
+            .with_stream_usage(true)
     }
 
     fn build_request(&self, request: ModelRequest, stream: bool) -> OpenAiRequest {
@@ -75,6 +79,7 @@ impl AnthropicProvider {
             temperature: request.options.temperature,
             max_tokens: request.options.max_tokens,
             stream,
+            continuation: None,
         }
     }
 }
@@ -90,9 +95,16 @@ impl ModelProvider for AnthropicProvider {
     ) -> ProviderFuture<'a, Result<ModelResponse, ProviderError>> {
         Box::pin(async move {
             request.validate()?;
-            let auth = OpenAiAuth::ApiKey(resolve_anthropic_api_key(&self.credentials)?);
+            let api_key = resolve_anthropic_api_key(&self.credentials)?;
             let anthropic_request = self.build_request(request, false);
-            let response = self.transport.complete(anthropic_request, auth).await?;
+            let response = self
+                .transport
+                .complete(anthropic_request, OpenAiAuth::ApiKey(api_key.clone()))
+                .await
+                .inspect_err(|err| {
+                    self.credentials
+                        .observe_error(ProviderId::Anthropic, api_key.expose(), err)
+                })?;
 
             let mut mapped = response.into_model_response();
             mapped.provider = ProviderId::Anthropic;
@@ -106,19 +118,58 @@ impl ModelProvider for AnthropicProvider {
     ) -> ProviderFuture<'a, Result<BoxedEventStream<'a>, ProviderError>> {
         Box::pin(async move {
             request.validate()?;
-            let auth = OpenAiAuth::ApiKey(resolve_anthropic_api_key(&self.credentials)?);
+            let api_key = resolve_anthropic_api_key(&self.credentials)?;
             let anthropic_request = self.build_request(request, true);
-            let mut chunks = self.transport.stream(anthropic_request, auth).await?;
+            let auth = OpenAiAuth::ApiKey(api_key.clone());
+            let observe = move |err: &ProviderError| {
+                self.credentials
+                    .observe_error(ProviderId::Anthropic, api_key.expose(), err)
+            };
+            let mut chunks = self
+                .transport
+                .stream(anthropic_request, auth)
+                .await
+                .inspect_err(&observe)?;
 
             let stream = try_stream! {
                 while let Some(chunk) = chunks.next().await {
-                    yield map_stream_chunk(chunk?);
+                    yield map_stream_chunk(chunk.inspect_err(&observe)?);
                 }
             };
 
             Ok(Box::pin(stream) as BoxedEventStream<'a>)
         })
     }
+
+    fn health<'a>(&'a self) -> ProviderFuture<'a, ProviderHealth> {
+        Box::pin(async move {
+            let started = Instant::now();
+            model_listing_health(ProviderId::Anthropic, started, self.list_models().await)
+        })
+    }
+
+    fn list_models<'a>(&'a self) -> ProviderFuture<'a, Result<Vec<ModelInfo>, ProviderError>> {
+        Box::pin(async move {
+            let api_key = resolve_anthropic_api_key(&self.credentials)?;
+            let models = self
+                .transport
+                .list_models(OpenAiAuth::ApiKey(api_key.clone()))
+                .await
+                .inspect_err(|err| {
+                    self.credentials
+                        .observe_error(ProviderId::Anthropic, api_key.expose(), err)
+                })?;
+
+            Ok(models
+                .into_iter()
+                .map(|model| ModelInfo {
+                    provider: ProviderId::Anthropic,
+                    owner: model.owner.or_else(|| Some("anthropic".to_string())),
+                    ..model
+                })
+                .collect())
+        })
+    }
 }
 
 impl SecureCredentialManager {
@@ -154,5 +205,6 @@ fn map_stream_chunk(chunk: OpenAiStreamChunk) -> StreamEvent {
             mapped.provider = ProviderId::Anthropic;
             StreamEvent::ResponseComplete(mapped)
         }
+        OpenAiStreamChunk::Usage(usage) => StreamEvent::Usage(usage.into()),
     }
 }
@@ -0,0 +1,13 @@
+//! Google Gemini adapter over the native `generateContent` REST API.
+
+mod provider;
+mod transport;
+mod types;
+
+pub use provider::{GEMINI_BASE_URL, GeminiProvider};
+pub use transport::{GeminiChunkStream, GeminiHttpTransport, GeminiTransport};
+pub use types::{
+    GeminiCandidate, GeminiContent, GeminiFunctionCall, GeminiFunctionDeclaration,
+    GeminiFunctionResponse, GeminiGenerationConfig, GeminiPart, GeminiRequest, GeminiResponse,
+    GeminiTool, GeminiUsageMetadata,
+};
@@ -0,0 +1,478 @@
+//! Gemini provider mapping fiddlesticks requests onto `generateContent`.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use async_stream::try_stream;
+use futures_util::StreamExt;
+use reqwest::Client;
+use serde_json::{Map, Value};
+
+use crate::{
+    BoxedEventStream, Message, ModelInfo, ModelProvider, ModelRequest, ModelResponse, OutputItem,
+    ProviderError, ProviderFuture, ProviderHealth, ProviderId, Role, SecretString,
+    SecureCredentialManager, StopReason, StreamEvent, TokenUsage, ToolCall, ToolDefinition,
+    ToolResult, health::model_listing_health,
+};
+
+use super::transport::{GeminiHttpTransport, GeminiTransport};
+use super::types::{
+    GeminiContent, GeminiFunctionCall, GeminiFunctionDeclaration, GeminiFunctionResponse,
+    GeminiGenerationConfig, GeminiPart, GeminiRequest, GeminiResponse, GeminiTool,
+    GeminiUsageMetadata,
+};
+
+pub const GEMINI_BASE_URL: &str = "https://generativelanguage.googleapis.com/v1beta";
+
+/// Issued calls a provider remembers; the oldest are forgotten first.
+const MAX_REMEMBERED_TOOL_CALLS: usize = 1024;
+
+/// Gemini function calls are keyed by name, while fiddlesticks tool results only
+/// carry the call id, so the provider remembers issued calls to rebuild the
+/// `functionCall`/`functionResponse` pair on the follow-up request.
+///
+/// Entries are kept after their results are sent so a retried request still
+/// finds them, and bounded so a long-lived provider does not grow without limit.
+#[derive(Debug, Default)]
+struct ToolCallCache {
+    calls: HashMap<String, ToolCall>,
+    order: VecDeque<String>,
+}
+
+impl ToolCallCache {
+    fn get(&self, id: &str) -> Option<&ToolCall> {
+        self.calls.get(id)
+    }
+
+    fn insert(&mut self, call: ToolCall) {
+        if self.calls.insert(call.id.clone(), call.clone()).is_none() {
+            self.order.push_back(call.id);
+        }
+        while self.order.len() > MAX_REMEMBERED_TOOL_CALLS {
+            if let Some(oldest) = self.order.pop_front() {
+                self.calls.remove(&oldest);
+            }
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct GeminiProvider {
+    credentials: Arc<SecureCredentialManager>,
+    transport: Arc<dyn GeminiTransport>,
+    fallback_model: String,
+    tool_calls: Arc<Mutex<ToolCallCache>>,
+    /// Source of ids for calls Gemini returns without one, unique across requests.
+    next_call_id: Arc<AtomicU64>,
+}
+
+impl GeminiProvider {
+    pub fn new(
+        credentials: Arc<SecureCredentialManager>,
+        transport: Arc<dyn GeminiTransport>,
+    ) -> Self {
+        Self {
+            credentials,
+            transport,
+            fallback_model: "gemini-2.0-flash".to_string(),
+            tool_calls: Arc::new(Mutex::new(ToolCallCache::default())),
+            next_call_id: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    pub fn with_fallback_model(mut self, model: impl Into<String>) -> Self {
+        self.fallback_model = model.into();
+        self
+    }
+
+    pub fn default_http_transport(client: Client) -> GeminiHttpTransport {
+        GeminiHttpTransport::new(client).with_base_url(GEMINI_BASE_URL)
+    }
+
+    fn build_request(&self, request: ModelRequest) -> Result<GeminiRequest, ProviderError> {
+        let model = if request.model.trim().is_empty() {
+            self.fallback_model.clone()
+        } else {
+            request.model
+        };
+
+        let mut system_parts = Vec::new();
+        let mut contents = Vec::<GeminiContent>::new();
+        for message in request.messages {
+            match message.role {
+                Role::System => system_parts.push(GeminiPart::text(message.content)),
+                Role::User | Role::Tool if !message.content.is_empty() => {
+                    contents.push(GeminiContent::new(
+                        "user",
+                        vec![GeminiPart::text(message.content)],
+                    ));
+                }
+                Role::Assistant if !message.content.is_empty() => {
+                    contents.push(GeminiContent::new(
+                        "model",
+                        vec![GeminiPart::text(message.content)],
+                    ));
+                }
+                _ => {}
+            }
+        }
+
+        if !request.tool_results.is_empty() {
+            self.append_tool_results(&mut contents, request.tool_results)?;
+        }
+
+        let tools = if request.tools.is_empty() {
+            Vec::new()
+        } else {
+            vec![GeminiTool {
+                function_declarations: request
+                    .tools
+                    .into_iter()
+                    .map(function_declaration)
+                    .collect::<Result<Vec<_>, _>>()?,
+            }]
+        };
+
+        let generation_config = (request.options.temperature.is_some()
+            || request.options.max_tokens.is_some())
+        .then_some(GeminiGenerationConfig {
+            temperature: request.options.temperature,
+            max_output_tokens: request.options.max_tokens,
+        });
+
+        Ok(GeminiRequest {
+            model,
+            contents,
+            system_instruction: (!system_parts.is_empty()).then_some(GeminiContent {
+                role: None,
+                parts: system_parts,
+            }),
+            tools,
+            generation_config,
+        })
+    }
+
+    fn append_tool_results(
+        &self,
+        contents: &mut Vec<GeminiContent>,
+        tool_results: Vec<ToolResult>,
+    ) -> Result<(), ProviderError> {
+        let cache = self
+            .tool_calls
+            .lock()
+            .map_err(|_| ProviderError::other("Gemini tool call cache lock poisoned"))?;
+
+        let mut call_parts = Vec::with_capacity(tool_results.len());
+        let mut response_parts = Vec::with_capacity(tool_results.len());
+        for result in tool_results {
+            let call = cache.get(&result.tool_call_id);
+            let name = call
+                .map(|call| call.name.clone())
+                .unwrap_or_else(|| result.tool_call_id.clone());
+            let args = call
+                .and_then(|call| serde_json::from_str(&call.arguments).ok())
+                .unwrap_or_else(|| Value::Object(Map::new()));
+
+            call_parts.push(GeminiPart {
+                function_call: Some(GeminiFunctionCall {
+                    id: Some(result.tool_call_id.clone()),
+                    name: name.clone(),
+                    args,
+                }),
+                ..GeminiPart::default()
+            });
+            response_parts.push(GeminiPart {
+                function_response: Some(GeminiFunctionResponse {
+                    id: Some(result.tool_call_id),
+                    name,
+                    response: tool_output_value(result.output),
+                }),
+                ..GeminiPart::default()
+            });
+        }
+
+        match contents.last_mut() {
+            Some(last) if last.role.as_deref() == Some("model") => {
+                last.parts.extend(call_parts);
+            }
+            _ => contents.push(GeminiContent::new("model", call_parts)),
+        }
+        contents.push(GeminiContent::new("user", response_parts));
+
+        Ok(())
+    }
+
+    fn remember_tool_calls(&self, tool_calls: &[ToolCall]) -> Result<(), ProviderError> {
+        if tool_calls.is_empty() {
+            return Ok(());
+        }
+
+        let mut cache = self
+            .tool_calls
+            .lock()
+            .map_err(|_| ProviderError::other("Gemini tool call cache lock poisoned"))?;
+        for call in tool_calls {
+            cache.insert(call.clone());
+        }
+
+        Ok(())
+    }
+}
+
+impl ModelProvider for GeminiProvider {
+    fn id(&self) -> ProviderId {
+        ProviderId::Gemini
+    }
+
+    fn complete<'a>(
+        &'a self,
+        request: ModelRequest,
+    ) -> ProviderFuture<'a, Result<ModelResponse, ProviderError>> {
+        Box::pin(async move {
+            request.validate()?;
+            let api_key = resolve_gemini_api_key(&self.credentials)?;
+            let gemini_request = self.build_request(request)?;
+            let model = gemini_request.model.clone();
+            let response = self
+                .transport
+                .generate_content(gemini_request, api_key.clone())
+                .await
+                .inspect_err(|err| {
+                    self.credentials
+                        .observe_error(ProviderId::Gemini, api_key.expose(), err)
+                })?;
+
+            let mut accumulator =
+                GeminiResponseAccumulator::new(model, Arc::clone(&self.next_call_id));
+            accumulator.apply(response);
+            self.remember_tool_calls(&accumulator.tool_calls)?;
+            Ok(accumulator.into_model_response())
+        })
+    }
+
+    fn stream<'a>(
+        &'a self,
+        request: ModelRequest,
+    ) -> ProviderFuture<'a, Result<BoxedEventStream<'a>, ProviderError>> {
+        Box::pin(async move {
+            request.validate()?;
+            let api_key = resolve_gemini_api_key(&self.credentials)?;
+            let gemini_request = self.build_request(request)?;
+            let model = gemini_request.model.clone();
+            let transport_key = api_key.clone();
+            let observe = move |err: &ProviderError| {
+                self.credentials
+                    .observe_error(ProviderId::Gemini, api_key.expose(), err)
+            };
+            let mut chunks = self
+                .transport
+                .stream_generate_content(gemini_request, transport_key)
+                .await
+                .inspect_err(&observe)?;
+
+            let stream = try_stream! {
+                let mut accumulator = GeminiResponseAccumulator::new(model, Arc::clone(&self.next_call_id));
+                while let Some(chunk) = chunks.next().await {
+                    for event in accumulator.apply(chunk.inspect_err(&observe)?) {
+                        yield event;
+                    }
+                }
+
+                self.remember_tool_calls(&accumulator.tool_calls)?;
+                yield StreamEvent::MessageComplete(Message::new(
+                    Role::Assistant,
+                    accumulator.text.clone(),
+                ));
+                yield StreamEvent::ResponseComplete(accumulator.into_model_response());
+            };
+
+            Ok(Box::pin(stream) as BoxedEventStream<'a>)
+        })
+    }
+
+    fn list_models<'a>(&'a self) -> ProviderFuture<'a, Result<Vec<ModelInfo>, ProviderError>> {
+        Box::pin(async move {
+            let api_key = resolve_gemini_api_key(&self.credentials)?;
+            self.transport
+                .list_models(api_key.clone())
+                .await
+                .inspect_err(|err| {
+                    self.credentials
+                        .observe_error(ProviderId::Gemini, api_key.expose(), err)
+                })
+        })
+    }
+
+    fn health<'a>(&'a self) -> ProviderFuture<'a, ProviderHealth> {
+        Box::pin(async move {
+            let started = Instant::now();
+            model_listing_health(ProviderId::Gemini, started, self.list_models().await)
+        })
+    }
+}
+
+impl SecureCredentialManager {
+    pub fn set_gemini_api_key(&self, api_key: impl Into<String>) -> Result<(), ProviderError> {
+        let api_key = api_key.into();
+        if api_key.trim().is_empty() {
+            return Err(ProviderError::authentication(
+                "Gemini API key must not be empty",
+            ));
+        }
+
+        self.set_api_key(ProviderId::Gemini, api_key)
+    }
+}
+
+fn resolve_gemini_api_key(
+    credentials: &SecureCredentialManager,
+) -> Result<SecretString, ProviderError> {
+    credentials
+        .api_key(ProviderId::Gemini)?
+        .ok_or_else(|| ProviderError::authentication("no Gemini credentials configured"))
+}
+
+fn function_declaration(tool: ToolDefinition) -> Result<GeminiFunctionDeclaration, ProviderError> {
+    let parameters = serde_json::from_str(&tool.input_schema).map_err(|err| {
+        ProviderError::invalid_request(format!(
+            "tool '{}' has an invalid input schema: {err}",
+            tool.name
+        ))
+    })?;
+
+    Ok(GeminiFunctionDeclaration {
+        name: tool.name,
+        description: tool.description,
+        parameters,
+    })
+}
+
+/// Gemini requires `functionResponse.response` to be a JSON object.
+fn tool_output_value(output: String) -> Value {
+    match serde_json::from_str::<Value>(&output) {
+        Ok(Value::Object(object)) => Value::Object(object),
+        Ok(value) => Value::Object(Map::from_iter([("output".to_string(), value)])),
+        Err(_) => Value::Object(Map::from_iter([(
+            "output".to_string(),
+            Value::String(output),
+        )])),
+    }
+}
+
+/// Folds one or more `GenerateContentResponse` chunks into a single response.
+struct GeminiResponseAccumulator {
+    model: String,
+    next_call_id: Arc<AtomicU64>,
+    text: String,
+    tool_calls: Vec<ToolCall>,
+    finish_reason: Option<String>,
+    usage: Option<GeminiUsageMetadata>,
+}
+
+impl GeminiResponseAccumulator {
+    fn new(model: String, next_call_id: Arc<AtomicU64>) -> Self {
+        Self {
+            model,
+            next_call_id,
+            text: String::new(),
+            tool_calls: Vec::new(),
+            finish_reason: None,
+            usage: None,
+        }
+    }
+
+    fn apply(&mut self, response: GeminiResponse) -> Vec<StreamEvent> {
+        let mut events = Vec::new();
+        if let Some(model_version) = response.model_version
+            && !model_version.is_empty()
+        {
+            self.model = model_version;
+        }
+        let usage = response.usage_metadata;
+        if let Some(usage) = usage {
+            self.usage = Some(usage);
+        }
+
+        let Some(candidate) = response.candidates.into_iter().next() else {
+            events.extend(usage.map(|usage| StreamEvent::Usage(token_usage(usage))));
+            return events;
+        };
+        if let Some(finish_reason) = candidate.finish_reason {
+            self.finish_reason = Some(finish_reason);
+        }
+
+        for part in candidate
+            .content
+            .map(|content| content.parts)
+            .unwrap_or_default()
+        {
+            if let Some(text) = part.text
+                && !text.is_empty()
+            {
+                self.text.push_str(&text);
+                events.push(StreamEvent::TextDelta(text));
+            }
+
+            if let Some(function_call) = part.function_call {
+                let call = ToolCall {
+                    id: function_call
+                        .id
+                        .filter(|id| !id.is_empty())
+                        .unwrap_or_else(|| {
+                            let index = self.next_call_id.fetch_add(1, Ordering::Relaxed);
+                            format!("tool_call_{index}")
+                        }),
+                    name: function_call.name,
+                    arguments: match function_call.args {
+                        Value::Null => "{}".to_string(),
+                        args => args.to_string(),
+                    },
+                };
+                self.tool_calls.push(call.clone());
+                events.push(StreamEvent::ToolCallDelta(call));
+            }
+        }
+
+        events.extend(usage.map(|usage| StreamEvent::Usage(token_usage(usage))));
+        events
+    }
+
+    fn into_model_response(self) -> ModelResponse {
+        let stop_reason = match self.finish_reason.as_deref() {
+            _ if !self.tool_calls.is_empty() => StopReason::ToolUse,
+            Some("STOP") => StopReason::EndTurn,
+            Some("MAX_TOKENS") => StopReason::MaxTokens,
+            _ => StopReason::Other,
+        };
+
+        let usage = token_usage(self.usage.unwrap_or_default());
+        let mut output = Vec::new();
+        if !self.text.is_empty() {
+            output.push(OutputItem::Message(Message::new(
+                Role::Assistant,
+                self.text,
+            )));
+        }
+        output.extend(self.tool_calls.into_iter().map(OutputItem::ToolCall));
+
+        ModelResponse {
+            provider: ProviderId::Gemini,
+            model: self.model,
+            output,
+            stop_reason,
+            usage,
+            response_id: None,
+        }
+    }
+}
+
+fn token_usage(usage: GeminiUsageMetadata) -> TokenUsage {
+    TokenUsage {
+        input_tokens: usage.prompt_token_count,
+        output_tokens: usage.candidates_token_count,
+        total_tokens: usage.total_token_count,
+        cached_input_tokens: usage.cached_content_token_count,
+    }
+}
@@ -0,0 +1,307 @@
+//! Gemini transport trait and reqwest-based HTTP implementation.
+
+use std::pin::Pin;
+
+use async_stream::try_stream;
+use futures_core::Stream;
+use futures_util::StreamExt;
+use reqwest::{Client, Response, StatusCode};
+
+use crate::{ModelInfo, ProviderError, ProviderFuture, ProviderId, SecretString};
+
+use super::provider::GEMINI_BASE_URL;
+use super::types::{GeminiErrorEnvelope, GeminiModelList, GeminiRequest, GeminiResponse};
+
+pub type GeminiChunkStream<'a> =
+    Pin<Box<dyn Stream<Item = Result<GeminiResponse, ProviderError>> + Send + 'a>>;
+
+pub trait GeminiTransport: Send + Sync + std::fmt::Debug {
+    fn generate_content<'a>(
+        &'a self,
+        request: GeminiRequest,
+        api_key: SecretString,
+    ) -> ProviderFuture<'a, Result<GeminiResponse, ProviderError>>;
+
+    fn stream_generate_content<'a>(
+        &'a self,
+        request: GeminiRequest,
+        api_key: SecretString,
+    ) -> ProviderFuture<'a, Result<GeminiChunkStream<'a>, ProviderError>>;
+
+    /// Lists models that support `generateContent`.
+    fn list_models<'a>(
+        &'a self,
+        _api_key: SecretString,
+    ) -> ProviderFuture<'a, Result<Vec<ModelInfo>, ProviderError>> {
+        Box::pin(async {
+            Err(ProviderError::invalid_request(
+                "model listing is not supported by this transport",
+            ))
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct GeminiHttpTransport {
+    client: Client,
+    base_url: String,
+}
+
+impl GeminiHttpTransport {
+    pub fn new(client: Client) -> Self {
+        Self {
+            client,
+            base_url: GEMINI_BASE_URL.to_string(),
+        }
+    }
+
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into();
+        self
+    }
+
+    fn endpoint(&self, model: &str, method: &str) -> String {
+        let model = model.trim_start_matches("models/");
+        format!(
+            "{}/models/{}:{}",
+            self.base_url.trim_end_matches('/'),
+            model,
+            method
+        )
+    }
+
+    async fn send(
+        &self,
+        url: String,
+        request: &GeminiRequest,
+        api_key: &SecretString,
+    ) -> Result<Response, ProviderError> {
+        let response = self
+            .client
+            .post(url)
+            .header("x-goog-api-key", api_key.expose())
+            .json(request)
+            .send()
+            .await
+            .map_err(|err| {
+                if err.is_timeout() {
+                    ProviderError::timeout(err.to_string())
+                } else {
+                    ProviderError::transport(err.to_string())
+                }
+            })?;
+
+        if !response.status().is_success() {
+            return Err(Self::parse_error(response).await);
+        }
+
+        Ok(response)
+    }
+
+    async fn parse_error(response: Response) -> ProviderError {
+        let status = response.status();
+        let retry_after = crate::adapters::retry_after_seconds(response.headers());
+        let body = response.text().await.unwrap_or_default();
+        map_error_response(status, retry_after, &body)
+    }
+}
+
+fn map_error_response(
+    status: StatusCode,
+    retry_after: Option<std::time::Duration>,
+    body: &str,
+) -> ProviderError {
+    let error = serde_json::from_str::<GeminiErrorEnvelope>(body)
+        .ok()
+        .map(|envelope| envelope.error);
+    let rejected_key = error
+        .as_ref()
+        .is_some_and(|error| error.is_authentication());
+    let message = error
+        .map(|error| error.message)
+        .filter(|message| !message.trim().is_empty())
+        .unwrap_or_else(|| format!("Gemini request failed with status {status}"));
+
+    match status {
+        _ if rejected_key => ProviderError::authentication(message),
+        StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => ProviderError::authentication(message),
+        StatusCode::TOO_MANY_REQUESTS => {
+            let error = ProviderError::rate_limited(message);
+            match retry_after {
+                Some(retry_after) => error.with_retry_after(retry_after),
+                None => error,
+            }
+        }
+        StatusCode::REQUEST_TIMEOUT | StatusCode::GATEWAY_TIMEOUT => {
+            ProviderError::timeout(message)
+        }
+        StatusCode::BAD_REQUEST | StatusCode::NOT_FOUND | StatusCode::UNPROCESSABLE_ENTITY => {
+            ProviderError::invalid_request(message)
+        }
+        StatusCode::SERVICE_UNAVAILABLE | StatusCode::BAD_GATEWAY => {
+            ProviderError::unavailable(message)
+        }
+        _ => ProviderError::transport(message),
+    }
+}
+
+impl GeminiTransport for GeminiHttpTransport {
+    fn generate_content<'a>(
+        &'a self,
+        request: GeminiRequest,
+        api_key: SecretString,
+    ) -> ProviderFuture<'a, Result<GeminiResponse, ProviderError>> {
+        Box::pin(async move {
+            let url = self.endpoint(&request.model, "generateContent");
+            let response = self.send(url, &request, &api_key).await?;
+
+            response
+                .json()
+                .await
+                .map_err(|err| ProviderError::transport(err.to_string()))
+        })
+    }
+
+    fn stream_generate_content<'a>(
+        &'a self,
+        request: GeminiRequest,
+        api_key: SecretString,
+    ) -> ProviderFuture<'a, Result<GeminiChunkStream<'a>, ProviderError>> {
+        Box::pin(async move {
+            let url = format!(
+                "{}?alt=sse",
+                self.endpoint(&request.model, "streamGenerateContent")
+            );
+            let response = self.send(url, &request, &api_key).await?;
+
+            let stream = try_stream! {
+                let mut chunks = response.bytes_stream();
+                let mut sse_buffer = String::new();
+
+                while let Some(item) = chunks.next().await {
+                    let bytes = item.map_err(|err| ProviderError::transport(err.to_string()))?;
+                    let text = std::str::from_utf8(&bytes)
+                        .map_err(|err| ProviderError::transport(err.to_string()))?;
+                    sse_buffer.push_str(text);
+
+                    while let Some(newline_index) = sse_buffer.find('\n') {
+                        let line = sse_buffer.drain(..=newline_index).collect::<String>();
+                        let line = line.trim();
+
+                        if !line.starts_with("data:") {
+                            continue;
+                        }
+
+                        let payload = line.trim_start_matches("data:").trim();
+                        if payload.is_empty() {
+                            continue;
+                        }
+
+                        let parsed: GeminiResponse = serde_json::from_str(payload)
+                            .map_err(|err| ProviderError::transport(err.to_string()))?;
+                        yield parsed;
+                    }
+                }
+            };
+
+            Ok(Box::pin(stream) as GeminiChunkStream<'a>)
+        })
+    }
+
+    fn list_models<'a>(
+        &'a self,
+        api_key: SecretString,
+    ) -> ProviderFuture<'a, Result<Vec<ModelInfo>, ProviderError>> {
+        Box::pin(async move {
+            let url = format!("{}/models", self.base_url.trim_end_matches('/'));
+            let mut models = Vec::new();
+            let mut page_token = None::<String>;
+            loop {
+                let mut builder = self
+                    .client
+                    .get(&url)
+                    .header("x-goog-api-key", api_key.expose())
+                    .query(&[("pageSize", "1000")]);
+                if let Some(page_token) = &page_token {
+                    builder = builder.query(&[("pageToken", page_token)]);
+                }
+                let response = builder.send().await.map_err(|err| {
+                    if err.is_timeout() {
+                        ProviderError::timeout(err.to_string())
+                    } else {
+                        ProviderError::transport(err.to_string())
+                    }
+                })?;
+
+                if !response.status().is_success() {
+                    return Err(Self::parse_error(response).await);
+                }
+
+                let page: GeminiModelList = response
+                    .json()
+                    .await
+                    .map_err(|err| ProviderError::transport(err.to_string()))?;
+                models.extend(
+                    page.models
+                        .into_iter()
+                        .filter(|model| {
+                            model.supported_generation_methods.is_empty()
+                                || model
+                                    .supported_generation_methods
+                                    .iter()
+                                    .any(|method| method == "generateContent")
+                        })
+                        .map(|model| ModelInfo {
+                            provider: ProviderId::Gemini,
+                            id: model.name.trim_start_matches("models/").to_string(),
+                            display_name: model.display_name,
+                            owner: Some("google".to_string()),
+                            created: None,
+                            context_window: model.input_token_limit,
+                        }),
+                );
+
+                match page.next_page_token {
+                    Some(token) if !token.is_empty() => page_token = Some(token),
+                    _ => break,
+                }
+            }
+
+            models.sort_by(|left, right| left.id.cmp(&right.id));
+            Ok(models)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ProviderErrorKind;
+
+    #[test]
+    fn invalid_api_keys_map_to_authentication_errors() {
+        let body = r#"{
+            "error": {
+                "code": 400,
+                "message": "API key not valid. Please pass a valid API key.",
+                "status": "INVALID_ARGUMENT",
+                "details": [{
+                    "@type": "type.googleapis.com/google.rpc.ErrorInfo",
+                    "reason": "API_KEY_INVALID",
+                    "domain": "googleapis.com"
+                }]
+            }
+        }"#;
+
+        let error = map_error_response(StatusCode::BAD_REQUEST, None, body);
+        assert_eq!(error.kind, ProviderErrorKind::Authentication);
+        assert_eq!(
+            error.message,
+            "API key not valid. Please pass a valid API key."
+        );
+
+        let malformed = r#"{"error": {"message": "bad schema", "status": "INVALID_ARGUMENT"}}"#;
+        let error = map_error_response(StatusCode::BAD_REQUEST, None, malformed);
+        assert_eq!(error.kind, ProviderErrorKind::InvalidRequest);
+    }
+}
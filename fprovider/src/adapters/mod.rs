@@ -11,3 +11,86 @@ pub mod anthropic;
 
 #[cfg(feature = "provider-ollama")]
 pub mod ollama;
+
+#[cfg(feature = "provider-gemini")]
+pub mod gemini;
+
+/// Parses a `Retry-After` header expressed in whole seconds.
+#[cfg(any(feature = "provider-openai", feature = "provider-gemini"))]
+pub(crate) fn retry_after_seconds(
+    headers: &reqwest::header::HeaderMap,
+) -> Option<std::time::Duration> {
+    headers
+        .get(reqwest::header::RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse::<u64>()
+        .ok()
+        .map(std::time::Duration::from_secs)
+}
+
+/// Parses an RFC 3339 timestamp such as `2024-05-01T10:21:45.5-07:00` into Unix seconds.
+/// Fractional seconds are dropped.
+#[cfg(feature = "provider-openai")]
+pub(crate) fn rfc3339_to_unix_seconds(input: &str) -> Option<u64> {
+    let (date, time) = input.trim().split_once(['T', 't', ' '])?;
+    let mut date_parts = date.splitn(3, '-');
+    let year = date_parts.next()?.parse::<i64>().ok()?;
+    let month = date_parts.next()?.parse::<i64>().ok()?;
+    let day = date_parts.next()?.parse::<i64>().ok()?;
+
+    let (clock, offset_seconds) = match time.find(['Z', 'z', '+', '-']) {
+        Some(index) => {
+            let (clock, zone) = time.split_at(index);
+            let offset = match zone {
+                "Z" | "z" => 0,
+                _ => {
+                    let sign = if zone.starts_with('-') { -1 } else { 1 };
+                    let (hours, minutes) = zone[1..].split_once(':')?;
+                    sign * (hours.parse::<i64>().ok()? * 3600 + minutes.parse::<i64>().ok()? * 60)
+                }
+            };
+            (clock, offset)
+        }
+        None => (time, 0),
+    };
+    let clock = clock.split('.').next()?;
+    let mut clock_parts = clock.splitn(3, ':');
+    let hour = clock_parts.next()?.parse::<i64>().ok()?;
+    let minute = clock_parts.next()?.parse::<i64>().ok()?;
+    let second = clock_parts.next()?.parse::<i64>().ok()?;
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) || hour > 23 || minute > 59 {
+        return None;
+    }
+
+    // Days since 1970-01-01 in the proleptic Gregorian calendar.
+    let shifted_year = if month <= 2 { year - 1 } else { year };
+    let era = shifted_year.div_euclid(400);
+    let year_of_era = shifted_year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    let days = era * 146_097 + day_of_era - 719_468;
+
+    let seconds = days * 86_400 + hour * 3600 + minute * 60 + second - offset_seconds;
+    u64::try_from(seconds).ok()
+}
+
+#[cfg(all(test, feature = "provider-openai"))]
+mod tests {
+    use super::rfc3339_to_unix_seconds;
+
+    #[test]
+    fn rfc3339_timestamps_convert_to_unix_seconds() {
+        assert_eq!(rfc3339_to_unix_seconds("1970-01-01T00:00:00Z"), Some(0));
+        assert_eq!(
+            rfc3339_to_unix_seconds("2025-02-19T00:00:00Z"),
+            Some(1_739_923_200)
+        );
+        assert_eq!(
+            rfc3339_to_unix_seconds("2024-05-01T10:21:45.123456789-07:00"),
+            Some(1_714_584_105)
+        );
+        assert_eq!(rfc3339_to_unix_seconds("yesterday"), None);
+    }
+}
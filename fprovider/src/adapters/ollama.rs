@@ -1,6 +1,7 @@
 //! Ollama provider implemented over OpenAI-compatible transport.
 
 use std::sync::Arc;
+use std::time::Instant;
 
 use async_stream::try_stream;
 use futures_util::StreamExt;
@@ -12,8 +13,8 @@ use crate::adapters::openai::{
     OpenAiTransport,
 };
 use crate::{
-    BoxedEventStream, Message, ModelProvider, ModelRequest, ModelResponse, ProviderError,
-    ProviderFuture, ProviderId, Role, SecretString, StreamEvent,
+    BoxedEventStream, Message, ModelInfo, ModelProvider, ModelRequest, ModelResponse,
+    ProviderError, ProviderFuture, ProviderHealth, ProviderId, Role, SecretString, StreamEvent,
 };
 
 pub const OLLAMA_BASE_URL: &str = "http://localhost:11434/v1";
@@ -23,6 +24,7 @@ pub const OLLAMA_HOST_URL: &str = "http://localhost:11434";
 pub struct OllamaProvider {
     transport: Arc<dyn OpenAiTransport>,
     fallback_model: String,
+    host_url: String,
 }
 
 impl OllamaProvider {
@@ -30,16 +32,26 @@ impl OllamaProvider {
         Self {
             transport,
             fallback_model: "llama3.2".to_string(),
+            host_url: OLLAMA_HOST_URL.to_string(),
         }
     }
 
+    /// Native Ollama API root used for model listing and health probes, without the `/v1`
+    /// suffix.
+    pub fn with_host_url(mut self, host_url: impl Into<String>) -> Self {
+        self.host_url = host_url.into();
+        self
+    }
+
     pub fn with_fallback_model(mut self, model: impl Into<String>) -> Self {
         self.fallback_model = model.into();
         self
     }
 
     pub fn default_http_transport(client: Client) -> OpenAiHttpTransport {
-        OpenAiHttpTransport::new(client).with_base_url(OLLAMA_BASE_URL)
+        OpenAiHttpTransport::new(client)
+            .with_base_url(OLLAMA_BASE_URL)
+            .with_stream_usage(true)
     }
 
     fn build_request(&self, request: ModelRequest, stream: bool) -> OpenAiRequest {
@@ -72,6 +84,7 @@ impl OllamaProvider {
             temperature: request.options.temperature,
             max_tokens: request.options.max_tokens,
             stream,
+            continuation: None,
         }
     }
 
@@ -124,15 +137,29 @@ impl ModelProvider for OllamaProvider {
             Ok(Box::pin(stream) as BoxedEventStream<'a>)
         })
     }
+
+    fn list_models<'a>(&'a self) -> ProviderFuture<'a, Result<Vec<ModelInfo>, ProviderError>> {
+        Box::pin(list_ollama_models_with_base_url(self.host_url.clone()))
+    }
+
+    fn health<'a>(&'a self) -> ProviderFuture<'a, ProviderHealth> {
+        Box::pin(async move {
+            let started = Instant::now();
+            let outcome = list_ollama_models_with_base_url(self.host_url.clone())
+                .await
+                .map(|_| ());
+            ProviderHealth::from_probe(ProviderId::Ollama, started, outcome)
+        })
+    }
 }
 
-pub async fn list_ollama_models() -> Result<Vec<String>, ProviderError> {
+pub async fn list_ollama_models() -> Result<Vec<ModelInfo>, ProviderError> {
     list_ollama_models_with_base_url(OLLAMA_HOST_URL).await
 }
 
 pub async fn list_ollama_models_with_base_url(
     base_url: impl Into<String>,
-) -> Result<Vec<String>, ProviderError> {
+) -> Result<Vec<ModelInfo>, ProviderError> {
     let base_url = base_url.into();
     let endpoint = format!("{}/api/tags", base_url.trim_end_matches('/'));
 
@@ -158,13 +185,13 @@ pub async fn list_ollama_models_with_base_url(
         .await
         .map_err(|err| ProviderError::transport(err.to_string()))?;
 
-    let mut ids = parsed
+    let mut models = parsed
         .models
         .into_iter()
-        .map(|m| m.name)
+        .map(ModelInfo::from)
         .collect::<Vec<_>>();
-    ids.sort();
-    Ok(ids)
+    models.sort_by(|left, right| left.id.cmp(&right.id));
+    Ok(models)
 }
 
 fn map_stream_chunk(chunk: OpenAiStreamChunk) -> StreamEvent {
@@ -179,6 +206,7 @@ fn map_stream_chunk(chunk: OpenAiStreamChunk) -> StreamEvent {
             mapped.provider = ProviderId::Ollama;
             StreamEvent::ResponseComplete(mapped)
         }
+        OpenAiStreamChunk::Usage(usage) => StreamEvent::Usage(usage.into()),
     }
 }
 
@@ -200,4 +228,17 @@ struct OllamaTagsResponse {
 #[derive(Debug, Deserialize)]
 struct OllamaModelTag {
     name: String,
+    #[serde(default)]
+    modified_at: Option<String>,
+}
+
+impl From<OllamaModelTag> for ModelInfo {
+    fn from(tag: OllamaModelTag) -> Self {
+        let mut model = ModelInfo::new(ProviderId::Ollama, tag.name);
+        model.created = tag
+            .modified_at
+            .as_deref()
+            .and_then(crate::adapters::rfc3339_to_unix_seconds);
+        model
+    }
 }
@@ -1,6 +1,8 @@
 //! OpenAI-specific credential helpers and auth resolution policy.
 
-use crate::{ProviderError, ProviderId, SecureCredentialManager};
+use std::time::SystemTime;
+
+use crate::{CredentialKind, ProviderError, ProviderId, SecureCredentialManager};
 
 use super::types::OpenAiAuth;
 
@@ -18,12 +20,51 @@ impl SecureCredentialManager {
 
         self.set_api_key(ProviderId::OpenAi, api_key)
     }
+
+    /// Stores an Azure OpenAI resource key, sent via the `api-key` header.
+    ///
+    /// Azure keys do not share the OpenAI `sk-` prefix, so only emptiness is checked.
+    pub fn set_azure_openai_api_key(
+        &self,
+        api_key: impl Into<String>,
+    ) -> Result<(), ProviderError> {
+        self.set_api_key(ProviderId::OpenAi, api_key)
+    }
+
+    /// Stores a Microsoft Entra ID access token for Azure OpenAI bearer auth.
+    ///
+    /// Pass the token's `expires_on` so expired tokens stop resolving instead of
+    /// surfacing as opaque 401 responses.
+    pub fn set_azure_openai_entra_token(
+        &self,
+        access_token: impl Into<String>,
+        expires_at: Option<SystemTime>,
+    ) -> Result<(), ProviderError> {
+        self.set_access_token(ProviderId::OpenAi, access_token, expires_at)
+    }
 }
 
-/// Resolves OpenAI authentication from API key credentials only.
+/// Benches pooled OpenAI API keys that hit rate limits.
+pub(crate) fn observe_openai_error(
+    credentials: &SecureCredentialManager,
+    auth: &OpenAiAuth,
+    error: &ProviderError,
+) {
+    if let OpenAiAuth::ApiKey(api_key) = auth {
+        credentials.observe_error(ProviderId::OpenAi, api_key.expose(), error);
+    }
+}
+
+/// Resolves OpenAI authentication from API key or Entra access token credentials.
 pub(crate) fn resolve_openai_auth(
     credentials: &SecureCredentialManager,
 ) -> Result<OpenAiAuth, ProviderError> {
+    if credentials.credential_kind(ProviderId::OpenAi)? == Some(CredentialKind::AccessToken)
+        && let Some(token) = credentials.access_token(ProviderId::OpenAi)?
+    {
+        return Ok(OpenAiAuth::BearerToken(token));
+    }
+
     if let Some(api_key) = credentials.api_key(ProviderId::OpenAi)? {
         return Ok(OpenAiAuth::ApiKey(api_key));
     }
@@ -0,0 +1,87 @@
+//! Azure OpenAI deployment routing for the HTTP transport.
+
+use std::collections::HashMap;
+
+pub const AZURE_OPENAI_DEFAULT_API_VERSION: &str = "2024-10-21";
+
+/// First api-version, preview or GA, that accepts `stream_options` on chat completions.
+const AZURE_STREAM_OPTIONS_API_VERSION: &str = "2024-09-01";
+
+/// Azure OpenAI resource settings.
+///
+/// Azure routes requests by deployment name rather than model id, so requests are
+/// mapped through [`AzureOpenAiConfig::with_deployment`]. Models without an
+/// explicit mapping are assumed to be deployed under their own name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AzureOpenAiConfig {
+    endpoint: String,
+    api_version: String,
+    deployments: HashMap<String, String>,
+}
+
+impl AzureOpenAiConfig {
+    /// Creates a config for a resource endpoint such as
+    /// `https://my-resource.openai.azure.com`.
+    pub fn new(endpoint: impl Into<String>) -> Self {
+        Self {
+            endpoint: endpoint.into(),
+            api_version: AZURE_OPENAI_DEFAULT_API_VERSION.to_string(),
+            deployments: HashMap::new(),
+        }
+    }
+
+    pub fn with_api_version(mut self, api_version: impl Into<String>) -> Self {
+        self.api_version = api_version.into();
+        self
+    }
+
+    pub fn with_deployment(
+        mut self,
+        model: impl Into<String>,
+        deployment: impl Into<String>,
+    ) -> Self {
+        self.deployments.insert(model.into(), deployment.into());
+        self
+    }
+
+    pub fn endpoint(&self) -> &str {
+        &self.endpoint
+    }
+
+    pub fn api_version(&self) -> &str {
+        &self.api_version
+    }
+
+    pub fn deployment_for<'a>(&'a self, model: &'a str) -> &'a str {
+        self.deployments
+            .get(model)
+            .map(String::as_str)
+            .unwrap_or(model)
+    }
+
+    /// Whether the configured api-version accepts `stream_options.include_usage`. Versions
+    /// are dated `YYYY-MM-DD` with an optional `-preview` suffix, so they compare as strings.
+    pub(crate) fn supports_stream_usage(&self) -> bool {
+        self.api_version
+            .get(..AZURE_STREAM_OPTIONS_API_VERSION.len())
+            .is_some_and(|date| date >= AZURE_STREAM_OPTIONS_API_VERSION)
+    }
+
+    pub(crate) fn url(&self, model: &str, path: &str) -> String {
+        format!(
+            "{}/openai/deployments/{}/{}?api-version={}",
+            self.endpoint.trim_end_matches('/'),
+            self.deployment_for(model),
+            path,
+            self.api_version
+        )
+    }
+
+    pub(crate) fn models_url(&self) -> String {
+        format!(
+            "{}/openai/models?api-version={}",
+            self.endpoint.trim_end_matches('/'),
+            self.api_version
+        )
+    }
+}
@@ -1,14 +1,19 @@
 //! OpenAI adapter module split by responsibility.
 
 mod auth;
+mod azure;
 mod provider;
+mod responses;
+mod responses_http;
 mod responses_ws;
 mod serde_api;
 mod tests;
 mod transport;
 mod types;
 
+pub use azure::{AZURE_OPENAI_DEFAULT_API_VERSION, AzureOpenAiConfig};
 pub use provider::OpenAiProvider;
+pub use responses_http::OpenAiResponsesHttpTransport;
 pub use responses_ws::OpenAiResponsesWebSocketTransport;
 pub use transport::{OpenAiChunkStream, OpenAiHttpTransport, OpenAiTransport};
 pub use types::{
@@ -1,16 +1,18 @@
 //! OpenAI provider implementation over transport and shared models.
 
 use std::sync::Arc;
+use std::time::Instant;
 
 use async_stream::try_stream;
 use futures_util::StreamExt;
 
 use crate::{
-    BoxedEventStream, ModelProvider, ModelRequest, ModelResponse, ProviderError, ProviderFuture,
-    ProviderId, SecureCredentialManager, StreamEvent,
+    BoxedEventStream, ModelInfo, ModelProvider, ModelRequest, ModelResponse, ProviderError,
+    ProviderFuture, ProviderHealth, ProviderId, SecureCredentialManager, StreamEvent,
+    health::model_listing_health,
 };
 
-use super::auth::resolve_openai_auth;
+use super::auth::{observe_openai_error, resolve_openai_auth};
 use super::transport::OpenAiTransport;
 use super::types::{OpenAiMessage, OpenAiRequest, OpenAiTool};
 
@@ -72,6 +74,7 @@ impl OpenAiProvider {
             temperature: request.options.temperature,
             max_tokens: request.options.max_tokens,
             stream,
+            continuation: request.continuation,
         }
     }
 }
@@ -89,7 +92,11 @@ impl ModelProvider for OpenAiProvider {
             request.validate()?;
             let auth = resolve_openai_auth(&self.credentials)?;
             let openai_request = self.build_openai_request(request, false);
-            let response = self.transport.complete(openai_request, auth).await?;
+            let response = self
+                .transport
+                .complete(openai_request, auth.clone())
+                .await
+                .inspect_err(|err| observe_openai_error(&self.credentials, &auth, err))?;
             Ok(response.into_model_response())
         })
     }
@@ -102,15 +109,40 @@ impl ModelProvider for OpenAiProvider {
             request.validate()?;
             let auth = resolve_openai_auth(&self.credentials)?;
             let openai_request = self.build_openai_request(request, true);
-            let mut chunks = self.transport.stream(openai_request, auth).await?;
+            let observed_auth = auth.clone();
+            let observe = move |err: &ProviderError| {
+                observe_openai_error(&self.credentials, &observed_auth, err)
+            };
+            let mut chunks = self
+                .transport
+                .stream(openai_request, auth)
+                .await
+                .inspect_err(&observe)?;
 
             let stream = try_stream! {
                 while let Some(chunk) = chunks.next().await {
-                    yield StreamEvent::from(chunk?);
+                    yield StreamEvent::from(chunk.inspect_err(&observe)?);
                 }
             };
 
             Ok(Box::pin(stream) as BoxedEventStream<'a>)
         })
     }
+
+    fn health<'a>(&'a self) -> ProviderFuture<'a, ProviderHealth> {
+        Box::pin(async move {
+            let started = Instant::now();
+            model_listing_health(ProviderId::OpenAi, started, self.list_models().await)
+        })
+    }
+
+    fn list_models<'a>(&'a self) -> ProviderFuture<'a, Result<Vec<ModelInfo>, ProviderError>> {
+        Box::pin(async move {
+            let auth = resolve_openai_auth(&self.credentials)?;
+            self.transport
+                .list_models(auth.clone())
+                .await
+                .inspect_err(|err| observe_openai_error(&self.credentials, &auth, err))
+        })
+    }
 }
@@ -0,0 +1,423 @@
+//! Responses API payload building and event accumulation shared by the
+//! WebSocket and HTTP transports.
+
+use std::collections::BTreeMap;
+
+use serde_json::{Value, json};
+
+use crate::{ProviderError, ResponseContinuation};
+
+use super::types::{
+    OpenAiAssistantMessage, OpenAiFinishReason, OpenAiRequest, OpenAiResponse, OpenAiStreamChunk,
+    OpenAiToolCall, OpenAiUsage,
+};
+
+#[derive(Debug)]
+pub(super) struct ResponsesEventAccumulator {
+    fallback_model: String,
+    text: String,
+    tool_calls: BTreeMap<String, OpenAiToolCall>,
+}
+
+impl ResponsesEventAccumulator {
+    pub(super) fn new(fallback_model: String) -> Self {
+        Self {
+            fallback_model,
+            text: String::new(),
+            tool_calls: BTreeMap::new(),
+        }
+    }
+
+    pub(super) fn handle_event(
+        &mut self,
+        event: Value,
+    ) -> Result<Vec<OpenAiStreamChunk>, ProviderError> {
+        let event_type = event
+            .get("type")
+            .and_then(Value::as_str)
+            .unwrap_or_default();
+
+        match event_type {
+            "error" => Err(map_error_event(event)),
+            "response.output_text.delta" => {
+                let delta = event
+                    .get("delta")
+                    .and_then(Value::as_str)
+                    .unwrap_or_default()
+                    .to_string();
+
+                if delta.is_empty() {
+                    Ok(Vec::new())
+                } else {
+                    self.text.push_str(&delta);
+                    Ok(vec![OpenAiStreamChunk::TextDelta(delta)])
+                }
+            }
+            "response.function_call_arguments.delta" => {
+                let call_id = event
+                    .get("call_id")
+                    .and_then(Value::as_str)
+                    .or_else(|| {
+                        event
+                            .get("item")
+                            .and_then(|item| item.get("call_id"))
+                            .and_then(Value::as_str)
+                    })
+                    .unwrap_or("tool_call_0")
+                    .to_string();
+                let delta = event
+                    .get("delta")
+                    .and_then(Value::as_str)
+                    .unwrap_or_default();
+                let name = event
+                    .get("name")
+                    .and_then(Value::as_str)
+                    .or_else(|| {
+                        event
+                            .get("item")
+                            .and_then(|item| item.get("name"))
+                            .and_then(Value::as_str)
+                    })
+                    .unwrap_or_default();
+
+                let entry =
+                    self.tool_calls
+                        .entry(call_id.clone())
+                        .or_insert_with(|| OpenAiToolCall {
+                            id: call_id.clone(),
+                            name: String::new(),
+                            arguments: String::new(),
+                        });
+
+                if !name.is_empty() {
+                    entry.name = name.to_string();
+                }
+                if !delta.is_empty() {
+                    entry.arguments.push_str(delta);
+                }
+
+                Ok(vec![OpenAiStreamChunk::ToolCallDelta(entry.clone())])
+            }
+            "response.output_item.added" | "response.output_item.done" => {
+                let Some(item) = event.get("item") else {
+                    return Ok(Vec::new());
+                };
+
+                if item.get("type").and_then(Value::as_str) == Some("function_call") {
+                    let call_id = item
+                        .get("call_id")
+                        .and_then(Value::as_str)
+                        .or_else(|| item.get("id").and_then(Value::as_str))
+                        .unwrap_or("tool_call_0")
+                        .to_string();
+
+                    let entry =
+                        self.tool_calls
+                            .entry(call_id.clone())
+                            .or_insert_with(|| OpenAiToolCall {
+                                id: call_id.clone(),
+                                name: String::new(),
+                                arguments: String::new(),
+                            });
+
+                    if let Some(name) = item.get("name").and_then(Value::as_str) {
+                        entry.name = name.to_string();
+                    }
+                    if let Some(arguments) = item.get("arguments").and_then(Value::as_str) {
+                        entry.arguments = arguments.to_string();
+                    }
+
+                    return Ok(vec![OpenAiStreamChunk::ToolCallDelta(entry.clone())]);
+                }
+
+                if item.get("type").and_then(Value::as_str) == Some("message") {
+                    let message_text = parse_message_text(item);
+                    if self.text.is_empty() && !message_text.is_empty() {
+                        self.text = message_text;
+                    }
+                }
+
+                Ok(Vec::new())
+            }
+            "response.completed"
+            | "response.failed"
+            | "response.incomplete"
+            | "response.cancelled" => {
+                let response = parse_completed_response(
+                    event.get("response").unwrap_or(&Value::Null),
+                    &self.fallback_model,
+                    &self.text,
+                    &self.tool_calls,
+                )?;
+
+                Ok(vec![
+                    OpenAiStreamChunk::MessageComplete(response.message.clone()),
+                    OpenAiStreamChunk::ResponseComplete(response),
+                ])
+            }
+            _ => Ok(Vec::new()),
+        }
+    }
+}
+
+/// Builds the Responses API body shared by the WebSocket and HTTP transports.
+///
+/// When `continuation` is set, messages already covered by that response are skipped
+/// and `previous_response_id` is attached instead.
+pub(super) fn build_responses_payload(
+    request: OpenAiRequest,
+    continuation: Option<&ResponseContinuation>,
+) -> Result<Value, ProviderError> {
+    let skip = continuation.map_or(0, |continuation| continuation.message_count);
+    let mut input = Vec::<Value>::new();
+    for message in request.messages.into_iter().skip(skip) {
+        if matches!(message.role, super::types::OpenAiRole::Tool) {
+            let call_id = message.tool_call_id.ok_or_else(|| {
+                ProviderError::invalid_request("OpenAI tool message is missing tool_call_id")
+            })?;
+
+            input.push(json!({
+                "type": "function_call_output",
+                "call_id": call_id,
+                "output": message.content,
+            }));
+            continue;
+        }
+
+        if message.content.trim().is_empty()
+            && !matches!(message.role, super::types::OpenAiRole::Assistant)
+        {
+            return Err(ProviderError::invalid_request(
+                "OpenAI message content must not be empty",
+            ));
+        }
+
+        input.push(json!({
+            "type": "message",
+            "role": message.role.as_str(),
+            "content": [{
+                "type": "input_text",
+                "text": message.content,
+            }],
+        }));
+    }
+
+    if input.is_empty() {
+        return Err(ProviderError::invalid_request(
+            "OpenAI request requires at least one message",
+        ));
+    }
+
+    let mut tools = Vec::<Value>::new();
+    for tool in request.tools {
+        let parameters = serde_json::from_str::<Value>(&tool.input_schema)
+            .map_err(|_| ProviderError::invalid_request("OpenAI tool schema must be valid JSON"))?;
+
+        tools.push(json!({
+            "type": "function",
+            "name": tool.name,
+            "description": tool.description,
+            "parameters": parameters,
+        }));
+    }
+
+    let mut payload = json!({
+        "model": request.model,
+        "store": false,
+        "input": input,
+        "tools": tools,
+        "temperature": request.temperature,
+        "max_output_tokens": request.max_tokens,
+    });
+    if let Some(continuation) = continuation {
+        payload["previous_response_id"] = Value::String(continuation.response_id.clone());
+    }
+
+    Ok(payload)
+}
+
+pub(super) fn parse_completed_response(
+    response: &Value,
+    fallback_model: &str,
+    accumulated_text: &str,
+    accumulated_tool_calls: &BTreeMap<String, OpenAiToolCall>,
+) -> Result<OpenAiResponse, ProviderError> {
+    let model = response
+        .get("model")
+        .and_then(Value::as_str)
+        .unwrap_or(fallback_model)
+        .to_string();
+
+    let mut message_content = accumulated_text.to_string();
+    let mut tool_calls = accumulated_tool_calls.clone();
+
+    if let Some(output) = response.get("output").and_then(Value::as_array) {
+        for item in output {
+            match item.get("type").and_then(Value::as_str) {
+                Some("message")
+                    if item.get("role").and_then(Value::as_str) == Some("assistant") =>
+                {
+                    let parsed = parse_message_text(item);
+                    if !parsed.is_empty() {
+                        message_content = parsed;
+                    }
+                }
+                Some("function_call") => {
+                    let call_id = item
+                        .get("call_id")
+                        .and_then(Value::as_str)
+                        .or_else(|| item.get("id").and_then(Value::as_str))
+                        .unwrap_or("tool_call_0")
+                        .to_string();
+                    let name = item
+                        .get("name")
+                        .and_then(Value::as_str)
+                        .unwrap_or_default()
+                        .to_string();
+                    let arguments = item
+                        .get("arguments")
+                        .and_then(Value::as_str)
+                        .unwrap_or_default()
+                        .to_string();
+
+                    tool_calls.insert(
+                        call_id.clone(),
+                        OpenAiToolCall {
+                            id: call_id,
+                            name,
+                            arguments,
+                        },
+                    );
+                }
+                _ => {}
+            }
+        }
+    }
+
+    let finish_reason = parse_response_finish_reason(response);
+    let usage = parse_response_usage(response);
+
+    Ok(OpenAiResponse {
+        id: response
+            .get("id")
+            .and_then(Value::as_str)
+            .map(str::to_string),
+        model,
+        message: OpenAiAssistantMessage {
+            content: message_content,
+            tool_calls: tool_calls.into_values().collect(),
+        },
+        finish_reason,
+        usage,
+    })
+}
+
+fn parse_message_text(item: &Value) -> String {
+    let mut output = String::new();
+    if let Some(content) = item.get("content").and_then(Value::as_array) {
+        for part in content {
+            if part.get("type").and_then(Value::as_str) == Some("output_text")
+                && let Some(text) = part.get("text").and_then(Value::as_str)
+            {
+                output.push_str(text);
+            }
+        }
+    }
+    output
+}
+
+fn parse_response_finish_reason(response: &Value) -> OpenAiFinishReason {
+    if let Some(reason) = response
+        .get("stop_reason")
+        .and_then(Value::as_str)
+        .map(|value| value.to_ascii_lowercase())
+    {
+        return match reason.as_str() {
+            "stop" => OpenAiFinishReason::Stop,
+            "length" | "max_output_tokens" => OpenAiFinishReason::Length,
+            "tool_calls" | "function_call" => OpenAiFinishReason::ToolCalls,
+            "cancelled" => OpenAiFinishReason::Cancelled,
+            _ => OpenAiFinishReason::Other,
+        };
+    }
+
+    match response
+        .get("status")
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+    {
+        "completed" => OpenAiFinishReason::Stop,
+        "incomplete" => OpenAiFinishReason::Length,
+        "cancelled" => OpenAiFinishReason::Cancelled,
+        _ => OpenAiFinishReason::Other,
+    }
+}
+
+fn parse_response_usage(response: &Value) -> OpenAiUsage {
+    let usage = response.get("usage").unwrap_or(&Value::Null);
+
+    OpenAiUsage {
+        prompt_tokens: usage
+            .get("input_tokens")
+            .and_then(Value::as_u64)
+            .unwrap_or(0) as u32,
+        completion_tokens: usage
+            .get("output_tokens")
+            .and_then(Value::as_u64)
+            .unwrap_or(0) as u32,
+        total_tokens: usage
+            .get("total_tokens")
+            .and_then(Value::as_u64)
+            .unwrap_or(0) as u32,
+        cached_tokens: usage
+            .get("input_tokens_details")
+            .and_then(|details| details.get("cached_tokens"))
+            .and_then(Value::as_u64)
+            .unwrap_or(0) as u32,
+    }
+}
+
+/// Maps a Responses `error` event to a provider error.
+///
+/// WebSocket events nest details under `error`, while SSE events carry them inline.
+pub(super) fn map_error_event(event: Value) -> ProviderError {
+    let status = event
+        .get("status")
+        .and_then(Value::as_u64)
+        .unwrap_or_default() as u16;
+
+    let error = event.get("error").unwrap_or(&event);
+    let code = error
+        .get("code")
+        .and_then(Value::as_str)
+        .unwrap_or_default();
+    let message = error
+        .get("message")
+        .and_then(Value::as_str)
+        .unwrap_or("OpenAI Responses request failed")
+        .to_string();
+
+    match code {
+        "previous_response_not_found" | "websocket_connection_limit_reached" => {
+            ProviderError::invalid_request(message)
+        }
+        "rate_limit_exceeded" => ProviderError::rate_limited(message),
+        _ => match status {
+            401 | 403 => ProviderError::authentication(message),
+            408 | 504 => ProviderError::timeout(message),
+            429 => ProviderError::rate_limited(message),
+            400 | 422 => ProviderError::invalid_request(message),
+            502 | 503 => ProviderError::unavailable(message),
+            _ => ProviderError::transport(message),
+        },
+    }
+}
+
+pub(super) fn is_previous_response_not_found(event: &Value) -> bool {
+    event.get("type").and_then(Value::as_str) == Some("error")
+        && event
+            .get("error")
+            .and_then(|error| error.get("code"))
+            .and_then(Value::as_str)
+            == Some("previous_response_not_found")
+}
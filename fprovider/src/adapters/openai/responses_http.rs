@@ -0,0 +1,169 @@
+//! OpenAI Responses API transport over plain HTTP with SSE streaming.
+//!
+//! Useful where long-lived WebSockets are blocked (proxies, serverless runtimes).
+
+use std::collections::BTreeMap;
+
+use async_stream::try_stream;
+use futures_util::StreamExt;
+use reqwest::Client;
+use serde_json::Value;
+
+use crate::{ModelInfo, ProviderError, ProviderFuture};
+
+use super::responses::{
+    ResponsesEventAccumulator, build_responses_payload, parse_completed_response,
+};
+use super::transport::{OpenAiChunkStream, OpenAiHttpTransport, OpenAiTransport};
+use super::types::{OpenAiAuth, OpenAiRequest, OpenAiResponse, OpenAiStreamChunk};
+
+const OPENAI_BASE_URL: &str = "https://api.openai.com/v1";
+
+/// Stateless `/v1/responses` transport.
+///
+/// Requests are sent with `store: false`, so `previous_response_id` continuation is not
+/// available and the full history is always sent.
+#[derive(Debug, Clone)]
+pub struct OpenAiResponsesHttpTransport {
+    client: Client,
+    base_url: String,
+}
+
+impl OpenAiResponsesHttpTransport {
+    pub fn new(client: Client) -> Self {
+        Self {
+            client,
+            base_url: OPENAI_BASE_URL.to_string(),
+        }
+    }
+
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into();
+        self
+    }
+
+    async fn send(
+        &self,
+        request: OpenAiRequest,
+        auth: &OpenAiAuth,
+        stream: bool,
+    ) -> Result<reqwest::Response, ProviderError> {
+        let mut payload = build_responses_payload(request, None)?;
+        if stream {
+            payload["stream"] = Value::Bool(true);
+        }
+
+        let url = format!("{}/responses", self.base_url.trim_end_matches('/'));
+        let builder = self.client.post(url).json(&payload);
+        let builder = match auth {
+            OpenAiAuth::ApiKey(key) | OpenAiAuth::BearerToken(key) => {
+                builder.bearer_auth(key.expose())
+            }
+            OpenAiAuth::BrowserSession(token) => builder.header(
+                "Cookie",
+                format!("__Secure-next-auth.session-token={}", token.expose()),
+            ),
+        };
+
+        let response = builder.send().await.map_err(|err| {
+            if err.is_timeout() {
+                ProviderError::timeout(err.to_string())
+            } else {
+                ProviderError::transport(err.to_string())
+            }
+        })?;
+
+        if !response.status().is_success() {
+            return Err(OpenAiHttpTransport::parse_error(response).await);
+        }
+
+        Ok(response)
+    }
+}
+
+impl OpenAiTransport for OpenAiResponsesHttpTransport {
+    fn complete<'a>(
+        &'a self,
+        request: OpenAiRequest,
+        auth: OpenAiAuth,
+    ) -> ProviderFuture<'a, Result<OpenAiResponse, ProviderError>> {
+        Box::pin(async move {
+            let model = request.model.clone();
+            let response = self.send(request, &auth, false).await?;
+            let body: Value = response
+                .json()
+                .await
+                .map_err(|err| ProviderError::transport(err.to_string()))?;
+
+            parse_completed_response(&body, &model, "", &BTreeMap::new())
+        })
+    }
+
+    fn stream<'a>(
+        &'a self,
+        request: OpenAiRequest,
+        auth: OpenAiAuth,
+    ) -> ProviderFuture<'a, Result<OpenAiChunkStream<'a>, ProviderError>> {
+        Box::pin(async move {
+            let mut accumulator = ResponsesEventAccumulator::new(request.model.clone());
+            let response = self.send(request, &auth, true).await?;
+
+            let stream = try_stream! {
+                let mut chunks = response.bytes_stream();
+                let mut sse_buffer = String::new();
+                let mut completed = false;
+
+                'read: while let Some(item) = chunks.next().await {
+                    let bytes = item.map_err(|err| ProviderError::transport(err.to_string()))?;
+                    let text = std::str::from_utf8(&bytes)
+                        .map_err(|err| ProviderError::transport(err.to_string()))?;
+                    sse_buffer.push_str(text);
+
+                    while let Some(newline_index) = sse_buffer.find('\n') {
+                        let line = sse_buffer.drain(..=newline_index).collect::<String>();
+                        let line = line.trim();
+
+                        let Some(payload) = line.strip_prefix("data:") else {
+                            continue;
+                        };
+                        let payload = payload.trim();
+                        if payload.is_empty() || payload == "[DONE]" {
+                            continue;
+                        }
+
+                        let event = serde_json::from_str::<Value>(payload)
+                            .map_err(|err| ProviderError::transport(err.to_string()))?;
+                        for chunk in accumulator.handle_event(event)? {
+                            completed |= matches!(chunk, OpenAiStreamChunk::ResponseComplete(_));
+                            yield chunk;
+                        }
+
+                        if completed {
+                            break 'read;
+                        }
+                    }
+                }
+
+                if !completed {
+                    Err(ProviderError::transport(
+                        "OpenAI response stream ended before response completed",
+                    ))?;
+                }
+            };
+
+            Ok(Box::pin(stream) as OpenAiChunkStream<'a>)
+        })
+    }
+
+    fn list_models<'a>(
+        &'a self,
+        auth: OpenAiAuth,
+    ) -> ProviderFuture<'a, Result<Vec<ModelInfo>, ProviderError>> {
+        Box::pin(async move {
+            OpenAiHttpTransport::new(self.client.clone())
+                .with_base_url(self.base_url.clone())
+                .list_models(auth)
+                .await
+        })
+    }
+}
@@ -1,47 +1,152 @@
 //! OpenAI Responses API transport over persistent WebSocket mode.
-
-use std::collections::BTreeMap;
+//!
+//! A WebSocket connection carries one in-flight response at a time, so the transport keeps a
+//! small pool of connections per credential. Each request leases a connection for its whole
+//! lifetime, which correlates every server event with the request that produced it, and
+//! returns it to the pool only after `response.completed`. Concurrent `complete`/`stream`
+//! calls for the same credential proceed in parallel up to the configured pool size.
+//!
+//! Idle connections are capped across all credentials, evicting the least recently used
+//! first, so connections of a key that an `ApiKeyPool` rotated away from or benched are
+//! closed instead of lingering; a pool with nothing idle or leased is dropped entirely.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex as StdMutex};
 use std::time::{Duration, Instant};
 
 use async_stream::try_stream;
 use futures_util::{SinkExt, StreamExt};
 use http::{HeaderValue, header};
-use serde_json::{Value, json};
-use tokio::sync::Mutex;
+use reqwest::Client;
+use serde_json::Value;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
 use tokio_tungstenite::{
     MaybeTlsStream, WebSocketStream, connect_async,
     tungstenite::{Message as WsMessage, client::IntoClientRequest},
 };
 
-use crate::{ProviderError, ProviderFuture};
+use crate::{ModelInfo, ProviderError, ProviderFuture, ResponseContinuation, RetryPolicy};
 
-use super::transport::{OpenAiChunkStream, OpenAiTransport};
-use super::types::{
-    OpenAiAssistantMessage, OpenAiAuth, OpenAiFinishReason, OpenAiRequest, OpenAiResponse,
-    OpenAiStreamChunk, OpenAiToolCall, OpenAiUsage,
+use super::responses::{
+    ResponsesEventAccumulator, build_responses_payload, is_previous_response_not_found,
 };
+use super::transport::{OpenAiChunkStream, OpenAiHttpTransport, OpenAiTransport};
+use super::types::{OpenAiAuth, OpenAiRequest, OpenAiResponse, OpenAiStreamChunk};
 
 const OPENAI_RESPONSES_WS_URL: &str = "wss://api.openai.com/v1/responses";
 const CONNECTION_MAX_AGE: Duration = Duration::from_secs(55 * 60);
+const DEFAULT_POOL_SIZE: usize = 4;
+const DEFAULT_MAX_IDLE_CONNECTIONS: usize = 8;
+const DEFAULT_PING_INTERVAL: Duration = Duration::from_secs(30);
+const PING_TIMEOUT: Duration = Duration::from_secs(5);
+
+type WsSocket = WebSocketStream<MaybeTlsStream<tokio::net::TcpStream>>;
+
+#[derive(Debug)]
+struct WsConnection {
+    socket: WsSocket,
+    connected_at: Instant,
+    last_used: Instant,
+    /// With `store: false` the server only keeps the latest response of the current
+    /// connection, so continuation is only attempted against this id.
+    last_response_id: Option<String>,
+}
+
+impl WsConnection {
+    fn new(socket: WsSocket) -> Self {
+        let now = Instant::now();
+        Self {
+            socket,
+            connected_at: now,
+            last_used: now,
+            last_response_id: None,
+        }
+    }
+
+    fn is_expired(&self) -> bool {
+        self.connected_at.elapsed() >= CONNECTION_MAX_AGE
+    }
+
+    fn usable_continuation(&self, request: &OpenAiRequest) -> Option<ResponseContinuation> {
+        let continuation = request.continuation.as_ref()?;
+        let is_latest = self.last_response_id.as_deref() == Some(&continuation.response_id);
+        let has_new_input = continuation.message_count < request.messages.len();
+
+        (is_latest && has_new_input).then(|| continuation.clone())
+    }
+
+    /// Sends a ping and waits for the matching pong; `false` means the connection is unusable.
+    async fn ping(&mut self) -> bool {
+        if self
+            .socket
+            .send(WsMessage::Ping(Vec::new().into()))
+            .await
+            .is_err()
+        {
+            return false;
+        }
+
+        let pong = async {
+            while let Some(message) = self.socket.next().await {
+                match message {
+                    Ok(WsMessage::Pong(_)) => return true,
+                    Ok(WsMessage::Close(_)) | Err(_) => return false,
+                    Ok(_) => {}
+                }
+            }
+            false
+        };
+        tokio::time::timeout(PING_TIMEOUT, pong)
+            .await
+            .unwrap_or(false)
+    }
+}
+
+/// Connections for one credential; `permits` caps how many may be leased at once.
+#[derive(Debug)]
+struct CredentialPool {
+    idle: Vec<WsConnection>,
+    permits: Arc<Semaphore>,
+}
+
+impl CredentialPool {
+    /// Leases and waiting requests hold clones of `permits`, so a pool whose only reference is
+    /// the map's has none of either.
+    fn is_unused(&self) -> bool {
+        self.idle.is_empty() && Arc::strong_count(&self.permits) == 1
+    }
+}
 
-#[derive(Debug, Default)]
-struct WsConnectionState {
-    socket: Option<WebSocketStream<MaybeTlsStream<tokio::net::TcpStream>>>,
-    connected_at: Option<Instant>,
-    auth_cache_key: Option<String>,
+/// A connection checked out for a single request. Dropping a lease discards its connection;
+/// only [`OpenAiResponsesWebSocketTransport::release`] returns it to the pool.
+struct WsLease {
+    connection: WsConnection,
+    pool_key: String,
+    _permit: OwnedSemaphorePermit,
 }
 
 #[derive(Debug)]
 pub struct OpenAiResponsesWebSocketTransport {
     url: String,
-    connection: Mutex<WsConnectionState>,
+    pool_size: usize,
+    max_idle_connections: usize,
+    ping_interval: Duration,
+    reconnect_policy: RetryPolicy,
+    pools: StdMutex<HashMap<String, CredentialPool>>,
+    /// Model listing is not available over the socket and goes through plain HTTP.
+    http_client: Client,
 }
 
 impl OpenAiResponsesWebSocketTransport {
     pub fn new() -> Self {
         Self {
             url: OPENAI_RESPONSES_WS_URL.to_string(),
-            connection: Mutex::new(WsConnectionState::default()),
+            pool_size: DEFAULT_POOL_SIZE,
+            max_idle_connections: DEFAULT_MAX_IDLE_CONNECTIONS,
+            ping_interval: DEFAULT_PING_INTERVAL,
+            reconnect_policy: RetryPolicy::default(),
+            pools: StdMutex::new(HashMap::new()),
+            http_client: Client::new(),
         }
     }
 
@@ -50,38 +155,202 @@ impl OpenAiResponsesWebSocketTransport {
         self
     }
 
-    async fn ensure_connection(
+    /// Maximum number of concurrent connections (and in-flight requests) per credential.
+    pub fn with_pool_size(mut self, pool_size: usize) -> Self {
+        self.pool_size = pool_size.max(1);
+        self
+    }
+
+    /// Maximum number of idle connections kept across all credentials; the least recently used
+    /// are closed beyond it.
+    pub fn with_max_idle_connections(mut self, max_idle_connections: usize) -> Self {
+        self.max_idle_connections = max_idle_connections;
+        self
+    }
+
+    /// Idle connections older than this are pinged before reuse and replaced if they fail.
+    pub fn with_ping_interval(mut self, ping_interval: Duration) -> Self {
+        self.ping_interval = ping_interval;
+        self
+    }
+
+    /// Backoff applied when opening a connection fails.
+    pub fn with_reconnect_policy(mut self, reconnect_policy: RetryPolicy) -> Self {
+        self.reconnect_policy = reconnect_policy;
+        self
+    }
+
+    pub fn pool_size(&self) -> usize {
+        self.pool_size
+    }
+
+    pub fn max_idle_connections(&self) -> usize {
+        self.max_idle_connections
+    }
+
+    /// HTTP API root matching the socket URL, e.g. `https://api.openai.com/v1`.
+    fn http_base_url(&self) -> String {
+        let url = self.url.trim_end_matches('/');
+        let url = url.strip_suffix("/responses").unwrap_or(url);
+        if let Some(rest) = url.strip_prefix("wss://") {
+            format!("https://{rest}")
+        } else if let Some(rest) = url.strip_prefix("ws://") {
+            format!("http://{rest}")
+        } else {
+            url.to_string()
+        }
+    }
+
+    async fn acquire(
         &self,
-        state: &mut WsConnectionState,
+        request: &OpenAiRequest,
         auth: &OpenAiAuth,
-    ) -> Result<(), ProviderError> {
-        let now = Instant::now();
-        let auth_cache_key = auth_cache_key(auth);
-        let should_reconnect = state
-            .connected_at
-            .map(|connected_at| now.duration_since(connected_at) >= CONNECTION_MAX_AGE)
-            .unwrap_or(false)
-            || state.socket.is_none()
-            || state.auth_cache_key.as_ref() != Some(&auth_cache_key);
+    ) -> Result<WsLease, ProviderError> {
+        let pool_key = auth_cache_key(auth);
+        let permits = {
+            let mut pools = self.lock_pools()?;
+            let pool = pools
+                .entry(pool_key.clone())
+                .or_insert_with(|| CredentialPool {
+                    idle: Vec::new(),
+                    permits: Arc::new(Semaphore::new(self.pool_size)),
+                });
+            Arc::clone(&pool.permits)
+        };
+        let permit = permits
+            .acquire_owned()
+            .await
+            .map_err(|_| ProviderError::transport("OpenAI websocket pool closed"))?;
+
+        let continuation_id = request
+            .continuation
+            .as_ref()
+            .map(|continuation| continuation.response_id.as_str());
+        while let Some(mut connection) = self.take_idle(&pool_key, continuation_id)? {
+            if connection.is_expired() {
+                continue;
+            }
+            if connection.last_used.elapsed() >= self.ping_interval && !connection.ping().await {
+                continue;
+            }
+
+            return Ok(WsLease {
+                connection,
+                pool_key,
+                _permit: permit,
+            });
+        }
+
+        Ok(WsLease {
+            connection: self.connect(auth).await?,
+            pool_key,
+            _permit: permit,
+        })
+    }
 
-        if !should_reconnect {
-            return Ok(());
+    /// Prefers the connection that produced `continuation_id`, then the most recently used one.
+    fn take_idle(
+        &self,
+        pool_key: &str,
+        continuation_id: Option<&str>,
+    ) -> Result<Option<WsConnection>, ProviderError> {
+        let mut pools = self.lock_pools()?;
+        let Some(pool) = pools.get_mut(pool_key) else {
+            return Ok(None);
+        };
+
+        let matching = continuation_id.and_then(|id| {
+            pool.idle
+                .iter()
+                .position(|connection| connection.last_response_id.as_deref() == Some(id))
+        });
+        Ok(match matching {
+            Some(index) => Some(pool.idle.remove(index)),
+            None => pool.idle.pop(),
+        })
+    }
+
+    fn release(&self, lease: WsLease) {
+        let WsLease {
+            mut connection,
+            pool_key,
+            _permit: permit,
+        } = lease;
+        connection.last_used = Instant::now();
+        let Ok(mut pools) = self.pools.lock() else {
+            return;
+        };
+        if let Some(pool) = pools.get_mut(&pool_key) {
+            pool.idle.push(connection);
+        }
+        drop(permit);
+        self.evict_idle(&mut pools);
+    }
+
+    /// Drops expired idle connections, then the least recently used ones beyond
+    /// `max_idle_connections`, and finally pools left with nothing idle or leased.
+    fn evict_idle(&self, pools: &mut HashMap<String, CredentialPool>) {
+        for pool in pools.values_mut() {
+            pool.idle.retain(|connection| !connection.is_expired());
+        }
+
+        let mut idle = pools.values().map(|pool| pool.idle.len()).sum::<usize>();
+        while idle > self.max_idle_connections {
+            let oldest = pools
+                .iter()
+                .flat_map(|(key, pool)| {
+                    pool.idle
+                        .iter()
+                        .enumerate()
+                        .map(move |(index, connection)| (connection.last_used, key, index))
+                })
+                .min()
+                .map(|(_, key, index)| (key.clone(), index));
+            let Some((key, index)) = oldest else {
+                break;
+            };
+            if let Some(pool) = pools.get_mut(&key) {
+                pool.idle.remove(index);
+            }
+            idle -= 1;
         }
 
-        state.socket = None;
-        state.connected_at = None;
-        state.auth_cache_key = None;
+        pools.retain(|_, pool| !pool.is_unused());
+    }
+
+    fn lock_pools(
+        &self,
+    ) -> Result<std::sync::MutexGuard<'_, HashMap<String, CredentialPool>>, ProviderError> {
+        self.pools
+            .lock()
+            .map_err(|_| ProviderError::other("OpenAI websocket pool lock poisoned"))
+    }
+
+    async fn connect(&self, auth: &OpenAiAuth) -> Result<WsConnection, ProviderError> {
+        let mut attempt = 1_u32;
+        loop {
+            match self.open_socket(auth).await {
+                Ok(socket) => return Ok(WsConnection::new(socket)),
+                Err(err) if self.reconnect_policy.should_retry(attempt, &err) => {
+                    tokio::time::sleep(self.reconnect_policy.backoff_for_attempt(attempt)).await;
+                    attempt += 1;
+                }
+                Err(err) => return Err(err),
+            }
+        }
+    }
 
+    async fn open_socket(&self, auth: &OpenAiAuth) -> Result<WsSocket, ProviderError> {
         let mut request = self
             .url
             .as_str()
             .into_client_request()
-            .map_err(|err| ProviderError::transport(err.to_string()))?;
+            .map_err(|err| ProviderError::invalid_request(err.to_string()))?;
 
         match auth {
-            OpenAiAuth::ApiKey(key) => {
+            OpenAiAuth::ApiKey(key) | OpenAiAuth::BearerToken(key) => {
                 let header_value = HeaderValue::from_str(&format!("Bearer {}", key.expose()))
-                    .map_err(|err| ProviderError::transport(err.to_string()))?;
+                    .map_err(|err| ProviderError::invalid_request(err.to_string()))?;
                 request
                     .headers_mut()
                     .insert(header::AUTHORIZATION, header_value);
@@ -91,7 +360,7 @@ impl OpenAiResponsesWebSocketTransport {
                     "__Secure-next-auth.session-token={}",
                     session.expose()
                 ))
-                .map_err(|err| ProviderError::transport(err.to_string()))?;
+                .map_err(|err| ProviderError::invalid_request(err.to_string()))?;
                 request.headers_mut().insert(header::COOKIE, header_value);
             }
         }
@@ -99,18 +368,16 @@ impl OpenAiResponsesWebSocketTransport {
         let (socket, _) = connect_async(request)
             .await
             .map_err(|err| ProviderError::transport(err.to_string()))?;
-
-        state.socket = Some(socket);
-        state.connected_at = Some(now);
-        state.auth_cache_key = Some(auth_cache_key);
-        Ok(())
+        Ok(socket)
     }
 
     async fn send_create_request(
-        socket: &mut WebSocketStream<MaybeTlsStream<tokio::net::TcpStream>>,
+        socket: &mut WsSocket,
         request: OpenAiRequest,
+        continuation: Option<&ResponseContinuation>,
     ) -> Result<(), ProviderError> {
-        let payload = build_response_create_payload(request)?;
+        let mut payload = build_responses_payload(request, continuation)?;
+        payload["type"] = Value::String("response.create".to_string());
         socket
             .send(WsMessage::Text(payload.to_string().into()))
             .await
@@ -131,23 +398,15 @@ impl OpenAiTransport for OpenAiResponsesWebSocketTransport {
         auth: OpenAiAuth,
     ) -> ProviderFuture<'a, Result<OpenAiResponse, ProviderError>> {
         Box::pin(async move {
-            let mut state = self.connection.lock().await;
-            self.ensure_connection(&mut state, &auth).await?;
-            let socket = state
-                .socket
-                .as_mut()
-                .ok_or_else(|| ProviderError::transport("OpenAI websocket not connected"))?;
-
-            if let Err(err) = Self::send_create_request(socket, request.clone()).await {
-                state.socket = None;
-                return Err(err);
-            }
+            let mut lease = self.acquire(&request, &auth).await?;
+            let mut continuation = lease.connection.usable_continuation(&request);
+            let socket = &mut lease.connection.socket;
+
+            Self::send_create_request(socket, request.clone(), continuation.as_ref()).await?;
 
-            let mut accumulator = ResponsesEventAccumulator::new(request.model);
-            loop {
-                let next = socket.next().await;
-                let Some(message) = next else {
-                    state.socket = None;
+            let mut accumulator = ResponsesEventAccumulator::new(request.model.clone());
+            let response = 'receive: loop {
+                let Some(message) = socket.next().await else {
                     return Err(ProviderError::transport(
                         "OpenAI websocket closed before response completed",
                     ));
@@ -157,10 +416,17 @@ impl OpenAiTransport for OpenAiResponsesWebSocketTransport {
                     Ok(WsMessage::Text(text)) => {
                         let event = serde_json::from_str::<Value>(&text)
                             .map_err(|err| ProviderError::transport(err.to_string()))?;
+                        if continuation.is_some() && is_previous_response_not_found(&event) {
+                            continuation = None;
+                            accumulator = ResponsesEventAccumulator::new(request.model.clone());
+                            Self::send_create_request(socket, request.clone(), None).await?;
+                            continue;
+                        }
+
                         let chunks = accumulator.handle_event(event)?;
                         for chunk in chunks {
                             if let OpenAiStreamChunk::ResponseComplete(response) = chunk {
-                                return Ok(response);
+                                break 'receive response;
                             }
                         }
                     }
@@ -171,18 +437,18 @@ impl OpenAiTransport for OpenAiResponsesWebSocketTransport {
                             .map_err(|err| ProviderError::transport(err.to_string()))?;
                     }
                     Ok(WsMessage::Close(_)) => {
-                        state.socket = None;
                         return Err(ProviderError::transport(
                             "OpenAI websocket closed before response completed",
                         ));
                     }
                     Ok(_) => {}
-                    Err(err) => {
-                        state.socket = None;
-                        return Err(ProviderError::transport(err.to_string()));
-                    }
+                    Err(err) => return Err(ProviderError::transport(err.to_string())),
                 }
-            }
+            };
+
+            lease.connection.last_response_id = response.id.clone();
+            self.release(lease);
+            Ok(response)
         })
     }
 
@@ -193,18 +459,14 @@ impl OpenAiTransport for OpenAiResponsesWebSocketTransport {
     ) -> ProviderFuture<'a, Result<OpenAiChunkStream<'a>, ProviderError>> {
         Box::pin(async move {
             let stream = try_stream! {
-                let mut state = self.connection.lock().await;
-                self.ensure_connection(&mut state, &auth).await?;
-                let socket = state
-                    .socket
-                    .as_mut()
-                    .ok_or_else(|| ProviderError::transport("OpenAI websocket not connected"))?;
-
-                if let Err(err) = Self::send_create_request(socket, request.clone()).await {
-                    Err(err)?;
-                }
+                let mut lease = self.acquire(&request, &auth).await?;
+                let mut continuation = lease.connection.usable_continuation(&request);
+                let socket = &mut lease.connection.socket;
+
+                Self::send_create_request(socket, request.clone(), continuation.as_ref()).await?;
 
-                let mut accumulator = ResponsesEventAccumulator::new(request.model);
+                let mut accumulator = ResponsesEventAccumulator::new(request.model.clone());
+                let mut completed_response_id = None;
                 loop {
                     let next = socket.next().await;
                     let Some(message) = next else {
@@ -218,11 +480,19 @@ impl OpenAiTransport for OpenAiResponsesWebSocketTransport {
                         Ok(WsMessage::Text(text)) => {
                             let event = serde_json::from_str::<Value>(&text)
                                 .map_err(|err| ProviderError::transport(err.to_string()))?;
+                            if continuation.is_some() && is_previous_response_not_found(&event) {
+                                continuation = None;
+                                accumulator = ResponsesEventAccumulator::new(request.model.clone());
+                                Self::send_create_request(socket, request.clone(), None).await?;
+                                continue;
+                            }
+
                             let chunks = accumulator.handle_event(event)?;
 
                             let mut complete = false;
                             for chunk in chunks {
-                                if matches!(chunk, OpenAiStreamChunk::ResponseComplete(_)) {
+                                if let OpenAiStreamChunk::ResponseComplete(response) = &chunk {
+                                    completed_response_id = response.id.clone();
                                     complete = true;
                                 }
                                 yield chunk;
@@ -249,383 +519,25 @@ impl OpenAiTransport for OpenAiResponsesWebSocketTransport {
                         }
                     }
                 }
+
+                lease.connection.last_response_id = completed_response_id;
+                self.release(lease);
             };
 
             Ok(Box::pin(stream) as OpenAiChunkStream<'a>)
         })
     }
-}
-
-#[derive(Debug)]
-struct ResponsesEventAccumulator {
-    fallback_model: String,
-    text: String,
-    tool_calls: BTreeMap<String, OpenAiToolCall>,
-}
-
-impl ResponsesEventAccumulator {
-    fn new(fallback_model: String) -> Self {
-        Self {
-            fallback_model,
-            text: String::new(),
-            tool_calls: BTreeMap::new(),
-        }
-    }
-
-    fn handle_event(&mut self, event: Value) -> Result<Vec<OpenAiStreamChunk>, ProviderError> {
-        let event_type = event
-            .get("type")
-            .and_then(Value::as_str)
-            .unwrap_or_default();
-
-        match event_type {
-            "error" => Err(map_ws_error(event)),
-            "response.output_text.delta" => {
-                let delta = event
-                    .get("delta")
-                    .and_then(Value::as_str)
-                    .unwrap_or_default()
-                    .to_string();
-
-                if delta.is_empty() {
-                    Ok(Vec::new())
-                } else {
-                    self.text.push_str(&delta);
-                    Ok(vec![OpenAiStreamChunk::TextDelta(delta)])
-                }
-            }
-            "response.function_call_arguments.delta" => {
-                let call_id = event
-                    .get("call_id")
-                    .and_then(Value::as_str)
-                    .or_else(|| {
-                        event
-                            .get("item")
-                            .and_then(|item| item.get("call_id"))
-                            .and_then(Value::as_str)
-                    })
-                    .unwrap_or("tool_call_0")
-                    .to_string();
-                let delta = event
-                    .get("delta")
-                    .and_then(Value::as_str)
-                    .unwrap_or_default();
-                let name = event
-                    .get("name")
-                    .and_then(Value::as_str)
-                    .or_else(|| {
-                        event
-                            .get("item")
-                            .and_then(|item| item.get("name"))
-                            .and_then(Value::as_str)
-                    })
-                    .unwrap_or_default();
-
-                let entry =
-                    self.tool_calls
-                        .entry(call_id.clone())
-                        .or_insert_with(|| OpenAiToolCall {
-                            id: call_id.clone(),
-                            name: String::new(),
-                            arguments: String::new(),
-                        });
-
-                if !name.is_empty() {
-                    entry.name = name.to_string();
-                }
-                if !delta.is_empty() {
-                    entry.arguments.push_str(delta);
-                }
-
-                Ok(vec![OpenAiStreamChunk::ToolCallDelta(entry.clone())])
-            }
-            "response.output_item.added" | "response.output_item.done" => {
-                let Some(item) = event.get("item") else {
-                    return Ok(Vec::new());
-                };
-
-                if item.get("type").and_then(Value::as_str) == Some("function_call") {
-                    let call_id = item
-                        .get("call_id")
-                        .and_then(Value::as_str)
-                        .or_else(|| item.get("id").and_then(Value::as_str))
-                        .unwrap_or("tool_call_0")
-                        .to_string();
-
-                    let entry =
-                        self.tool_calls
-                            .entry(call_id.clone())
-                            .or_insert_with(|| OpenAiToolCall {
-                                id: call_id.clone(),
-                                name: String::new(),
-                                arguments: String::new(),
-                            });
-
-                    if let Some(name) = item.get("name").and_then(Value::as_str) {
-                        entry.name = name.to_string();
-                    }
-                    if let Some(arguments) = item.get("arguments").and_then(Value::as_str) {
-                        entry.arguments = arguments.to_string();
-                    }
-
-                    return Ok(vec![OpenAiStreamChunk::ToolCallDelta(entry.clone())]);
-                }
-
-                if item.get("type").and_then(Value::as_str) == Some("message") {
-                    let message_text = parse_message_text(item);
-                    if self.text.is_empty() && !message_text.is_empty() {
-                        self.text = message_text;
-                    }
-                }
-
-                Ok(Vec::new())
-            }
-            "response.completed"
-            | "response.failed"
-            | "response.incomplete"
-            | "response.cancelled" => {
-                let response = parse_completed_response(
-                    event.get("response").unwrap_or(&Value::Null),
-                    &self.fallback_model,
-                    &self.text,
-                    &self.tool_calls,
-                )?;
-
-                Ok(vec![
-                    OpenAiStreamChunk::MessageComplete(response.message.clone()),
-                    OpenAiStreamChunk::ResponseComplete(response),
-                ])
-            }
-            _ => Ok(Vec::new()),
-        }
-    }
-}
-
-fn build_response_create_payload(request: OpenAiRequest) -> Result<Value, ProviderError> {
-    let mut input = Vec::<Value>::new();
-    for message in request.messages {
-        if matches!(message.role, super::types::OpenAiRole::Tool) {
-            let call_id = message.tool_call_id.ok_or_else(|| {
-                ProviderError::invalid_request("OpenAI tool message is missing tool_call_id")
-            })?;
-
-            input.push(json!({
-                "type": "function_call_output",
-                "call_id": call_id,
-                "output": message.content,
-            }));
-            continue;
-        }
-
-        if message.content.trim().is_empty()
-            && !matches!(message.role, super::types::OpenAiRole::Assistant)
-        {
-            return Err(ProviderError::invalid_request(
-                "OpenAI message content must not be empty",
-            ));
-        }
-
-        input.push(json!({
-            "type": "message",
-            "role": message.role.as_str(),
-            "content": [{
-                "type": "input_text",
-                "text": message.content,
-            }],
-        }));
-    }
-
-    if input.is_empty() {
-        return Err(ProviderError::invalid_request(
-            "OpenAI request requires at least one message",
-        ));
-    }
-
-    let mut tools = Vec::<Value>::new();
-    for tool in request.tools {
-        let parameters = serde_json::from_str::<Value>(&tool.input_schema)
-            .map_err(|_| ProviderError::invalid_request("OpenAI tool schema must be valid JSON"))?;
-
-        tools.push(json!({
-            "type": "function",
-            "name": tool.name,
-            "description": tool.description,
-            "parameters": parameters,
-        }));
-    }
-
-    Ok(json!({
-        "type": "response.create",
-        "model": request.model,
-        "store": false,
-        "input": input,
-        "tools": tools,
-        "temperature": request.temperature,
-        "max_output_tokens": request.max_tokens,
-    }))
-}
-
-fn parse_completed_response(
-    response: &Value,
-    fallback_model: &str,
-    accumulated_text: &str,
-    accumulated_tool_calls: &BTreeMap<String, OpenAiToolCall>,
-) -> Result<OpenAiResponse, ProviderError> {
-    let model = response
-        .get("model")
-        .and_then(Value::as_str)
-        .unwrap_or(fallback_model)
-        .to_string();
-
-    let mut message_content = accumulated_text.to_string();
-    let mut tool_calls = accumulated_tool_calls.clone();
-
-    if let Some(output) = response.get("output").and_then(Value::as_array) {
-        for item in output {
-            match item.get("type").and_then(Value::as_str) {
-                Some("message") => {
-                    if item.get("role").and_then(Value::as_str) == Some("assistant") {
-                        let parsed = parse_message_text(item);
-                        if !parsed.is_empty() {
-                            message_content = parsed;
-                        }
-                    }
-                }
-                Some("function_call") => {
-                    let call_id = item
-                        .get("call_id")
-                        .and_then(Value::as_str)
-                        .or_else(|| item.get("id").and_then(Value::as_str))
-                        .unwrap_or("tool_call_0")
-                        .to_string();
-                    let name = item
-                        .get("name")
-                        .and_then(Value::as_str)
-                        .unwrap_or_default()
-                        .to_string();
-                    let arguments = item
-                        .get("arguments")
-                        .and_then(Value::as_str)
-                        .unwrap_or_default()
-                        .to_string();
-
-                    tool_calls.insert(
-                        call_id.clone(),
-                        OpenAiToolCall {
-                            id: call_id,
-                            name,
-                            arguments,
-                        },
-                    );
-                }
-                _ => {}
-            }
-        }
-    }
-
-    let finish_reason = parse_response_finish_reason(response);
-    let usage = parse_response_usage(response);
-
-    Ok(OpenAiResponse {
-        model,
-        message: OpenAiAssistantMessage {
-            content: message_content,
-            tool_calls: tool_calls.into_values().collect(),
-        },
-        finish_reason,
-        usage,
-    })
-}
-
-fn parse_message_text(item: &Value) -> String {
-    let mut output = String::new();
-    if let Some(content) = item.get("content").and_then(Value::as_array) {
-        for part in content {
-            if part.get("type").and_then(Value::as_str) == Some("output_text")
-                && let Some(text) = part.get("text").and_then(Value::as_str)
-            {
-                output.push_str(text);
-            }
-        }
-    }
-    output
-}
-
-fn parse_response_finish_reason(response: &Value) -> OpenAiFinishReason {
-    if let Some(reason) = response
-        .get("stop_reason")
-        .and_then(Value::as_str)
-        .map(|value| value.to_ascii_lowercase())
-    {
-        return match reason.as_str() {
-            "stop" => OpenAiFinishReason::Stop,
-            "length" | "max_output_tokens" => OpenAiFinishReason::Length,
-            "tool_calls" | "function_call" => OpenAiFinishReason::ToolCalls,
-            "cancelled" => OpenAiFinishReason::Cancelled,
-            _ => OpenAiFinishReason::Other,
-        };
-    }
 
-    match response
-        .get("status")
-        .and_then(Value::as_str)
-        .unwrap_or_default()
-    {
-        "completed" => OpenAiFinishReason::Stop,
-        "incomplete" => OpenAiFinishReason::Length,
-        "cancelled" => OpenAiFinishReason::Cancelled,
-        _ => OpenAiFinishReason::Other,
-    }
-}
-
-fn parse_response_usage(response: &Value) -> OpenAiUsage {
-    let usage = response.get("usage").unwrap_or(&Value::Null);
-
-    OpenAiUsage {
-        prompt_tokens: usage
-            .get("input_tokens")
-            .and_then(Value::as_u64)
-            .unwrap_or(0) as u32,
-        completion_tokens: usage
-            .get("output_tokens")
-            .and_then(Value::as_u64)
-            .unwrap_or(0) as u32,
-        total_tokens: usage
-            .get("total_tokens")
-            .and_then(Value::as_u64)
-            .unwrap_or(0) as u32,
-    }
-}
-
-fn map_ws_error(event: Value) -> ProviderError {
-    let status = event
-        .get("status")
-        .and_then(Value::as_u64)
-        .unwrap_or_default() as u16;
-
-    let error = event.get("error").unwrap_or(&Value::Null);
-    let code = error
-        .get("code")
-        .and_then(Value::as_str)
-        .unwrap_or_default();
-    let message = error
-        .get("message")
-        .and_then(Value::as_str)
-        .unwrap_or("OpenAI websocket request failed")
-        .to_string();
-
-    match code {
-        "previous_response_not_found" | "websocket_connection_limit_reached" => {
-            ProviderError::invalid_request(message)
-        }
-        _ => match status {
-            401 | 403 => ProviderError::authentication(message),
-            408 | 504 => ProviderError::timeout(message),
-            429 => ProviderError::rate_limited(message),
-            400 | 422 => ProviderError::invalid_request(message),
-            502 | 503 => ProviderError::unavailable(message),
-            _ => ProviderError::transport(message),
-        },
+    fn list_models<'a>(
+        &'a self,
+        auth: OpenAiAuth,
+    ) -> ProviderFuture<'a, Result<Vec<ModelInfo>, ProviderError>> {
+        Box::pin(async move {
+            OpenAiHttpTransport::new(self.http_client.clone())
+                .with_base_url(self.http_base_url())
+                .list_models(auth)
+                .await
+        })
     }
 }
 
@@ -633,5 +545,6 @@ fn auth_cache_key(auth: &OpenAiAuth) -> String {
     match auth {
         OpenAiAuth::ApiKey(value) => format!("api:{}", value.expose()),
         OpenAiAuth::BrowserSession(value) => format!("cookie:{}", value.expose()),
+        OpenAiAuth::BearerToken(value) => format!("bearer:{}", value.expose()),
     }
 }
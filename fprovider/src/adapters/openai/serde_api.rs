@@ -3,7 +3,7 @@
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
-use crate::ProviderError;
+use crate::{ModelInfo, ProviderError, ProviderId};
 
 use super::types::{
     OpenAiAssistantMessage, OpenAiFinishReason, OpenAiMessage, OpenAiRequest, OpenAiResponse,
@@ -16,9 +16,12 @@ pub(crate) enum OpenAiTokenParameter {
     MaxCompletionTokens,
 }
 
+/// `include_stream_usage` asks streams for a final usage chunk through `stream_options`, which
+/// not every OpenAI-compatible server accepts.
 pub(crate) fn build_api_request_with_token_parameter(
     request: OpenAiRequest,
     token_parameter: OpenAiTokenParameter,
+    include_stream_usage: bool,
 ) -> Result<OpenAiApiRequest, ProviderError> {
     let messages = request
         .messages
@@ -55,6 +58,11 @@ pub(crate) fn build_api_request_with_token_parameter(
         max_completion_tokens: (token_parameter == OpenAiTokenParameter::MaxCompletionTokens)
             .then_some(request.max_tokens)
             .flatten(),
+        stream_options: (request.stream && include_stream_usage).then_some(
+            OpenAiApiStreamOptions {
+                include_usage: true,
+            },
+        ),
         stream: request.stream,
     })
 }
@@ -63,12 +71,26 @@ pub(crate) fn parse_finish_reason(value: Option<&str>) -> OpenAiFinishReason {
     match value {
         Some("stop") => OpenAiFinishReason::Stop,
         Some("length") => OpenAiFinishReason::Length,
-        Some("tool_calls") => OpenAiFinishReason::ToolCalls,
+        Some("tool_calls") | Some("function_call") => OpenAiFinishReason::ToolCalls,
         Some("cancelled") => OpenAiFinishReason::Cancelled,
         _ => OpenAiFinishReason::Other,
     }
 }
 
+/// Some OpenAI-compatible servers report `stop` (or nothing) when the turn ended in tool
+/// calls; normalize those to `ToolCalls` so callers can rely on the stop reason.
+pub(crate) fn finish_reason_with_tool_calls(
+    finish_reason: OpenAiFinishReason,
+    has_tool_calls: bool,
+) -> OpenAiFinishReason {
+    match finish_reason {
+        OpenAiFinishReason::Stop | OpenAiFinishReason::Other if has_tool_calls => {
+            OpenAiFinishReason::ToolCalls
+        }
+        other => other,
+    }
+}
+
 pub(crate) fn extract_error_message(body: &str) -> Option<String> {
     let parsed = serde_json::from_str::<OpenAiApiErrorEnvelope>(body).ok()?;
     Some(parsed.error.message)
@@ -97,6 +119,13 @@ pub(crate) struct OpenAiApiRequest {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub max_completion_tokens: Option<u32>,
     pub stream: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stream_options: Option<OpenAiApiStreamOptions>,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct OpenAiApiStreamOptions {
+    pub include_usage: bool,
 }
 
 #[derive(Debug, Serialize)]
@@ -187,11 +216,32 @@ pub(crate) struct OpenAiApiToolFunction {
     pub arguments: String,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Default, Deserialize)]
 pub(crate) struct OpenAiApiUsage {
     pub prompt_tokens: u32,
     pub completion_tokens: u32,
     pub total_tokens: u32,
+    #[serde(default)]
+    pub prompt_tokens_details: Option<OpenAiApiPromptTokensDetails>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub(crate) struct OpenAiApiPromptTokensDetails {
+    #[serde(default)]
+    pub cached_tokens: u32,
+}
+
+impl From<OpenAiApiUsage> for OpenAiUsage {
+    fn from(value: OpenAiApiUsage) -> Self {
+        Self {
+            prompt_tokens: value.prompt_tokens,
+            completion_tokens: value.completion_tokens,
+            total_tokens: value.total_tokens,
+            cached_tokens: value
+                .prompt_tokens_details
+                .map_or(0, |details| details.cached_tokens),
+        }
+    }
 }
 
 impl TryFrom<OpenAiApiResponse> for OpenAiResponse {
@@ -215,24 +265,20 @@ impl TryFrom<OpenAiApiResponse> for OpenAiResponse {
             })
             .collect::<Vec<_>>();
 
-        let usage = value.usage.unwrap_or(OpenAiApiUsage {
-            prompt_tokens: 0,
-            completion_tokens: 0,
-            total_tokens: 0,
-        });
+        let finish_reason = finish_reason_with_tool_calls(
+            parse_finish_reason(choice.finish_reason.as_deref()),
+            !tool_calls.is_empty(),
+        );
 
         Ok(Self {
+            id: None,
             model: value.model,
             message: OpenAiAssistantMessage {
                 content: choice.message.content.unwrap_or_default(),
                 tool_calls,
             },
-            finish_reason: parse_finish_reason(choice.finish_reason.as_deref()),
-            usage: OpenAiUsage {
-                prompt_tokens: usage.prompt_tokens,
-                completion_tokens: usage.completion_tokens,
-                total_tokens: usage.total_tokens,
-            },
+            finish_reason,
+            usage: value.usage.unwrap_or_default().into(),
         })
     }
 }
@@ -241,7 +287,11 @@ impl TryFrom<OpenAiApiResponse> for OpenAiResponse {
 pub(crate) struct OpenAiApiStreamResponse {
     #[serde(default)]
     pub model: Option<String>,
+    #[serde(default)]
     pub choices: Vec<OpenAiApiStreamChoice>,
+    /// Only set on the final chunk, and only when `stream_options.include_usage` was requested.
+    #[serde(default)]
+    pub usage: Option<OpenAiApiUsage>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -268,3 +318,47 @@ pub(crate) struct OpenAiApiDeltaToolFunction {
     pub name: Option<String>,
     pub arguments: Option<String>,
 }
+
+/// `GET /models` page. Anthropic's compatible endpoint paginates with `has_more`/`last_id`.
+#[derive(Debug, Deserialize)]
+pub(crate) struct OpenAiApiModelList {
+    #[serde(default)]
+    pub data: Vec<OpenAiApiModel>,
+    #[serde(default)]
+    pub has_more: bool,
+    #[serde(default)]
+    pub last_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct OpenAiApiModel {
+    pub id: String,
+    #[serde(default)]
+    pub display_name: Option<String>,
+    #[serde(default)]
+    pub owned_by: Option<String>,
+    /// Unix seconds on OpenAI and Azure, an RFC 3339 string on Anthropic.
+    #[serde(default, alias = "created_at")]
+    pub created: Option<Value>,
+    #[serde(default, alias = "context_length")]
+    pub context_window: Option<u32>,
+}
+
+impl From<OpenAiApiModel> for ModelInfo {
+    fn from(value: OpenAiApiModel) -> Self {
+        let created = match value.created {
+            Some(Value::Number(number)) => number.as_u64(),
+            Some(Value::String(timestamp)) => crate::adapters::rfc3339_to_unix_seconds(&timestamp),
+            _ => None,
+        };
+
+        ModelInfo {
+            provider: ProviderId::OpenAi,
+            id: value.id,
+            display_name: value.display_name,
+            owner: value.owned_by,
+            created,
+            context_window: value.context_window,
+        }
+    }
+}
@@ -7,15 +7,22 @@ use std::sync::Arc;
 use futures_util::stream;
 
 use crate::{
-    Message, ModelRequest, ProviderError, ProviderFuture, Role, SecureCredentialManager, ToolResult,
+    HealthStatus, Message, ModelInfo, ModelProvider, ModelRequest, ProviderError,
+    ProviderErrorKind, ProviderFuture, ProviderId, ProviderRegistry, Role, SecretString,
+    SecureCredentialManager, ToolResult,
 };
 
+use super::auth::resolve_openai_auth;
+use super::azure::AzureOpenAiConfig;
 use super::provider::OpenAiProvider;
 use super::serde_api::{
-    OpenAiTokenParameter, build_api_request_with_token_parameter, parse_finish_reason,
+    OpenAiApiStreamResponse, OpenAiTokenParameter, build_api_request_with_token_parameter,
+    finish_reason_with_tool_calls, parse_finish_reason,
+};
+use super::transport::{OpenAiChunkStream, OpenAiHttpTransport, OpenAiTransport};
+use super::types::{
+    OpenAiAuth, OpenAiFinishReason, OpenAiRequest, OpenAiResponse, OpenAiRole, OpenAiUsage,
 };
-use super::transport::{OpenAiChunkStream, OpenAiTransport};
-use super::types::{OpenAiAuth, OpenAiFinishReason, OpenAiRequest, OpenAiResponse, OpenAiRole};
 
 #[derive(Debug)]
 struct NoopTransport;
@@ -41,6 +48,39 @@ impl OpenAiTransport for NoopTransport {
     }
 }
 
+/// Answers the models endpoint like a server that rejects the API key.
+#[derive(Debug)]
+struct RejectingTransport;
+
+impl OpenAiTransport for RejectingTransport {
+    fn complete<'a>(
+        &'a self,
+        _request: OpenAiRequest,
+        _auth: OpenAiAuth,
+    ) -> ProviderFuture<'a, Result<OpenAiResponse, ProviderError>> {
+        Box::pin(async { Err(ProviderError::other("not used")) })
+    }
+
+    fn stream<'a>(
+        &'a self,
+        _request: OpenAiRequest,
+        _auth: OpenAiAuth,
+    ) -> ProviderFuture<'a, Result<OpenAiChunkStream<'a>, ProviderError>> {
+        Box::pin(async { Err(ProviderError::other("not used")) })
+    }
+
+    fn list_models<'a>(
+        &'a self,
+        _auth: OpenAiAuth,
+    ) -> ProviderFuture<'a, Result<Vec<ModelInfo>, ProviderError>> {
+        Box::pin(async {
+            Err(ProviderError::authentication(
+                "http 401 Unauthorized: Incorrect API key provided",
+            ))
+        })
+    }
+}
+
 #[test]
 fn build_openai_request_appends_tool_results_as_tool_messages() {
     let provider = OpenAiProvider::new(
@@ -75,6 +115,53 @@ fn parse_finish_reason_maps_expected_values() {
         OpenAiFinishReason::Other
     );
     assert_eq!(parse_finish_reason(None), OpenAiFinishReason::Other);
+    assert_eq!(
+        finish_reason_with_tool_calls(OpenAiFinishReason::Stop, true),
+        OpenAiFinishReason::ToolCalls
+    );
+    assert_eq!(
+        finish_reason_with_tool_calls(OpenAiFinishReason::Length, true),
+        OpenAiFinishReason::Length
+    );
+}
+
+#[test]
+fn streaming_requests_ask_for_usage_and_parse_the_usage_chunk() {
+    let request = OpenAiRequest {
+        model: "gpt-4o-mini".to_string(),
+        messages: vec![super::types::OpenAiMessage {
+            role: OpenAiRole::User,
+            content: "hi".to_string(),
+            tool_call_id: None,
+        }],
+        tools: Vec::new(),
+        temperature: None,
+        max_tokens: None,
+        stream: true,
+        continuation: None,
+    };
+    let built = build_api_request_with_token_parameter(
+        request.clone(),
+        OpenAiTokenParameter::MaxTokens,
+        true,
+    )
+    .expect("request should build");
+    let body = serde_json::to_value(&built).expect("request should serialize");
+    assert_eq!(body["stream_options"]["include_usage"], true);
+
+    let without_usage =
+        build_api_request_with_token_parameter(request, OpenAiTokenParameter::MaxTokens, false)
+            .expect("request should build");
+    let body = serde_json::to_value(&without_usage).expect("request should serialize");
+    assert!(body.get("stream_options").is_none());
+
+    let chunk: OpenAiApiStreamResponse = serde_json::from_str(
+        r#"{"choices":[],"usage":{"prompt_tokens":10,"completion_tokens":4,"total_tokens":14,"prompt_tokens_details":{"cached_tokens":6}}}"#,
+    )
+    .expect("usage chunk should parse");
+    let usage = OpenAiUsage::from(chunk.usage.expect("usage should be present"));
+    assert_eq!(usage.cached_tokens, 6);
+    assert_eq!(usage.total_tokens, 14);
 }
 
 #[test]
@@ -90,11 +177,13 @@ fn build_api_request_switches_token_parameter_name() {
         temperature: Some(0.0),
         max_tokens: Some(256),
         stream: false,
+        continuation: None,
     };
 
     let legacy = build_api_request_with_token_parameter(
         openai_request.clone(),
         OpenAiTokenParameter::MaxTokens,
+        true,
     )
     .expect("request should build");
     assert_eq!(legacy.max_tokens, Some(256));
@@ -103,8 +192,168 @@ fn build_api_request_switches_token_parameter_name() {
     let modern = build_api_request_with_token_parameter(
         openai_request,
         OpenAiTokenParameter::MaxCompletionTokens,
+        true,
     )
     .expect("request should build");
     assert_eq!(modern.max_tokens, None);
     assert_eq!(modern.max_completion_tokens, Some(256));
 }
+
+#[test]
+fn stream_usage_is_requested_from_openai_and_recent_azure_by_default() {
+    let client = reqwest::Client::new();
+    assert!(OpenAiHttpTransport::new(client.clone()).sends_stream_usage());
+
+    let compatible =
+        OpenAiHttpTransport::new(client.clone()).with_base_url("http://localhost:11434/v1");
+    assert!(!compatible.sends_stream_usage());
+    assert!(compatible.with_stream_usage(true).sends_stream_usage());
+
+    let azure = |api_version: &str| {
+        OpenAiHttpTransport::azure(
+            client.clone(),
+            AzureOpenAiConfig::new("https://contoso.openai.azure.com/")
+                .with_api_version(api_version),
+        )
+        .sends_stream_usage()
+    };
+    assert!(azure(
+        crate::adapters::openai::AZURE_OPENAI_DEFAULT_API_VERSION
+    ));
+    assert!(azure("2024-09-01-preview"));
+    assert!(azure("2025-04-01-preview"));
+    assert!(!azure("2024-06-01"));
+    assert!(!azure("2024-08-01-preview"));
+    assert!(
+        !OpenAiHttpTransport::new(client)
+            .with_stream_usage(false)
+            .sends_stream_usage()
+    );
+}
+
+#[test]
+fn azure_transport_routes_models_to_deployments_with_api_version() {
+    let config = AzureOpenAiConfig::new("https://contoso.openai.azure.com/")
+        .with_api_version("2024-06-01")
+        .with_deployment("gpt-4o", "prod-gpt4o");
+    let transport = OpenAiHttpTransport::azure(reqwest::Client::new(), config);
+
+    assert_eq!(
+        transport.endpoint("gpt-4o", "chat/completions"),
+        "https://contoso.openai.azure.com/openai/deployments/prod-gpt4o/chat/completions?api-version=2024-06-01"
+    );
+    assert_eq!(
+        transport.endpoint("gpt-4o-mini", "chat/completions"),
+        "https://contoso.openai.azure.com/openai/deployments/gpt-4o-mini/chat/completions?api-version=2024-06-01"
+    );
+}
+
+#[test]
+fn azure_transport_sends_api_key_header_and_bearer_entra_tokens() {
+    let client = reqwest::Client::new();
+    let transport = OpenAiHttpTransport::azure(
+        client.clone(),
+        AzureOpenAiConfig::new("https://contoso.openai.azure.com"),
+    );
+
+    let api_key_request = transport
+        .apply_auth(
+            client.post("https://contoso.openai.azure.com"),
+            &OpenAiAuth::ApiKey(SecretString::new("azure-key")),
+        )
+        .expect("api key auth should apply")
+        .build()
+        .expect("request should build");
+    assert_eq!(api_key_request.headers()["api-key"], "azure-key");
+    assert!(api_key_request.headers().get("authorization").is_none());
+
+    let entra_request = transport
+        .apply_auth(
+            client.post("https://contoso.openai.azure.com"),
+            &OpenAiAuth::BearerToken(SecretString::new("entra-token")),
+        )
+        .expect("bearer auth should apply")
+        .build()
+        .expect("request should build");
+    assert_eq!(
+        entra_request.headers()["authorization"],
+        "Bearer entra-token"
+    );
+
+    let error = transport
+        .apply_auth(
+            client.post("https://contoso.openai.azure.com"),
+            &OpenAiAuth::BrowserSession(SecretString::new("session")),
+        )
+        .expect_err("browser sessions should be rejected for azure");
+    assert_eq!(error.kind, ProviderErrorKind::Authentication);
+}
+
+#[test]
+fn resolve_openai_auth_prefers_entra_access_tokens() {
+    let credentials = SecureCredentialManager::new();
+    credentials
+        .set_azure_openai_entra_token("entra-token", None)
+        .expect("token should set");
+
+    let auth = resolve_openai_auth(&credentials).expect("auth should resolve");
+    assert_eq!(
+        auth,
+        OpenAiAuth::BearerToken(SecretString::new("entra-token"))
+    );
+
+    credentials
+        .set_azure_openai_api_key("0123456789abcdef")
+        .expect("key should set");
+    let auth = resolve_openai_auth(&credentials).expect("auth should resolve");
+    assert_eq!(
+        auth,
+        OpenAiAuth::ApiKey(SecretString::new("0123456789abcdef"))
+    );
+}
+
+#[tokio::test]
+async fn health_reports_missing_credentials_as_unhealthy() {
+    let credentials = Arc::new(SecureCredentialManager::new());
+    let provider = OpenAiProvider::new(credentials.clone(), Arc::new(NoopTransport));
+
+    let missing = provider.health().await;
+    assert_eq!(missing.status, HealthStatus::Unhealthy);
+    assert_eq!(
+        missing.error.map(|err| err.kind),
+        Some(ProviderErrorKind::Authentication)
+    );
+
+    credentials
+        .set_openai_api_key("sk-test")
+        .expect("key should store");
+    assert_eq!(provider.health().await.status, HealthStatus::Unknown);
+}
+
+#[tokio::test]
+async fn health_reports_rejected_keys_as_unhealthy() {
+    let credentials = Arc::new(SecureCredentialManager::new());
+    credentials
+        .set_openai_api_key("sk-revoked")
+        .expect("key should store");
+    let provider = OpenAiProvider::new(credentials, Arc::new(RejectingTransport));
+
+    let health = provider.health().await;
+    assert_eq!(health.status, HealthStatus::Unhealthy);
+    assert!(!health.is_ready());
+    assert_eq!(
+        health.error.map(|err| err.kind),
+        Some(ProviderErrorKind::Authentication)
+    );
+
+    let mut registry = ProviderRegistry::new();
+    registry.register(provider);
+    assert!(!registry.is_ready().await);
+    assert_eq!(
+        registry
+            .health(ProviderId::OpenAi)
+            .await
+            .map(|health| health.status),
+        Some(HealthStatus::Unhealthy)
+    );
+}
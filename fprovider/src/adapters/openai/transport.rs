@@ -8,11 +8,13 @@ use futures_core::Stream;
 use futures_util::StreamExt;
 use reqwest::{Client, Response, StatusCode};
 
-use crate::{ProviderError, ProviderFuture};
+use crate::{ModelInfo, ProviderError, ProviderFuture};
 
+use super::azure::AzureOpenAiConfig;
 use super::serde_api::{
-    OpenAiApiStreamResponse, OpenAiTokenParameter, build_api_request_with_token_parameter,
-    extract_error_message, parse_finish_reason,
+    OpenAiApiModelList, OpenAiApiStreamResponse, OpenAiTokenParameter,
+    build_api_request_with_token_parameter, extract_error_message, finish_reason_with_tool_calls,
+    parse_finish_reason,
 };
 use super::types::{
     OpenAiAssistantMessage, OpenAiAuth, OpenAiFinishReason, OpenAiRequest, OpenAiResponse,
@@ -34,47 +36,106 @@ pub trait OpenAiTransport: Send + Sync + std::fmt::Debug {
         request: OpenAiRequest,
         auth: OpenAiAuth,
     ) -> ProviderFuture<'a, Result<OpenAiChunkStream<'a>, ProviderError>>;
+
+    /// Lists the models visible to `auth`. Returned entries carry [`crate::ProviderId::OpenAi`];
+    /// adapters reusing the transport relabel them.
+    fn list_models<'a>(
+        &'a self,
+        _auth: OpenAiAuth,
+    ) -> ProviderFuture<'a, Result<Vec<ModelInfo>, ProviderError>> {
+        Box::pin(async {
+            Err(ProviderError::invalid_request(
+                "model listing is not supported by this transport",
+            ))
+        })
+    }
 }
 
+const OPENAI_BASE_URL: &str = "https://api.openai.com/v1";
+
 #[derive(Debug, Clone)]
 pub struct OpenAiHttpTransport {
     client: Client,
     base_url: String,
+    azure: Option<AzureOpenAiConfig>,
+    stream_usage: Option<bool>,
 }
 
 impl OpenAiHttpTransport {
     pub fn new(client: Client) -> Self {
         Self {
             client,
-            base_url: "https://api.openai.com/v1".to_string(),
+            base_url: OPENAI_BASE_URL.to_string(),
+            azure: None,
+            stream_usage: None,
         }
     }
 
+    /// Creates a transport that targets Azure OpenAI deployments.
+    ///
+    /// Requests are routed to `{endpoint}/openai/deployments/{deployment}/...` with the
+    /// configured `api-version`, and API keys are sent via the `api-key` header.
+    pub fn azure(client: Client, config: AzureOpenAiConfig) -> Self {
+        Self::new(client).with_azure(config)
+    }
+
     pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
         self.base_url = base_url.into();
         self
     }
 
-    fn endpoint(&self, path: &str) -> String {
-        format!("{}/{}", self.base_url.trim_end_matches('/'), path)
+    pub fn with_azure(mut self, config: AzureOpenAiConfig) -> Self {
+        self.azure = Some(config);
+        self
     }
 
-    fn apply_auth(
+    /// Whether streams send `stream_options.include_usage` to get a final usage chunk.
+    ///
+    /// Defaults to on for `api.openai.com` and for Azure api-versions from `2024-09-01`, since
+    /// other OpenAI-compatible servers may reject the field. The Anthropic, Ollama and
+    /// OpenCode Zen default transports opt in.
+    pub fn with_stream_usage(mut self, enabled: bool) -> Self {
+        self.stream_usage = Some(enabled);
+        self
+    }
+
+    pub(super) fn sends_stream_usage(&self) -> bool {
+        self.stream_usage.unwrap_or_else(|| match &self.azure {
+            Some(azure) => azure.supports_stream_usage(),
+            None => self.base_url.trim_end_matches('/') == OPENAI_BASE_URL,
+        })
+    }
+
+    pub(super) fn endpoint(&self, model: &str, path: &str) -> String {
+        match &self.azure {
+            Some(azure) => azure.url(model, path),
+            None => format!("{}/{}", self.base_url.trim_end_matches('/'), path),
+        }
+    }
+
+    pub(super) fn apply_auth(
         &self,
         builder: reqwest::RequestBuilder,
         auth: &OpenAiAuth,
-    ) -> reqwest::RequestBuilder {
-        match auth {
-            OpenAiAuth::ApiKey(key) => builder.bearer_auth(key.expose()),
-            OpenAiAuth::BrowserSession(token) => builder.header(
+    ) -> Result<reqwest::RequestBuilder, ProviderError> {
+        match (auth, self.azure.is_some()) {
+            (OpenAiAuth::ApiKey(key), true) => Ok(builder.header("api-key", key.expose())),
+            (OpenAiAuth::ApiKey(key), false) | (OpenAiAuth::BearerToken(key), _) => {
+                Ok(builder.bearer_auth(key.expose()))
+            }
+            (OpenAiAuth::BrowserSession(_), true) => Err(ProviderError::authentication(
+                "Azure OpenAI does not accept browser session credentials",
+            )),
+            (OpenAiAuth::BrowserSession(token), false) => Ok(builder.header(
                 "Cookie",
                 format!("__Secure-next-auth.session-token={}", token.expose()),
-            ),
+            )),
         }
     }
 
-    async fn parse_error(response: Response) -> ProviderError {
+    pub(super) async fn parse_error(response: Response) -> ProviderError {
         let status = response.status();
+        let retry_after = crate::adapters::retry_after_seconds(response.headers());
         let body = response.text().await.unwrap_or_default();
         let message = extract_error_message(&body)
             .unwrap_or_else(|| format!("OpenAI request failed with status {status}"));
@@ -83,7 +144,13 @@ impl OpenAiHttpTransport {
             StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => {
                 ProviderError::authentication(message)
             }
-            StatusCode::TOO_MANY_REQUESTS => ProviderError::rate_limited(message),
+            StatusCode::TOO_MANY_REQUESTS => {
+                let error = ProviderError::rate_limited(message);
+                match retry_after {
+                    Some(retry_after) => error.with_retry_after(retry_after),
+                    None => error,
+                }
+            }
             StatusCode::REQUEST_TIMEOUT | StatusCode::GATEWAY_TIMEOUT => {
                 ProviderError::timeout(message)
             }
@@ -109,12 +176,15 @@ impl OpenAiTransport for OpenAiHttpTransport {
                 OpenAiTokenParameter::MaxTokens,
                 OpenAiTokenParameter::MaxCompletionTokens,
             ] {
-                let api_request =
-                    build_api_request_with_token_parameter(request.clone(), token_parameter)?;
-                let url = self.endpoint("chat/completions");
+                let api_request = build_api_request_with_token_parameter(
+                    request.clone(),
+                    token_parameter,
+                    self.sends_stream_usage(),
+                )?;
+                let url = self.endpoint(&request.model, "chat/completions");
                 let builder = self.client.post(url).json(&api_request);
                 let response = self
-                    .apply_auth(builder, &auth)
+                    .apply_auth(builder, &auth)?
                     .send()
                     .await
                     .map_err(|err| {
@@ -161,12 +231,15 @@ impl OpenAiTransport for OpenAiHttpTransport {
                 OpenAiTokenParameter::MaxTokens,
                 OpenAiTokenParameter::MaxCompletionTokens,
             ] {
-                let api_request =
-                    build_api_request_with_token_parameter(request.clone(), token_parameter)?;
-                let url = self.endpoint("chat/completions");
+                let api_request = build_api_request_with_token_parameter(
+                    request.clone(),
+                    token_parameter,
+                    self.sends_stream_usage(),
+                )?;
+                let url = self.endpoint(&request.model, "chat/completions");
                 let builder = self.client.post(url).json(&api_request);
                 let next_response =
-                    self.apply_auth(builder, &auth)
+                    self.apply_auth(builder, &auth)?
                         .send()
                         .await
                         .map_err(|err| {
@@ -205,6 +278,7 @@ impl OpenAiTransport for OpenAiHttpTransport {
                 let mut tool_calls: BTreeMap<u32, OpenAiToolCall> = BTreeMap::new();
                 let mut model = None::<String>;
                 let mut finish_reason = OpenAiFinishReason::Other;
+                let mut usage = OpenAiUsage::default();
 
                 while let Some(item) = chunks.next().await {
                     let bytes = item.map_err(|err| ProviderError::transport(err.to_string()))?;
@@ -233,6 +307,11 @@ impl OpenAiTransport for OpenAiHttpTransport {
                             model = Some(stream_model);
                         }
 
+                        if let Some(chunk_usage) = parsed.usage {
+                            usage = chunk_usage.into();
+                            yield OpenAiStreamChunk::Usage(usage);
+                        }
+
                         if let Some(choice) = parsed.choices.first() {
                             if let Some(delta_content) = &choice.delta.content
                                 && !delta_content.is_empty()
@@ -287,21 +366,70 @@ impl OpenAiTransport for OpenAiHttpTransport {
                     content,
                     tool_calls: tool_calls.into_values().collect(),
                 };
+                let finish_reason =
+                    finish_reason_with_tool_calls(finish_reason, !final_message.tool_calls.is_empty());
 
                 yield OpenAiStreamChunk::MessageComplete(final_message.clone());
                 yield OpenAiStreamChunk::ResponseComplete(OpenAiResponse {
+                    id: None,
                     model: model.unwrap_or(model_for_fallback),
                     message: final_message,
                     finish_reason,
-                    usage: OpenAiUsage {
-                        prompt_tokens: 0,
-                        completion_tokens: 0,
-                        total_tokens: 0,
-                    },
+                    usage,
                 });
             };
 
             Ok(Box::pin(stream) as OpenAiChunkStream<'a>)
         })
     }
+
+    fn list_models<'a>(
+        &'a self,
+        auth: OpenAiAuth,
+    ) -> ProviderFuture<'a, Result<Vec<ModelInfo>, ProviderError>> {
+        Box::pin(async move {
+            let url = match &self.azure {
+                Some(azure) => azure.models_url(),
+                None => format!("{}/models", self.base_url.trim_end_matches('/')),
+            };
+
+            let mut models = Vec::new();
+            let mut after_id = None::<String>;
+            loop {
+                let mut builder = self.client.get(&url);
+                if let Some(after_id) = &after_id {
+                    builder = builder.query(&[("after_id", after_id)]);
+                }
+                let response = self
+                    .apply_auth(builder, &auth)?
+                    .send()
+                    .await
+                    .map_err(|err| {
+                        if err.is_timeout() {
+                            ProviderError::timeout(err.to_string())
+                        } else {
+                            ProviderError::transport(err.to_string())
+                        }
+                    })?;
+
+                if !response.status().is_success() {
+                    return Err(Self::parse_error(response).await);
+                }
+
+                let page: OpenAiApiModelList = response
+                    .json()
+                    .await
+                    .map_err(|err| ProviderError::transport(err.to_string()))?;
+                models.extend(page.data.into_iter().map(ModelInfo::from));
+
+                match page.last_id {
+                    Some(last_id) if page.has_more => after_id = Some(last_id),
+                    _ => break,
+                }
+            }
+
+            models.sort_by(|left, right| left.id.cmp(&right.id));
+            Ok(models)
+        })
+    }
 }
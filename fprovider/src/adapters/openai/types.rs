@@ -3,8 +3,8 @@
 use std::fmt::Formatter;
 
 use crate::{
-    Message, ModelResponse, OutputItem, ProviderId, Role, SecretString, StopReason, StreamEvent,
-    TokenUsage, ToolCall, ToolDefinition, ToolResult,
+    Message, ModelResponse, OutputItem, ProviderId, ResponseContinuation, Role, SecretString,
+    StopReason, StreamEvent, TokenUsage, ToolCall, ToolDefinition, ToolResult,
 };
 
 #[derive(Debug, Clone, PartialEq)]
@@ -15,6 +15,8 @@ pub struct OpenAiRequest {
     pub temperature: Option<f32>,
     pub max_tokens: Option<u32>,
     pub stream: bool,
+    /// Only honored by transports that support stateful continuation (Responses API).
+    pub continuation: Option<ResponseContinuation>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -93,6 +95,7 @@ impl From<ToolDefinition> for OpenAiTool {
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct OpenAiResponse {
+    pub id: Option<String>,
     pub model: String,
     pub message: OpenAiAssistantMessage,
     pub finish_reason: OpenAiFinishReason,
@@ -122,6 +125,7 @@ impl OpenAiResponse {
             output,
             stop_reason: self.finish_reason.into(),
             usage: self.usage.into(),
+            response_id: self.id,
         }
     }
 }
@@ -170,11 +174,13 @@ impl From<OpenAiFinishReason> for StopReason {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub struct OpenAiUsage {
     pub prompt_tokens: u32,
     pub completion_tokens: u32,
     pub total_tokens: u32,
+    /// Prompt tokens served from the provider's prompt cache.
+    pub cached_tokens: u32,
 }
 
 impl From<OpenAiUsage> for TokenUsage {
@@ -183,6 +189,7 @@ impl From<OpenAiUsage> for TokenUsage {
             input_tokens: value.prompt_tokens,
             output_tokens: value.completion_tokens,
             total_tokens: value.total_tokens,
+            cached_input_tokens: value.cached_tokens,
         }
     }
 }
@@ -191,6 +198,8 @@ impl From<OpenAiUsage> for TokenUsage {
 pub enum OpenAiAuth {
     ApiKey(SecretString),
     BrowserSession(SecretString),
+    /// OAuth bearer token, used for Microsoft Entra ID auth against Azure OpenAI.
+    BearerToken(SecretString),
 }
 
 impl std::fmt::Debug for OpenAiAuth {
@@ -198,6 +207,7 @@ impl std::fmt::Debug for OpenAiAuth {
         match self {
             Self::ApiKey(_) => f.write_str("OpenAiAuth::ApiKey([REDACTED])"),
             Self::BrowserSession(_) => f.write_str("OpenAiAuth::BrowserSession([REDACTED])"),
+            Self::BearerToken(_) => f.write_str("OpenAiAuth::BearerToken([REDACTED])"),
         }
     }
 }
@@ -208,6 +218,8 @@ pub enum OpenAiStreamChunk {
     ToolCallDelta(OpenAiToolCall),
     MessageComplete(OpenAiAssistantMessage),
     ResponseComplete(OpenAiResponse),
+    /// Interim usage report, sent before `ResponseComplete` when the API provides one.
+    Usage(OpenAiUsage),
 }
 
 impl From<OpenAiStreamChunk> for StreamEvent {
@@ -221,6 +233,7 @@ impl From<OpenAiStreamChunk> for StreamEvent {
             OpenAiStreamChunk::ResponseComplete(response) => {
                 Self::ResponseComplete(response.into_model_response())
             }
+            OpenAiStreamChunk::Usage(usage) => Self::Usage(usage.into()),
         }
     }
 }
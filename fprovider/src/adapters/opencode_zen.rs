@@ -1,19 +1,20 @@
 //! OpenCode Zen provider implemented via OpenAI-compatible transport.
 
 use std::sync::Arc;
+use std::time::Instant;
 
 use async_stream::try_stream;
 use futures_util::StreamExt;
-use reqwest::{Client, StatusCode};
-use serde::Deserialize;
+use reqwest::Client;
 
 use crate::adapters::openai::{
     OpenAiAuth, OpenAiHttpTransport, OpenAiMessage, OpenAiRequest, OpenAiStreamChunk, OpenAiTool,
     OpenAiTransport,
 };
 use crate::{
-    BoxedEventStream, Message, ModelProvider, ModelRequest, ModelResponse, ProviderError,
-    ProviderFuture, ProviderId, Role, SecureCredentialManager, StreamEvent,
+    BoxedEventStream, Message, ModelInfo, ModelProvider, ModelRequest, ModelResponse,
+    ProviderError, ProviderErrorKind, ProviderFuture, ProviderHealth, ProviderId, Role,
+    SecretString, SecureCredentialManager, StreamEvent,
 };
 
 pub const OPENCODE_ZEN_BASE_URL: &str = "https://opencode.ai/zen/v1";
@@ -44,12 +45,9 @@ impl OpenCodeZenProvider {
     }
 
     pub fn default_http_transport(client: Client) -> OpenAiHttpTransport {
-        OpenAiHttpTransport::new(client).with_base_url(OPENCODE_ZEN_BASE_URL)
-    }
-
-    pub async fn list_models(&self) -> Result<Vec<String>, ProviderError> {
-        let key = resolve_zen_api_key(&self.credentials)?;
-        list_zen_models_with_api_key(key.expose()).await
+        OpenAiHttpTransport::new(client)
+            .with_base_url(OPENCODE_ZEN_BASE_URL)
+            .with_stream_usage(true)
     }
 
     fn build_request(&self, request: ModelRequest, stream: bool) -> OpenAiRequest {
@@ -82,6 +80,7 @@ impl OpenCodeZenProvider {
             temperature: request.options.temperature,
             max_tokens: request.options.max_tokens,
             stream,
+            continuation: None,
         }
     }
 }
@@ -97,9 +96,16 @@ impl ModelProvider for OpenCodeZenProvider {
     ) -> ProviderFuture<'a, Result<ModelResponse, ProviderError>> {
         Box::pin(async move {
             request.validate()?;
-            let auth = OpenAiAuth::ApiKey(resolve_zen_api_key(&self.credentials)?);
+            let api_key = resolve_zen_api_key(&self.credentials)?;
             let zen_request = self.build_request(request, false);
-            let response = self.transport.complete(zen_request, auth).await?;
+            let response = self
+                .transport
+                .complete(zen_request, OpenAiAuth::ApiKey(api_key.clone()))
+                .await
+                .inspect_err(|err| {
+                    self.credentials
+                        .observe_error(ProviderId::OpenCodeZen, api_key.expose(), err)
+                })?;
 
             let mut mapped = response.into_model_response();
             mapped.provider = ProviderId::OpenCodeZen;
@@ -113,19 +119,51 @@ impl ModelProvider for OpenCodeZenProvider {
     ) -> ProviderFuture<'a, Result<BoxedEventStream<'a>, ProviderError>> {
         Box::pin(async move {
             request.validate()?;
-            let auth = OpenAiAuth::ApiKey(resolve_zen_api_key(&self.credentials)?);
+            let api_key = resolve_zen_api_key(&self.credentials)?;
             let zen_request = self.build_request(request, true);
-            let mut chunks = self.transport.stream(zen_request, auth).await?;
+            let auth = OpenAiAuth::ApiKey(api_key.clone());
+            let observe = move |err: &ProviderError| {
+                self.credentials
+                    .observe_error(ProviderId::OpenCodeZen, api_key.expose(), err)
+            };
+            let mut chunks = self
+                .transport
+                .stream(zen_request, auth)
+                .await
+                .inspect_err(&observe)?;
 
             let stream = try_stream! {
                 while let Some(chunk) = chunks.next().await {
-                    yield map_stream_chunk(chunk?);
+                    yield map_stream_chunk(chunk.inspect_err(&observe)?);
                 }
             };
 
             Ok(Box::pin(stream) as BoxedEventStream<'a>)
         })
     }
+
+    fn health<'a>(&'a self) -> ProviderFuture<'a, ProviderHealth> {
+        Box::pin(async move {
+            let started = Instant::now();
+            let outcome = self.list_models().await.map(|_| ());
+            ProviderHealth::from_probe(ProviderId::OpenCodeZen, started, outcome)
+        })
+    }
+
+    fn list_models<'a>(&'a self) -> ProviderFuture<'a, Result<Vec<ModelInfo>, ProviderError>> {
+        Box::pin(async move {
+            let api_key = resolve_zen_api_key(&self.credentials)?;
+            let models = self
+                .transport
+                .list_models(OpenAiAuth::ApiKey(api_key.clone()))
+                .await
+                .inspect_err(|err| {
+                    self.credentials
+                        .observe_error(ProviderId::OpenCodeZen, api_key.expose(), err)
+                })?;
+            Ok(relabel_zen_models(models))
+        })
+    }
 }
 
 impl SecureCredentialManager {
@@ -139,7 +177,7 @@ impl SecureCredentialManager {
 
 pub async fn list_zen_models_with_api_key(
     api_key: impl Into<String>,
-) -> Result<Vec<String>, ProviderError> {
+) -> Result<Vec<ModelInfo>, ProviderError> {
     let key = api_key.into();
     let trimmed = key.trim();
     if trimmed.is_empty() {
@@ -148,42 +186,27 @@ pub async fn list_zen_models_with_api_key(
         ));
     }
 
-    let response = Client::new()
-        .get(OPENCODE_ZEN_MODELS_URL)
-        .bearer_auth(trimmed)
-        .send()
+    let models = OpenAiHttpTransport::new(Client::new())
+        .with_base_url(OPENCODE_ZEN_BASE_URL)
+        .list_models(OpenAiAuth::ApiKey(SecretString::new(trimmed)))
         .await
-        .map_err(|err| {
-            if err.is_timeout() {
-                ProviderError::timeout(err.to_string())
-            } else {
-                ProviderError::transport(err.to_string())
+        .map_err(|err| match err.kind {
+            ProviderErrorKind::Authentication => {
+                ProviderError::authentication("OpenCode Zen API key is invalid or expired")
             }
+            _ => err,
         })?;
+    Ok(relabel_zen_models(models))
+}
 
-    if response.status() == StatusCode::UNAUTHORIZED || response.status() == StatusCode::FORBIDDEN {
-        return Err(ProviderError::authentication(
-            "OpenCode Zen API key is invalid or expired",
-        ));
-    }
-
-    if !response.status().is_success() {
-        let code = response.status();
-        let body = response.text().await.unwrap_or_default();
-        return Err(ProviderError::transport(format!(
-            "http {code}: {}",
-            truncate(&body, 4096)
-        )));
-    }
-
-    let parsed = response
-        .json::<ZenModelsResponse>()
-        .await
-        .map_err(|err| ProviderError::transport(err.to_string()))?;
-
-    let mut ids = parsed.data.into_iter().map(|m| m.id).collect::<Vec<_>>();
-    ids.sort();
-    Ok(ids)
+fn relabel_zen_models(models: Vec<ModelInfo>) -> Vec<ModelInfo> {
+    models
+        .into_iter()
+        .map(|model| ModelInfo {
+            provider: ProviderId::OpenCodeZen,
+            ..model
+        })
+        .collect()
 }
 
 fn resolve_zen_api_key(
@@ -206,24 +229,6 @@ fn map_stream_chunk(chunk: OpenAiStreamChunk) -> StreamEvent {
             mapped.provider = ProviderId::OpenCodeZen;
             StreamEvent::ResponseComplete(mapped)
         }
+        OpenAiStreamChunk::Usage(usage) => StreamEvent::Usage(usage.into()),
     }
 }
-
-fn truncate(input: &str, max: usize) -> String {
-    if input.len() <= max {
-        return input.to_string();
-    }
-    let mut output = input[..max].to_string();
-    output.push_str("...");
-    output
-}
-
-#[derive(Debug, Deserialize)]
-struct ZenModelsResponse {
-    data: Vec<ZenModel>,
-}
-
-#[derive(Debug, Deserialize)]
-struct ZenModel {
-    id: String,
-}
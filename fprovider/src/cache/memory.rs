@@ -0,0 +1,141 @@
+//! In-process LRU response cache.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+
+use super::{CacheKey, CachedResponse, ResponseCache};
+use crate::ProviderError;
+
+/// Bounded in-memory cache that evicts the least recently used entry once full.
+///
+/// Entries never expire unless a TTL is set with [`InMemoryResponseCache::with_ttl`].
+#[derive(Debug)]
+pub struct InMemoryResponseCache {
+    capacity: usize,
+    ttl: Option<Duration>,
+    state: Mutex<LruState>,
+}
+
+#[derive(Debug, Default)]
+struct LruState {
+    entries: HashMap<CacheKey, LruEntry>,
+    recency: BTreeMap<u64, CacheKey>,
+    tick: u64,
+}
+
+#[derive(Debug)]
+struct LruEntry {
+    response: CachedResponse,
+    expires_at: Option<SystemTime>,
+    last_used: u64,
+}
+
+impl LruState {
+    fn touch(&mut self, key: &CacheKey) {
+        self.tick += 1;
+        let tick = self.tick;
+        if let Some(entry) = self.entries.get_mut(key) {
+            self.recency.remove(&entry.last_used);
+            entry.last_used = tick;
+            self.recency.insert(tick, key.clone());
+        }
+    }
+
+    fn remove(&mut self, key: &CacheKey) {
+        if let Some(entry) = self.entries.remove(key) {
+            self.recency.remove(&entry.last_used);
+        }
+    }
+}
+
+impl InMemoryResponseCache {
+    /// Creates a cache holding at most `capacity` entries (minimum one).
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            ttl: None,
+            state: Mutex::new(LruState::default()),
+        }
+    }
+
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = Some(ttl);
+        self
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn len(&self) -> usize {
+        self.state
+            .lock()
+            .map(|state| state.entries.len())
+            .unwrap_or(0)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn state(&self) -> Result<std::sync::MutexGuard<'_, LruState>, ProviderError> {
+        self.state
+            .lock()
+            .map_err(|_| ProviderError::other("response cache lock poisoned"))
+    }
+}
+
+impl ResponseCache for InMemoryResponseCache {
+    fn get(&self, key: &CacheKey) -> Result<Option<CachedResponse>, ProviderError> {
+        let mut state = self.state()?;
+        let expired = match state.entries.get(key) {
+            Some(entry) => entry
+                .expires_at
+                .is_some_and(|expires_at| SystemTime::now() >= expires_at),
+            None => return Ok(None),
+        };
+
+        if expired {
+            state.remove(key);
+            return Ok(None);
+        }
+
+        state.touch(key);
+        Ok(state.entries.get(key).map(|entry| entry.response.clone()))
+    }
+
+    fn put(&self, key: &CacheKey, response: CachedResponse) -> Result<(), ProviderError> {
+        let mut state = self.state()?;
+        state.remove(key);
+        while state.entries.len() >= self.capacity {
+            let Some((_, oldest)) = state.recency.pop_first() else {
+                break;
+            };
+            state.entries.remove(&oldest);
+        }
+
+        state.entries.insert(
+            key.clone(),
+            LruEntry {
+                response,
+                expires_at: self.ttl.map(|ttl| SystemTime::now() + ttl),
+                last_used: 0,
+            },
+        );
+        state.touch(key);
+        Ok(())
+    }
+
+    fn remove(&self, key: &CacheKey) -> Result<(), ProviderError> {
+        self.state()?.remove(key);
+        Ok(())
+    }
+
+    fn clear(&self) -> Result<(), ProviderError> {
+        let mut state = self.state()?;
+        state.entries.clear();
+        state.recency.clear();
+        Ok(())
+    }
+}
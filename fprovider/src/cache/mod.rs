@@ -0,0 +1,401 @@
+//! Response caching around [`ModelProvider`] for repeated, deterministic requests.
+//!
+//! [`CachingProvider`] keys every call on a canonical hash of the request (see
+//! [`CacheKey::for_request`]) and serves repeats from a [`ResponseCache`]. Completions are
+//! stored as the final [`ModelResponse`]; streams are stored as the full event sequence and
+//! replayed in order on a hit.
+//!
+//! By default only requests that pin `temperature` to `0.0` are cached, and responses served
+//! from the cache never carry a `response_id`, so callers cannot continue from a provider
+//! response that was produced for someone else.
+//!
+//! ```rust
+//! use std::sync::Arc;
+//!
+//! use fprovider::{CachingProvider, InMemoryResponseCache, ModelProvider};
+//!
+//! fn cached(provider: Arc<dyn ModelProvider>) -> CachingProvider {
+//!     CachingProvider::new(provider, Arc::new(InMemoryResponseCache::new(256)))
+//! }
+//! ```
+
+mod memory;
+#[cfg(feature = "response-cache-sqlite")]
+mod sqlite;
+
+use std::fmt::{self, Display, Formatter};
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use futures_core::Stream;
+
+use crate::{
+    BoxedEventStream, ModelInfo, ModelProvider, ModelRequest, ModelResponse, NoopOperationHooks,
+    ProviderError, ProviderFuture, ProviderHealth, ProviderId, ProviderLayer,
+    ProviderOperationHooks, Role, StreamEvent, VecEventStream,
+};
+
+pub use memory::InMemoryResponseCache;
+#[cfg(feature = "response-cache-sqlite")]
+pub use sqlite::SqliteResponseCache;
+
+const FNV_OFFSET_BASIS: u128 = 0x6c62272e07bb014262b821756295c58d;
+const FNV_PRIME: u128 = 0x0000000001000000000000000000013b;
+
+/// Stable identifier for a cacheable provider call.
+///
+/// The key is a 128-bit FNV-1a hash, rendered as hex, over the provider id, the operation,
+/// and the request's model, messages, generation options, tools and tool results. Request
+/// metadata, the `stream` flag and any [`ResponseContinuation`] are left out because they do
+/// not change what the model is asked to produce.
+///
+/// [`ResponseContinuation`]: crate::ResponseContinuation
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CacheKey(String);
+
+impl CacheKey {
+    pub fn for_request(provider: ProviderId, operation: &str, request: &ModelRequest) -> Self {
+        let mut hasher = KeyHasher::new();
+        hasher.write_str(&provider.to_string());
+        hasher.write_str(operation);
+        hasher.write_str(&request.model);
+
+        hasher.write_len(request.messages.len());
+        for message in &request.messages {
+            hasher.write_str(role_tag(message.role));
+            hasher.write_str(&message.content);
+        }
+
+        match request.options.temperature {
+            Some(temperature) => {
+                hasher.write_bytes(&[1]);
+                hasher.write_bytes(&temperature.to_bits().to_le_bytes());
+            }
+            None => hasher.write_bytes(&[0]),
+        }
+        match request.options.max_tokens {
+            Some(max_tokens) => {
+                hasher.write_bytes(&[1]);
+                hasher.write_bytes(&max_tokens.to_le_bytes());
+            }
+            None => hasher.write_bytes(&[0]),
+        }
+
+        hasher.write_len(request.tools.len());
+        for tool in &request.tools {
+            hasher.write_str(&tool.name);
+            hasher.write_str(&tool.description);
+            hasher.write_str(&tool.input_schema);
+        }
+
+        hasher.write_len(request.tool_results.len());
+        for result in &request.tool_results {
+            hasher.write_str(&result.tool_call_id);
+            hasher.write_str(&result.output);
+        }
+
+        Self(format!("{:032x}", hasher.finish()))
+    }
+
+    pub fn as_str(&self) -> &str {
+        self.0.as_str()
+    }
+}
+
+impl Display for CacheKey {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+struct KeyHasher(u128);
+
+impl KeyHasher {
+    fn new() -> Self {
+        Self(FNV_OFFSET_BASIS)
+    }
+
+    fn write_bytes(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= u128::from(*byte);
+            self.0 = self.0.wrapping_mul(FNV_PRIME);
+        }
+    }
+
+    fn write_len(&mut self, len: usize) {
+        self.write_bytes(&(len as u64).to_le_bytes());
+    }
+
+    fn write_str(&mut self, value: &str) {
+        self.write_len(value.len());
+        self.write_bytes(value.as_bytes());
+    }
+
+    fn finish(&self) -> u128 {
+        self.0
+    }
+}
+
+fn role_tag(role: Role) -> &'static str {
+    match role {
+        Role::System => "system",
+        Role::User => "user",
+        Role::Assistant => "assistant",
+        Role::Tool => "tool",
+    }
+}
+
+/// A stored provider result.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CachedResponse {
+    Completion(ModelResponse),
+    /// Every event of a stream that finished without errors.
+    Stream(Vec<StreamEvent>),
+}
+
+/// Storage backend for [`CachingProvider`].
+///
+/// Backends own their expiry policy; expired entries must read as misses.
+pub trait ResponseCache: Send + Sync {
+    fn get(&self, key: &CacheKey) -> Result<Option<CachedResponse>, ProviderError>;
+
+    fn put(&self, key: &CacheKey, response: CachedResponse) -> Result<(), ProviderError>;
+
+    fn remove(&self, key: &CacheKey) -> Result<(), ProviderError>;
+
+    fn clear(&self) -> Result<(), ProviderError>;
+}
+
+/// Serves repeated requests from a [`ResponseCache`] instead of the wrapped provider.
+///
+/// The cache is best-effort: a backend error on lookup is treated as a miss and an error on
+/// store is dropped, so a broken cache never fails a call. Provider errors and streams that
+/// end in an error or without a `ResponseComplete` event are never stored. Each lookup is
+/// reported through [`ProviderOperationHooks::on_cache_hit`] or
+/// [`ProviderOperationHooks::on_cache_miss`] with the operation name `"complete"` or
+/// `"stream"`.
+///
+/// Only deterministic requests (`temperature` of `0.0`) are cached unless
+/// [`CachingProvider::with_deterministic_only`] opts out. Cache hits clear `response_id` on
+/// the returned response.
+pub struct CachingProvider {
+    inner: Arc<dyn ModelProvider>,
+    cache: Arc<dyn ResponseCache>,
+    hooks: Arc<dyn ProviderOperationHooks>,
+    deterministic_only: bool,
+}
+
+impl CachingProvider {
+    pub fn new(inner: Arc<dyn ModelProvider>, cache: Arc<dyn ResponseCache>) -> Self {
+        Self {
+            inner,
+            cache,
+            hooks: Arc::new(NoopOperationHooks),
+            deterministic_only: true,
+        }
+    }
+
+    pub fn with_hooks(mut self, hooks: Arc<dyn ProviderOperationHooks>) -> Self {
+        self.hooks = hooks;
+        self
+    }
+
+    /// Only cache requests that pin `temperature` to `0.0`; everything else passes through
+    /// without a lookup. Enabled by default; pass `false` to also cache sampled requests.
+    pub fn with_deterministic_only(mut self, deterministic_only: bool) -> Self {
+        self.deterministic_only = deterministic_only;
+        self
+    }
+
+    pub fn cache(&self) -> &Arc<dyn ResponseCache> {
+        &self.cache
+    }
+
+    fn is_cacheable(&self, request: &ModelRequest) -> bool {
+        !self.deterministic_only || request.options.temperature == Some(0.0)
+    }
+
+    fn lookup(&self, operation: &str, key: &CacheKey) -> Option<CachedResponse> {
+        let provider = self.id();
+        match self.cache.get(key).ok().flatten() {
+            Some(cached) => {
+                self.hooks.on_cache_hit(provider, operation);
+                Some(cached)
+            }
+            None => {
+                self.hooks.on_cache_miss(provider, operation);
+                None
+            }
+        }
+    }
+}
+
+impl fmt::Debug for CachingProvider {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CachingProvider")
+            .field("provider", &self.inner.id())
+            .field("deterministic_only", &self.deterministic_only)
+            .finish_non_exhaustive()
+    }
+}
+
+impl ModelProvider for CachingProvider {
+    fn id(&self) -> ProviderId {
+        self.inner.id()
+    }
+
+    fn complete<'a>(
+        &'a self,
+        request: ModelRequest,
+    ) -> ProviderFuture<'a, Result<ModelResponse, ProviderError>> {
+        Box::pin(async move {
+            if !self.is_cacheable(&request) {
+                return self.inner.complete(request).await;
+            }
+
+            let key = CacheKey::for_request(self.id(), "complete", &request);
+            if let Some(CachedResponse::Completion(response)) = self.lookup("complete", &key) {
+                return Ok(without_response_id(response));
+            }
+
+            let response = self.inner.complete(request).await?;
+            let _ = self
+                .cache
+                .put(&key, CachedResponse::Completion(response.clone()));
+            Ok(response)
+        })
+    }
+
+    fn stream<'a>(
+        &'a self,
+        request: ModelRequest,
+    ) -> ProviderFuture<'a, Result<BoxedEventStream<'a>, ProviderError>> {
+        Box::pin(async move {
+            if !self.is_cacheable(&request) {
+                return self.inner.stream(request).await;
+            }
+
+            let key = CacheKey::for_request(self.id(), "stream", &request);
+            if let Some(CachedResponse::Stream(events)) = self.lookup("stream", &key) {
+                let replay = events
+                    .into_iter()
+                    .map(|event| match event {
+                        StreamEvent::ResponseComplete(response) => {
+                            Ok(StreamEvent::ResponseComplete(without_response_id(response)))
+                        }
+                        event => Ok(event),
+                    })
+                    .collect();
+                return Ok(Box::pin(VecEventStream::new(replay)) as BoxedEventStream<'a>);
+            }
+
+            let inner = self.inner.stream(request).await?;
+            Ok(Box::pin(RecordingStream {
+                inner,
+                cache: Arc::clone(&self.cache),
+                key,
+                events: Vec::new(),
+                failed: false,
+            }) as BoxedEventStream<'a>)
+        })
+    }
+
+    fn health<'a>(&'a self) -> ProviderFuture<'a, ProviderHealth> {
+        self.inner.health()
+    }
+
+    fn list_models<'a>(&'a self) -> ProviderFuture<'a, Result<Vec<ModelInfo>, ProviderError>> {
+        self.inner.list_models()
+    }
+}
+
+/// A cached response was produced for an earlier caller, so its provider-side state must not
+/// be continued from.
+fn without_response_id(mut response: ModelResponse) -> ModelResponse {
+    response.response_id = None;
+    response
+}
+
+struct RecordingStream<'a> {
+    inner: BoxedEventStream<'a>,
+    cache: Arc<dyn ResponseCache>,
+    key: CacheKey,
+    events: Vec<StreamEvent>,
+    failed: bool,
+}
+
+impl Stream for RecordingStream<'_> {
+    type Item = Result<StreamEvent, ProviderError>;
+
+    fn poll_next(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<StreamEvent, ProviderError>>> {
+        let next = self.inner.as_mut().poll_next(cx);
+        match &next {
+            Poll::Ready(Some(Ok(event))) if !self.failed => self.events.push(event.clone()),
+            Poll::Ready(Some(Err(_))) => self.failed = true,
+            Poll::Ready(None) if !self.failed => {
+                // Mark as done so a stream polled past its end does not store twice.
+                self.failed = true;
+                let events = std::mem::take(&mut self.events);
+                if matches!(events.last(), Some(StreamEvent::ResponseComplete(_))) {
+                    let _ = self.cache.put(&self.key, CachedResponse::Stream(events));
+                }
+            }
+            _ => {}
+        }
+        next
+    }
+}
+
+/// [`ProviderLayer`] that wraps providers in a [`CachingProvider`] sharing one cache.
+#[derive(Clone)]
+pub struct CacheLayer {
+    cache: Arc<dyn ResponseCache>,
+    hooks: Arc<dyn ProviderOperationHooks>,
+    deterministic_only: bool,
+}
+
+impl CacheLayer {
+    pub fn new(cache: Arc<dyn ResponseCache>) -> Self {
+        Self {
+            cache,
+            hooks: Arc::new(NoopOperationHooks),
+            deterministic_only: true,
+        }
+    }
+
+    pub fn with_hooks(mut self, hooks: Arc<dyn ProviderOperationHooks>) -> Self {
+        self.hooks = hooks;
+        self
+    }
+
+    /// See [`CachingProvider::with_deterministic_only`]; enabled by default.
+    pub fn with_deterministic_only(mut self, deterministic_only: bool) -> Self {
+        self.deterministic_only = deterministic_only;
+        self
+    }
+}
+
+impl fmt::Debug for CacheLayer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CacheLayer")
+            .field("deterministic_only", &self.deterministic_only)
+            .finish_non_exhaustive()
+    }
+}
+
+impl ProviderLayer for CacheLayer {
+    fn layer(&self, inner: Arc<dyn ModelProvider>) -> Arc<dyn ModelProvider> {
+        Arc::new(
+            CachingProvider::new(inner, Arc::clone(&self.cache))
+                .with_hooks(Arc::clone(&self.hooks))
+                .with_deterministic_only(self.deterministic_only),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests;
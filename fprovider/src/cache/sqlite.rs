@@ -0,0 +1,456 @@
+//! SQLite-backed response cache with per-entry expiry.
+
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use rusqlite::{Connection, OptionalExtension, params};
+use serde::{Deserialize, Serialize};
+
+use super::{CacheKey, CachedResponse, ResponseCache};
+use crate::{
+    Message, ModelResponse, OutputItem, ProviderError, ProviderId, Role, StopReason, StreamEvent,
+    TokenUsage, ToolCall,
+};
+
+const KIND_COMPLETION: &str = "completion";
+const KIND_STREAM: &str = "stream";
+
+/// Persistent cache stored in a single SQLite table.
+///
+/// Entries written with a TTL carry an absolute expiry; expired rows read as misses and are
+/// deleted lazily, or in bulk with [`SqliteResponseCache::purge_expired`].
+#[derive(Debug)]
+pub struct SqliteResponseCache {
+    connection: Mutex<Connection>,
+    ttl: Option<Duration>,
+}
+
+impl SqliteResponseCache {
+    pub fn new(path: impl AsRef<Path>) -> Result<Self, ProviderError> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent()
+            && !parent.as_os_str().is_empty()
+        {
+            std::fs::create_dir_all(parent).map_err(|err| {
+                ProviderError::other(format!(
+                    "failed to create response cache directory '{}': {err}",
+                    parent.display()
+                ))
+            })?;
+        }
+
+        let connection = Connection::open(path).map_err(|err| {
+            ProviderError::other(format!(
+                "failed to open response cache '{}': {err}",
+                path.display()
+            ))
+        })?;
+        Self::from_connection(connection)
+    }
+
+    pub fn new_in_memory() -> Result<Self, ProviderError> {
+        let connection = Connection::open_in_memory().map_err(|err| {
+            ProviderError::other(format!("failed to open in-memory response cache: {err}"))
+        })?;
+        Self::from_connection(connection)
+    }
+
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = Some(ttl);
+        self
+    }
+
+    /// Deletes every expired row and returns how many were removed.
+    pub fn purge_expired(&self) -> Result<usize, ProviderError> {
+        let conn = self.connection()?;
+        conn.execute(
+            "DELETE FROM response_cache WHERE expires_at_ms IS NOT NULL AND expires_at_ms <= ?1",
+            params![now_millis()],
+        )
+        .map_err(storage_error)
+    }
+
+    fn from_connection(connection: Connection) -> Result<Self, ProviderError> {
+        connection
+            .busy_timeout(Duration::from_secs(5))
+            .map_err(storage_error)?;
+        connection
+            .execute_batch(
+                "
+                CREATE TABLE IF NOT EXISTS response_cache (
+                    cache_key TEXT PRIMARY KEY,
+                    kind TEXT NOT NULL,
+                    payload_json TEXT NOT NULL,
+                    created_at_ms INTEGER NOT NULL,
+                    expires_at_ms INTEGER
+                );
+                ",
+            )
+            .map_err(storage_error)?;
+
+        Ok(Self {
+            connection: Mutex::new(connection),
+            ttl: None,
+        })
+    }
+
+    fn connection(&self) -> Result<std::sync::MutexGuard<'_, Connection>, ProviderError> {
+        self.connection
+            .lock()
+            .map_err(|_| ProviderError::other("response cache lock poisoned"))
+    }
+}
+
+impl ResponseCache for SqliteResponseCache {
+    fn get(&self, key: &CacheKey) -> Result<Option<CachedResponse>, ProviderError> {
+        let conn = self.connection()?;
+        let row = conn
+            .query_row(
+                "SELECT kind, payload_json, expires_at_ms FROM response_cache WHERE cache_key = ?1",
+                params![key.as_str()],
+                |row| {
+                    Ok((
+                        row.get::<_, String>(0)?,
+                        row.get::<_, String>(1)?,
+                        row.get::<_, Option<i64>>(2)?,
+                    ))
+                },
+            )
+            .optional()
+            .map_err(storage_error)?;
+
+        let Some((kind, payload, expires_at_ms)) = row else {
+            return Ok(None);
+        };
+
+        if expires_at_ms.is_some_and(|expires_at_ms| expires_at_ms <= now_millis()) {
+            conn.execute(
+                "DELETE FROM response_cache WHERE cache_key = ?1",
+                params![key.as_str()],
+            )
+            .map_err(storage_error)?;
+            return Ok(None);
+        }
+
+        decode(&kind, &payload).map(Some)
+    }
+
+    fn put(&self, key: &CacheKey, response: CachedResponse) -> Result<(), ProviderError> {
+        let (kind, payload) = encode(&response)?;
+        let now = now_millis();
+        let expires_at_ms = self
+            .ttl
+            .map(|ttl| now.saturating_add(i64::try_from(ttl.as_millis()).unwrap_or(i64::MAX)));
+
+        let conn = self.connection()?;
+        conn.execute(
+            "INSERT INTO response_cache (cache_key, kind, payload_json, created_at_ms, expires_at_ms)
+             VALUES (?1, ?2, ?3, ?4, ?5)
+             ON CONFLICT(cache_key) DO UPDATE SET
+                kind = excluded.kind,
+                payload_json = excluded.payload_json,
+                created_at_ms = excluded.created_at_ms,
+                expires_at_ms = excluded.expires_at_ms",
+            params![key.as_str(), kind, payload, now, expires_at_ms],
+        )
+        .map_err(storage_error)?;
+        Ok(())
+    }
+
+    fn remove(&self, key: &CacheKey) -> Result<(), ProviderError> {
+        let conn = self.connection()?;
+        conn.execute(
+            "DELETE FROM response_cache WHERE cache_key = ?1",
+            params![key.as_str()],
+        )
+        .map_err(storage_error)?;
+        Ok(())
+    }
+
+    fn clear(&self) -> Result<(), ProviderError> {
+        let conn = self.connection()?;
+        conn.execute("DELETE FROM response_cache", [])
+            .map_err(storage_error)?;
+        Ok(())
+    }
+}
+
+fn storage_error(err: rusqlite::Error) -> ProviderError {
+    ProviderError::other(format!("response cache storage failed: {err}"))
+}
+
+fn now_millis() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| i64::try_from(elapsed.as_millis()).unwrap_or(i64::MAX))
+        .unwrap_or(0)
+}
+
+fn encode(response: &CachedResponse) -> Result<(&'static str, String), ProviderError> {
+    let encoded = match response {
+        CachedResponse::Completion(response) => (
+            KIND_COMPLETION,
+            serde_json::to_string(&StoredResponse::from_response(response)),
+        ),
+        CachedResponse::Stream(events) => (
+            KIND_STREAM,
+            serde_json::to_string(
+                &events
+                    .iter()
+                    .map(StoredEvent::from_event)
+                    .collect::<Vec<_>>(),
+            ),
+        ),
+    };
+
+    let (kind, payload) = encoded;
+    payload
+        .map(|payload| (kind, payload))
+        .map_err(|err| ProviderError::other(format!("failed to encode cached response: {err}")))
+}
+
+fn decode(kind: &str, payload: &str) -> Result<CachedResponse, ProviderError> {
+    let malformed = |_| ProviderError::other("cached response payload is malformed");
+    match kind {
+        KIND_COMPLETION => {
+            let stored: StoredResponse = serde_json::from_str(payload).map_err(malformed)?;
+            stored.into_response().map(CachedResponse::Completion)
+        }
+        KIND_STREAM => {
+            let stored: Vec<StoredEvent> = serde_json::from_str(payload).map_err(malformed)?;
+            stored
+                .into_iter()
+                .map(StoredEvent::into_event)
+                .collect::<Result<Vec<_>, _>>()
+                .map(CachedResponse::Stream)
+        }
+        other => Err(ProviderError::other(format!(
+            "unknown cached response kind '{other}'"
+        ))),
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct StoredMessage {
+    role: String,
+    content: String,
+}
+
+impl StoredMessage {
+    fn from_message(message: &Message) -> Self {
+        Self {
+            role: super::role_tag(message.role).to_string(),
+            content: message.content.clone(),
+        }
+    }
+
+    fn into_message(self) -> Result<Message, ProviderError> {
+        let role = match self.role.as_str() {
+            "system" => Role::System,
+            "user" => Role::User,
+            "assistant" => Role::Assistant,
+            "tool" => Role::Tool,
+            other => {
+                return Err(ProviderError::other(format!(
+                    "unknown cached message role '{other}'"
+                )));
+            }
+        };
+        Ok(Message::new(role, self.content))
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct StoredToolCall {
+    id: String,
+    name: String,
+    arguments: String,
+}
+
+impl StoredToolCall {
+    fn from_call(call: &ToolCall) -> Self {
+        Self {
+            id: call.id.clone(),
+            name: call.name.clone(),
+            arguments: call.arguments.clone(),
+        }
+    }
+
+    fn into_call(self) -> ToolCall {
+        ToolCall {
+            id: self.id,
+            name: self.name,
+            arguments: self.arguments,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum StoredOutputItem {
+    Message(StoredMessage),
+    ToolCall(StoredToolCall),
+}
+
+#[derive(Serialize, Deserialize)]
+struct StoredResponse {
+    provider: String,
+    model: String,
+    output: Vec<StoredOutputItem>,
+    stop_reason: String,
+    input_tokens: u32,
+    output_tokens: u32,
+    total_tokens: u32,
+    #[serde(default)]
+    cached_input_tokens: u32,
+    response_id: Option<String>,
+}
+
+impl StoredResponse {
+    fn from_response(response: &ModelResponse) -> Self {
+        Self {
+            provider: response.provider.to_string(),
+            model: response.model.clone(),
+            output: response
+                .output
+                .iter()
+                .map(|item| match item {
+                    OutputItem::Message(message) => {
+                        StoredOutputItem::Message(StoredMessage::from_message(message))
+                    }
+                    OutputItem::ToolCall(call) => {
+                        StoredOutputItem::ToolCall(StoredToolCall::from_call(call))
+                    }
+                })
+                .collect(),
+            stop_reason: stop_reason_tag(response.stop_reason).to_string(),
+            input_tokens: response.usage.input_tokens,
+            output_tokens: response.usage.output_tokens,
+            total_tokens: response.usage.total_tokens,
+            cached_input_tokens: response.usage.cached_input_tokens,
+            response_id: response.response_id.clone(),
+        }
+    }
+
+    fn into_response(self) -> Result<ModelResponse, ProviderError> {
+        let output = self
+            .output
+            .into_iter()
+            .map(|item| match item {
+                StoredOutputItem::Message(message) => {
+                    message.into_message().map(OutputItem::Message)
+                }
+                StoredOutputItem::ToolCall(call) => Ok(OutputItem::ToolCall(call.into_call())),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(ModelResponse {
+            provider: parse_provider(&self.provider)?,
+            model: self.model,
+            output,
+            stop_reason: parse_stop_reason(&self.stop_reason)?,
+            usage: TokenUsage {
+                input_tokens: self.input_tokens,
+                output_tokens: self.output_tokens,
+                total_tokens: self.total_tokens,
+                cached_input_tokens: self.cached_input_tokens,
+            },
+            response_id: self.response_id,
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "type", content = "data", rename_all = "snake_case")]
+enum StoredEvent {
+    TextDelta(String),
+    ToolCallDelta(StoredToolCall),
+    MessageComplete(StoredMessage),
+    ResponseComplete(StoredResponse),
+    Usage(StoredUsage),
+}
+
+#[derive(Serialize, Deserialize)]
+struct StoredUsage {
+    input_tokens: u32,
+    output_tokens: u32,
+    total_tokens: u32,
+    cached_input_tokens: u32,
+}
+
+impl StoredEvent {
+    fn from_event(event: &StreamEvent) -> Self {
+        match event {
+            StreamEvent::TextDelta(text) => Self::TextDelta(text.clone()),
+            StreamEvent::ToolCallDelta(call) => {
+                Self::ToolCallDelta(StoredToolCall::from_call(call))
+            }
+            StreamEvent::MessageComplete(message) => {
+                Self::MessageComplete(StoredMessage::from_message(message))
+            }
+            StreamEvent::ResponseComplete(response) => {
+                Self::ResponseComplete(StoredResponse::from_response(response))
+            }
+            StreamEvent::Usage(usage) => Self::Usage(StoredUsage {
+                input_tokens: usage.input_tokens,
+                output_tokens: usage.output_tokens,
+                total_tokens: usage.total_tokens,
+                cached_input_tokens: usage.cached_input_tokens,
+            }),
+        }
+    }
+
+    fn into_event(self) -> Result<StreamEvent, ProviderError> {
+        Ok(match self {
+            Self::TextDelta(text) => StreamEvent::TextDelta(text),
+            Self::ToolCallDelta(call) => StreamEvent::ToolCallDelta(call.into_call()),
+            Self::MessageComplete(message) => StreamEvent::MessageComplete(message.into_message()?),
+            Self::ResponseComplete(response) => {
+                StreamEvent::ResponseComplete(response.into_response()?)
+            }
+            Self::Usage(usage) => StreamEvent::Usage(TokenUsage {
+                input_tokens: usage.input_tokens,
+                output_tokens: usage.output_tokens,
+                total_tokens: usage.total_tokens,
+                cached_input_tokens: usage.cached_input_tokens,
+            }),
+        })
+    }
+}
+
+fn stop_reason_tag(stop_reason: StopReason) -> &'static str {
+    match stop_reason {
+        StopReason::EndTurn => "end_turn",
+        StopReason::MaxTokens => "max_tokens",
+        StopReason::ToolUse => "tool_use",
+        StopReason::Cancelled => "cancelled",
+        StopReason::Other => "other",
+    }
+}
+
+fn parse_stop_reason(value: &str) -> Result<StopReason, ProviderError> {
+    match value {
+        "end_turn" => Ok(StopReason::EndTurn),
+        "max_tokens" => Ok(StopReason::MaxTokens),
+        "tool_use" => Ok(StopReason::ToolUse),
+        "cancelled" => Ok(StopReason::Cancelled),
+        "other" => Ok(StopReason::Other),
+        other => Err(ProviderError::other(format!(
+            "unknown cached stop reason '{other}'"
+        ))),
+    }
+}
+
+fn parse_provider(value: &str) -> Result<ProviderId, ProviderError> {
+    [
+        ProviderId::OpenCodeZen,
+        ProviderId::OpenAi,
+        ProviderId::Anthropic,
+        ProviderId::Ollama,
+        ProviderId::Gemini,
+    ]
+    .into_iter()
+    .find(|provider| provider.to_string() == value)
+    .ok_or_else(|| ProviderError::other(format!("unknown cached provider '{value}'")))
+}
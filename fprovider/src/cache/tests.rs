@@ -0,0 +1,313 @@
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+use super::*;
+use crate::{Message, OutputItem, StopReason, TokenUsage, ToolCall, ToolDefinition};
+
+#[derive(Default)]
+struct CountingProvider {
+    completions: AtomicUsize,
+    streams: AtomicUsize,
+    fail_stream: bool,
+}
+
+impl ModelProvider for CountingProvider {
+    fn id(&self) -> ProviderId {
+        ProviderId::OpenAi
+    }
+
+    fn complete<'a>(
+        &'a self,
+        request: ModelRequest,
+    ) -> ProviderFuture<'a, Result<ModelResponse, ProviderError>> {
+        Box::pin(async move {
+            let call = self.completions.fetch_add(1, Ordering::SeqCst) + 1;
+            Ok(response(&request.model, &format!("answer {call}")))
+        })
+    }
+
+    fn stream<'a>(
+        &'a self,
+        request: ModelRequest,
+    ) -> ProviderFuture<'a, Result<BoxedEventStream<'a>, ProviderError>> {
+        Box::pin(async move {
+            let call = self.streams.fetch_add(1, Ordering::SeqCst) + 1;
+            let text = format!("streamed {call}");
+            let mut events = vec![
+                Ok(StreamEvent::TextDelta(text.clone())),
+                Ok(StreamEvent::ToolCallDelta(ToolCall {
+                    id: "call-1".to_string(),
+                    name: "lookup".to_string(),
+                    arguments: "{}".to_string(),
+                })),
+                Ok(StreamEvent::MessageComplete(Message::new(
+                    Role::Assistant,
+                    text.clone(),
+                ))),
+            ];
+            if self.fail_stream {
+                events.push(Err(ProviderError::transport("connection reset")));
+            } else {
+                events.push(Ok(StreamEvent::ResponseComplete(response(
+                    &request.model,
+                    &text,
+                ))));
+            }
+            Ok(Box::pin(VecEventStream::new(events)) as BoxedEventStream<'a>)
+        })
+    }
+}
+
+fn response(model: &str, text: &str) -> ModelResponse {
+    ModelResponse {
+        provider: ProviderId::OpenAi,
+        model: model.to_string(),
+        output: vec![OutputItem::Message(Message::new(Role::Assistant, text))],
+        stop_reason: StopReason::EndTurn,
+        usage: TokenUsage {
+            input_tokens: 3,
+            output_tokens: 2,
+            total_tokens: 5,
+            cached_input_tokens: 0,
+        },
+        response_id: Some("resp_1".to_string()),
+    }
+}
+
+fn request(prompt: &str) -> ModelRequest {
+    ModelRequest::new("gpt-4o-mini", vec![Message::new(Role::User, prompt)]).with_temperature(0.0)
+}
+
+#[derive(Default)]
+struct RecordingHooks {
+    events: Mutex<Vec<String>>,
+}
+
+impl ProviderOperationHooks for RecordingHooks {
+    fn on_cache_hit(&self, _provider: ProviderId, operation: &str) {
+        self.events
+            .lock()
+            .expect("events lock")
+            .push(format!("hit:{operation}"));
+    }
+
+    fn on_cache_miss(&self, _provider: ProviderId, operation: &str) {
+        self.events
+            .lock()
+            .expect("events lock")
+            .push(format!("miss:{operation}"));
+    }
+}
+
+async fn collect(mut stream: BoxedEventStream<'_>) -> Vec<Result<StreamEvent, ProviderError>> {
+    let mut events = Vec::new();
+    while let Some(event) = std::future::poll_fn(|cx| stream.as_mut().poll_next(cx)).await {
+        events.push(event);
+    }
+    events
+}
+
+#[test]
+fn cache_key_ignores_metadata_and_stream_flag_but_not_content() {
+    let base = request("hello");
+    let key = CacheKey::for_request(ProviderId::OpenAi, "complete", &base);
+
+    let tagged = base
+        .clone()
+        .with_metadata("trace", "abc")
+        .enable_streaming();
+    assert_eq!(
+        key,
+        CacheKey::for_request(ProviderId::OpenAi, "complete", &tagged)
+    );
+    assert_eq!(key.as_str().len(), 32);
+
+    let variants = [
+        CacheKey::for_request(ProviderId::Anthropic, "complete", &base),
+        CacheKey::for_request(ProviderId::OpenAi, "stream", &base),
+        CacheKey::for_request(ProviderId::OpenAi, "complete", &request("hello!")),
+        CacheKey::for_request(
+            ProviderId::OpenAi,
+            "complete",
+            &base.clone().with_temperature(0.5),
+        ),
+        CacheKey::for_request(
+            ProviderId::OpenAi,
+            "complete",
+            &base.clone().with_tools(vec![ToolDefinition {
+                name: "lookup".to_string(),
+                description: "Look things up".to_string(),
+                input_schema: "{}".to_string(),
+            }]),
+        ),
+    ];
+    for variant in variants {
+        assert_ne!(key, variant);
+    }
+}
+
+#[tokio::test]
+async fn caching_provider_serves_repeat_completions_and_reports_hooks() {
+    let inner = Arc::new(CountingProvider::default());
+    let hooks = Arc::new(RecordingHooks::default());
+    let provider = CachingProvider::new(inner.clone(), Arc::new(InMemoryResponseCache::new(8)))
+        .with_hooks(hooks.clone());
+
+    let first = provider
+        .complete(request("hello"))
+        .await
+        .expect("first call");
+    let second = provider
+        .complete(request("hello"))
+        .await
+        .expect("second call");
+
+    assert_eq!(first.response_id.as_deref(), Some("resp_1"));
+    assert_eq!(second.response_id, None);
+    assert_eq!(
+        ModelResponse {
+            response_id: None,
+            ..first
+        },
+        second
+    );
+    assert_eq!(inner.completions.load(Ordering::SeqCst), 1);
+    assert_eq!(
+        *hooks.events.lock().expect("events lock"),
+        vec!["miss:complete", "hit:complete"]
+    );
+}
+
+#[tokio::test]
+async fn caching_provider_replays_completed_streams_only() {
+    let inner = Arc::new(CountingProvider::default());
+    let provider = CachingProvider::new(inner.clone(), Arc::new(InMemoryResponseCache::new(8)));
+
+    let live = collect(provider.stream(request("hello")).await.expect("stream")).await;
+    let replayed = collect(provider.stream(request("hello")).await.expect("stream")).await;
+    assert_eq!(live.len(), 4);
+    assert_eq!(live[..3], replayed[..3]);
+    let Some(Ok(StreamEvent::ResponseComplete(live_response))) = live.last() else {
+        panic!("live stream should complete");
+    };
+    let Some(Ok(StreamEvent::ResponseComplete(replayed_response))) = replayed.last() else {
+        panic!("replayed stream should complete");
+    };
+    assert_eq!(live_response.response_id.as_deref(), Some("resp_1"));
+    assert_eq!(replayed_response.response_id, None);
+    assert_eq!(inner.streams.load(Ordering::SeqCst), 1);
+
+    let failing = Arc::new(CountingProvider {
+        fail_stream: true,
+        ..CountingProvider::default()
+    });
+    let provider = CachingProvider::new(failing.clone(), Arc::new(InMemoryResponseCache::new(8)));
+    for _ in 0..2 {
+        let events = collect(provider.stream(request("hello")).await.expect("stream")).await;
+        assert!(matches!(events.last(), Some(Err(_))));
+    }
+    assert_eq!(failing.streams.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn sampled_requests_bypass_the_cache_by_default() {
+    let inner = Arc::new(CountingProvider::default());
+    let hooks = Arc::new(RecordingHooks::default());
+    let provider = CachingProvider::new(inner.clone(), Arc::new(InMemoryResponseCache::new(8)))
+        .with_hooks(hooks.clone());
+
+    let sampled = ModelRequest::new("gpt-4o-mini", vec![Message::new(Role::User, "hello")])
+        .with_temperature(0.7);
+    provider.complete(sampled.clone()).await.expect("call");
+    provider.complete(sampled).await.expect("call");
+
+    assert_eq!(inner.completions.load(Ordering::SeqCst), 2);
+    assert!(hooks.events.lock().expect("events lock").is_empty());
+}
+
+#[tokio::test]
+async fn deterministic_only_opt_out_caches_sampled_requests() {
+    let inner = Arc::new(CountingProvider::default());
+    let provider = CachingProvider::new(inner.clone(), Arc::new(InMemoryResponseCache::new(8)))
+        .with_deterministic_only(false);
+
+    let sampled = ModelRequest::new("gpt-4o-mini", vec![Message::new(Role::User, "hello")])
+        .with_temperature(0.7);
+    provider.complete(sampled.clone()).await.expect("call");
+    provider.complete(sampled).await.expect("call");
+
+    assert_eq!(inner.completions.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn in_memory_cache_evicts_least_recently_used_and_expires_entries() {
+    let cache = InMemoryResponseCache::new(2);
+    let key =
+        |prompt: &str| CacheKey::for_request(ProviderId::OpenAi, "complete", &request(prompt));
+    let entry = |text: &str| CachedResponse::Completion(response("gpt-4o-mini", text));
+
+    cache.put(&key("a"), entry("a")).expect("put a");
+    cache.put(&key("b"), entry("b")).expect("put b");
+    assert!(cache.get(&key("a")).expect("get a").is_some());
+    cache.put(&key("c"), entry("c")).expect("put c");
+
+    assert_eq!(cache.len(), 2);
+    assert!(cache.get(&key("b")).expect("get b").is_none());
+    assert!(cache.get(&key("a")).expect("get a").is_some());
+    assert!(cache.get(&key("c")).expect("get c").is_some());
+
+    let expiring = InMemoryResponseCache::new(2).with_ttl(Duration::ZERO);
+    expiring.put(&key("a"), entry("a")).expect("put a");
+    assert!(expiring.get(&key("a")).expect("get a").is_none());
+    assert!(expiring.is_empty());
+}
+
+#[cfg(feature = "response-cache-sqlite")]
+#[test]
+fn sqlite_cache_round_trips_entries_and_honors_ttl() {
+    let path = std::env::temp_dir().join(format!(
+        "fprovider-response-cache-{}.sqlite",
+        std::process::id()
+    ));
+    let key = CacheKey::for_request(ProviderId::OpenAi, "stream", &request("hello"));
+    let events = vec![
+        StreamEvent::TextDelta("hi".to_string()),
+        StreamEvent::ToolCallDelta(ToolCall {
+            id: "call-1".to_string(),
+            name: "lookup".to_string(),
+            arguments: "{\"q\":1}".to_string(),
+        }),
+        StreamEvent::MessageComplete(Message::new(Role::Assistant, "hi")),
+        StreamEvent::ResponseComplete(response("gpt-4o-mini", "hi")),
+    ];
+
+    {
+        let cache = SqliteResponseCache::new(&path).expect("cache should open");
+        cache
+            .put(&key, CachedResponse::Stream(events.clone()))
+            .expect("put");
+    }
+
+    let reopened = SqliteResponseCache::new(&path).expect("cache should reopen");
+    assert_eq!(
+        reopened.get(&key).expect("get"),
+        Some(CachedResponse::Stream(events))
+    );
+    reopened.clear().expect("clear");
+    assert_eq!(reopened.get(&key).expect("get"), None);
+    drop(reopened);
+    std::fs::remove_file(&path).ok();
+
+    let expiring = SqliteResponseCache::new_in_memory()
+        .expect("cache should open")
+        .with_ttl(Duration::ZERO);
+    expiring
+        .put(
+            &key,
+            CachedResponse::Completion(response("gpt-4o-mini", "hi")),
+        )
+        .expect("put");
+    assert_eq!(expiring.get(&key).expect("get"), None);
+    assert_eq!(expiring.purge_expired().expect("purge"), 0);
+}
@@ -0,0 +1,504 @@
+//! Pluggable credential sources resolved lazily by [`SecureCredentialManager`].
+//!
+//! ```rust
+//! use std::sync::Arc;
+//!
+//! use fprovider::{EnvCredentialSource, ProviderId, SecureCredentialManager};
+//!
+//! let manager = SecureCredentialManager::new();
+//! manager
+//!     .set_source(
+//!         ProviderId::OpenAi,
+//!         Arc::new(EnvCredentialSource::api_key("FPROVIDER_DOC_MISSING_KEY")),
+//!     )
+//!     .expect("source should register");
+//!
+//! // The variable is unset, so nothing resolves.
+//! assert!(!manager.has_credentials(ProviderId::OpenAi).expect("lookup should work"));
+//! ```
+//!
+//! [`SecureCredentialManager`]: crate::SecureCredentialManager
+
+use std::io::Read;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime};
+
+use crate::{BrowserLoginSession, CredentialKind, ProviderCredential, ProviderError, SecretString};
+
+/// A credential produced by a [`CredentialSource`].
+#[derive(Debug)]
+pub struct SourcedCredential {
+    pub credential: ProviderCredential,
+    pub expires_at: Option<SystemTime>,
+}
+
+impl SourcedCredential {
+    pub fn new(
+        kind: CredentialKind,
+        secret: impl Into<String>,
+        expires_at: Option<SystemTime>,
+    ) -> Self {
+        let credential = match kind {
+            CredentialKind::ApiKey => ProviderCredential::ApiKey(SecretString::new(secret)),
+            CredentialKind::AccessToken => {
+                ProviderCredential::AccessToken(SecretString::new(secret))
+            }
+            CredentialKind::BrowserSession => {
+                ProviderCredential::BrowserSession(BrowserLoginSession::new(secret, expires_at))
+            }
+        };
+
+        Self {
+            credential,
+            expires_at,
+        }
+    }
+}
+
+/// Supplies credentials on demand.
+///
+/// The manager calls [`CredentialSource::load`] when no credential is cached, when
+/// the cached one is about to expire, or when [`CredentialSource::is_stale`] reports
+/// that the backing value changed.
+pub trait CredentialSource: Send + Sync {
+    /// Returns the current credential, or `None` when the source has nothing to offer.
+    fn load(&self) -> Result<Option<SourcedCredential>, ProviderError>;
+
+    /// Returns `true` when the last loaded credential no longer reflects the source.
+    fn is_stale(&self) -> bool {
+        false
+    }
+}
+
+/// Reads a credential from an environment variable.
+#[derive(Debug, Clone)]
+pub struct EnvCredentialSource {
+    variable: String,
+    kind: CredentialKind,
+}
+
+impl EnvCredentialSource {
+    pub fn new(variable: impl Into<String>, kind: CredentialKind) -> Self {
+        Self {
+            variable: variable.into(),
+            kind,
+        }
+    }
+
+    pub fn api_key(variable: impl Into<String>) -> Self {
+        Self::new(variable, CredentialKind::ApiKey)
+    }
+}
+
+impl CredentialSource for EnvCredentialSource {
+    fn load(&self) -> Result<Option<SourcedCredential>, ProviderError> {
+        let value = std::env::var(&self.variable).unwrap_or_default();
+        let value = value.trim();
+        if value.is_empty() {
+            return Ok(None);
+        }
+
+        Ok(Some(SourcedCredential::new(self.kind, value, None)))
+    }
+}
+
+/// Reads a credential from a file and reloads it whenever the file is modified.
+#[derive(Debug)]
+pub struct FileCredentialSource {
+    path: PathBuf,
+    kind: CredentialKind,
+    loaded_modified_at: Mutex<Option<SystemTime>>,
+}
+
+impl FileCredentialSource {
+    pub fn new(path: impl Into<PathBuf>, kind: CredentialKind) -> Self {
+        Self {
+            path: path.into(),
+            kind,
+            loaded_modified_at: Mutex::new(None),
+        }
+    }
+
+    pub fn api_key(path: impl Into<PathBuf>) -> Self {
+        Self::new(path, CredentialKind::ApiKey)
+    }
+
+    fn modified_at(&self) -> Option<SystemTime> {
+        std::fs::metadata(&self.path)
+            .and_then(|metadata| metadata.modified())
+            .ok()
+    }
+}
+
+impl CredentialSource for FileCredentialSource {
+    fn load(&self) -> Result<Option<SourcedCredential>, ProviderError> {
+        let modified_at = self.modified_at();
+        let contents = match std::fs::read_to_string(&self.path) {
+            Ok(contents) => contents,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => {
+                return Err(ProviderError::authentication(format!(
+                    "failed to read credential file '{}': {err}",
+                    self.path.display()
+                )));
+            }
+        };
+
+        *self
+            .loaded_modified_at
+            .lock()
+            .map_err(|_| ProviderError::other("credential file source lock poisoned"))? =
+            modified_at;
+
+        let value = contents.trim();
+        if value.is_empty() {
+            return Ok(None);
+        }
+
+        Ok(Some(SourcedCredential::new(self.kind, value, None)))
+    }
+
+    fn is_stale(&self) -> bool {
+        let loaded = self
+            .loaded_modified_at
+            .lock()
+            .map(|loaded| *loaded)
+            .unwrap_or(None);
+        loaded != self.modified_at()
+    }
+}
+
+const DEFAULT_COMMAND_TIMEOUT: Duration = Duration::from_secs(30);
+const COMMAND_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Runs an external helper command and uses its trimmed stdout as the credential.
+///
+/// Without a TTL the output is cached until the manager is asked to reload; with
+/// [`CommandCredentialSource::with_ttl`] the helper is re-run before the TTL elapses.
+/// The manager calls the helper synchronously, so one that runs longer than
+/// [`CommandCredentialSource::with_timeout`] (30s by default) is killed and the load fails.
+#[derive(Debug, Clone)]
+pub struct CommandCredentialSource {
+    program: String,
+    args: Vec<String>,
+    kind: CredentialKind,
+    ttl: Option<Duration>,
+    timeout: Duration,
+}
+
+impl CommandCredentialSource {
+    pub fn new(program: impl Into<String>, kind: CredentialKind) -> Self {
+        Self {
+            program: program.into(),
+            args: Vec::new(),
+            kind,
+            ttl: None,
+            timeout: DEFAULT_COMMAND_TIMEOUT,
+        }
+    }
+
+    pub fn with_args<I, S>(mut self, args: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.args = args.into_iter().map(Into::into).collect();
+        self
+    }
+
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = Some(ttl);
+        self
+    }
+
+    /// How long the helper may run before it is killed.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Runs the helper and returns its stdout, killing it once `timeout` elapses.
+    fn run(&self) -> Result<Vec<u8>, ProviderError> {
+        let spawn_error = |err: std::io::Error| {
+            ProviderError::authentication(format!(
+                "failed to run credential helper '{}': {err}",
+                self.program
+            ))
+        };
+        let mut child = Command::new(&self.program)
+            .args(&self.args)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(spawn_error)?;
+
+        // Drained on its own thread so a helper writing more than the pipe buffer cannot
+        // block before it exits.
+        let mut stdout = child
+            .stdout
+            .take()
+            .ok_or_else(|| ProviderError::other("credential helper stdout is not piped"))?;
+        let reader = std::thread::spawn(move || {
+            let mut output = Vec::new();
+            stdout.read_to_end(&mut output).map(|_| output)
+        });
+
+        let deadline = Instant::now() + self.timeout;
+        let status = loop {
+            if let Some(status) = child.try_wait().map_err(spawn_error)? {
+                break status;
+            }
+            if Instant::now() >= deadline {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(ProviderError::timeout(format!(
+                    "credential helper '{}' did not finish within {:?}",
+                    self.program, self.timeout
+                )));
+            }
+            std::thread::sleep(COMMAND_POLL_INTERVAL);
+        };
+
+        if !status.success() {
+            return Err(ProviderError::authentication(format!(
+                "credential helper '{}' exited with {status}",
+                self.program
+            )));
+        }
+
+        reader
+            .join()
+            .map_err(|_| ProviderError::other("credential helper reader panicked"))?
+            .map_err(spawn_error)
+    }
+}
+
+impl CredentialSource for CommandCredentialSource {
+    fn load(&self) -> Result<Option<SourcedCredential>, ProviderError> {
+        let output = self.run()?;
+        let stdout = String::from_utf8(output).map_err(|_| {
+            ProviderError::authentication("credential helper output must be valid UTF-8")
+        })?;
+        let value = stdout.trim();
+        if value.is_empty() {
+            return Ok(None);
+        }
+
+        let expires_at = self.ttl.and_then(|ttl| SystemTime::now().checked_add(ttl));
+        Ok(Some(SourcedCredential::new(self.kind, value, expires_at)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+    use crate::{
+        CredentialAccessAction, CredentialAccessEvent, CredentialAccessObserver, ProviderId,
+        SecureCredentialManager,
+    };
+
+    struct CountingSource {
+        loads: AtomicUsize,
+        ttl: Option<Duration>,
+    }
+
+    impl CredentialSource for CountingSource {
+        fn load(&self) -> Result<Option<SourcedCredential>, ProviderError> {
+            let count = self.loads.fetch_add(1, Ordering::SeqCst) + 1;
+            let expires_at = self.ttl.map(|ttl| SystemTime::now() + ttl);
+            Ok(Some(SourcedCredential::new(
+                CredentialKind::ApiKey,
+                format!("sk-{count}"),
+                expires_at,
+            )))
+        }
+    }
+
+    #[derive(Default)]
+    struct Recorder {
+        events: Mutex<Vec<CredentialAccessEvent>>,
+    }
+
+    impl CredentialAccessObserver for Recorder {
+        fn on_event(&self, event: CredentialAccessEvent) {
+            self.events.lock().expect("events lock").push(event);
+        }
+    }
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("fprovider-{name}-{}", std::process::id()))
+    }
+
+    #[test]
+    fn manager_resolves_source_lazily_and_caches_result() {
+        let source = Arc::new(CountingSource {
+            loads: AtomicUsize::new(0),
+            ttl: None,
+        });
+        let manager = SecureCredentialManager::new();
+        manager
+            .set_source(ProviderId::OpenAi, source.clone())
+            .expect("source should register");
+        assert_eq!(source.loads.load(Ordering::SeqCst), 0);
+
+        for _ in 0..3 {
+            let key = manager
+                .with_api_key(ProviderId::OpenAi, str::to_string)
+                .expect("lookup should work");
+            assert_eq!(key.as_deref(), Some("sk-1"));
+        }
+        assert_eq!(source.loads.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn manager_refreshes_source_before_expiry() {
+        // The 60s margin exceeds the TTL, so it is clamped to a quarter of the lifetime.
+        let source = Arc::new(CountingSource {
+            loads: AtomicUsize::new(0),
+            ttl: Some(Duration::from_millis(400)),
+        });
+        let recorder = Arc::new(Recorder::default());
+        let manager = SecureCredentialManager::with_observer(recorder.clone())
+            .with_refresh_margin(Duration::from_secs(60));
+        manager
+            .set_source(ProviderId::OpenAi, source.clone())
+            .expect("source should register");
+
+        let first = manager
+            .with_api_key(ProviderId::OpenAi, str::to_string)
+            .expect("lookup should work");
+        let second = manager
+            .with_api_key(ProviderId::OpenAi, str::to_string)
+            .expect("lookup should work");
+        std::thread::sleep(Duration::from_millis(320));
+        let third = manager
+            .with_api_key(ProviderId::OpenAi, str::to_string)
+            .expect("lookup should work");
+
+        assert_eq!(first.as_deref(), Some("sk-1"));
+        assert_eq!(second.as_deref(), Some("sk-1"));
+        assert_eq!(third.as_deref(), Some("sk-2"));
+
+        let events = recorder.events.lock().expect("events lock");
+        let actions = events.iter().map(|event| event.action).collect::<Vec<_>>();
+        assert_eq!(
+            actions,
+            vec![
+                CredentialAccessAction::Set,
+                CredentialAccessAction::AccessGranted,
+                CredentialAccessAction::AccessGranted,
+                CredentialAccessAction::Rotated,
+                CredentialAccessAction::AccessGranted,
+            ]
+        );
+    }
+
+    #[test]
+    fn file_source_reloads_when_file_changes() {
+        let path = temp_path("file-source");
+        std::fs::write(&path, "sk-file-1\n").expect("file should write");
+
+        let manager = SecureCredentialManager::new();
+        manager
+            .set_source(
+                ProviderId::Anthropic,
+                Arc::new(FileCredentialSource::api_key(&path)),
+            )
+            .expect("source should register");
+
+        let first = manager
+            .api_key(ProviderId::Anthropic)
+            .expect("lookup should work")
+            .expect("key should resolve");
+        assert_eq!(first.expose(), "sk-file-1");
+
+        let file = std::fs::File::options()
+            .write(true)
+            .truncate(true)
+            .open(&path)
+            .expect("file should open");
+        std::io::Write::write_all(&mut &file, b"sk-file-2").expect("file should write");
+        file.set_modified(SystemTime::now() + Duration::from_secs(5))
+            .expect("mtime should update");
+        drop(file);
+
+        let second = manager
+            .api_key(ProviderId::Anthropic)
+            .expect("lookup should work")
+            .expect("key should resolve");
+        assert_eq!(second.expose(), "sk-file-2");
+
+        std::fs::remove_file(&path).expect("file should be removed");
+    }
+
+    #[test]
+    fn env_source_ignores_missing_variables() {
+        let source = EnvCredentialSource::api_key("FPROVIDER_TEST_UNSET_CREDENTIAL_VARIABLE");
+        assert!(source.load().expect("load should work").is_none());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn command_source_uses_helper_stdout_and_ttl() {
+        let source = CommandCredentialSource::new("echo", CredentialKind::AccessToken)
+            .with_args(["  token-123  "])
+            .with_ttl(Duration::from_secs(300));
+
+        let loaded = source
+            .load()
+            .expect("helper should run")
+            .expect("helper should produce a credential");
+
+        assert_eq!(loaded.credential.kind(), CredentialKind::AccessToken);
+        assert!(loaded.expires_at.is_some());
+        let ProviderCredential::AccessToken(token) = loaded.credential else {
+            panic!("expected access token");
+        };
+        assert_eq!(token.expose(), "token-123");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn command_source_kills_helpers_that_exceed_the_timeout() {
+        let source = CommandCredentialSource::new("sleep", CredentialKind::ApiKey)
+            .with_args(["5"])
+            .with_timeout(Duration::from_millis(50));
+
+        let started = Instant::now();
+        let err = source.load().expect_err("hung helper should time out");
+        assert_eq!(err.kind, crate::ProviderErrorKind::Timeout);
+        assert!(started.elapsed() < Duration::from_secs(2));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn command_source_reports_helper_failures() {
+        let source = CommandCredentialSource::new("false", CredentialKind::ApiKey);
+        let err = source.load().expect_err("failing helper should error");
+        assert_eq!(err.kind, crate::ProviderErrorKind::Authentication);
+    }
+
+    #[test]
+    fn revoke_drops_registered_source() {
+        let source = Arc::new(CountingSource {
+            loads: AtomicUsize::new(0),
+            ttl: None,
+        });
+        let manager = SecureCredentialManager::new();
+        manager
+            .set_source(ProviderId::OpenAi, source)
+            .expect("source should register");
+        assert!(manager.has_credentials(ProviderId::OpenAi).expect("lookup"));
+
+        manager
+            .revoke(ProviderId::OpenAi)
+            .expect("revoke should work");
+        assert!(!manager.has_credentials(ProviderId::OpenAi).expect("lookup"));
+    }
+}
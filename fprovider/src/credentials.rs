@@ -23,12 +23,29 @@ use std::collections::HashMap;
 use std::sync::{Arc, Mutex, MutexGuard};
 use std::time::{Duration, SystemTime};
 
-use crate::{ProviderError, ProviderId};
+use zeroize::Zeroize;
+
+use crate::key_pool::PooledKeyStatus;
+#[cfg(feature = "credential-vault")]
+use crate::vault::{CredentialVault, VaultRecord};
+use crate::{ApiKeyPool, CredentialSource, ProviderError, ProviderErrorKind, ProviderId};
+
+/// Explicitly set entries copied out of the credentials map for a vault write.
+#[cfg(feature = "credential-vault")]
+type VaultSnapshot = Vec<VaultRecord>;
+#[cfg(not(feature = "credential-vault"))]
+type VaultSnapshot = ();
+
+const DEFAULT_REFRESH_MARGIN: Duration = Duration::from_secs(60);
+
+type SourceMap = HashMap<ProviderId, Arc<dyn CredentialSource>>;
+type PoolMap = HashMap<ProviderId, ApiKeyPool>;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum CredentialKind {
     ApiKey,
     BrowserSession,
+    AccessToken,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -38,6 +55,10 @@ pub enum CredentialAccessAction {
     AccessGranted,
     AccessDenied,
     Cleared,
+    /// A pooled key was benched after a rate limit.
+    KeyBenched,
+    /// A benched pooled key became selectable again.
+    KeyRestored,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -45,6 +66,8 @@ pub struct CredentialAccessEvent {
     pub provider: ProviderId,
     pub kind: Option<CredentialKind>,
     pub action: CredentialAccessAction,
+    /// Index of the key within an [`ApiKeyPool`], for pooled credentials.
+    pub key_index: Option<usize>,
 }
 
 pub trait CredentialAccessObserver: Send + Sync {
@@ -81,6 +104,20 @@ impl CredentialMetadata {
         }
     }
 
+    /// When a source-backed credential should be reloaded: `margin` before it expires, but no
+    /// earlier than three quarters into its lifetime, so short-lived credentials are still reused.
+    fn refresh_at(&self, margin: Duration) -> Option<SystemTime> {
+        let expires_at = self.expires_at?;
+        let lifetime = expires_at
+            .duration_since(self.created_at)
+            .unwrap_or_default();
+        Some(
+            expires_at
+                .checked_sub(margin.min(lifetime / 4))
+                .unwrap_or(expires_at),
+        )
+    }
+
     fn mark_used(&mut self, now: SystemTime) {
         self.last_used_at = Some(now);
         self.access_count = self.access_count.saturating_add(1);
@@ -123,9 +160,7 @@ impl std::fmt::Debug for SecretString {
 
 impl Drop for SecretString {
     fn drop(&mut self) {
-        unsafe {
-            self.value.as_mut_vec().fill(0);
-        }
+        self.value.zeroize();
     }
 }
 
@@ -155,6 +190,8 @@ impl std::fmt::Debug for BrowserLoginSession {
 pub enum ProviderCredential {
     ApiKey(SecretString),
     BrowserSession(BrowserLoginSession),
+    /// Short-lived OAuth bearer token, such as a Microsoft Entra ID access token.
+    AccessToken(SecretString),
 }
 
 impl ProviderCredential {
@@ -162,6 +199,21 @@ impl ProviderCredential {
         match self {
             Self::ApiKey(_) => CredentialKind::ApiKey,
             Self::BrowserSession(_) => CredentialKind::BrowserSession,
+            Self::AccessToken(_) => CredentialKind::AccessToken,
+        }
+    }
+}
+
+impl ProviderCredential {
+    #[cfg(feature = "credential-vault")]
+    fn duplicate(&self) -> Self {
+        match self {
+            Self::ApiKey(secret) => Self::ApiKey(secret.clone()),
+            Self::AccessToken(secret) => Self::AccessToken(secret.clone()),
+            Self::BrowserSession(session) => Self::BrowserSession(BrowserLoginSession {
+                session_token: session.session_token.clone(),
+                expires_at: session.expires_at,
+            }),
         }
     }
 }
@@ -170,6 +222,7 @@ impl std::fmt::Debug for ProviderCredential {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Self::ApiKey(_) => f.write_str("ProviderCredential::ApiKey([REDACTED])"),
+            Self::AccessToken(_) => f.write_str("ProviderCredential::AccessToken([REDACTED])"),
             Self::BrowserSession(session) => f
                 .debug_tuple("ProviderCredential::BrowserSession")
                 .field(session)
@@ -180,19 +233,36 @@ impl std::fmt::Debug for ProviderCredential {
 
 pub struct SecureCredentialManager {
     credentials: Mutex<HashMap<ProviderId, CredentialEntry>>,
+    sources: Mutex<SourceMap>,
+    pools: Mutex<PoolMap>,
+    /// Serializes changes that write the vault, which derive a key and sync the file without
+    /// holding `credentials`, so lookups never wait behind the KDF.
+    vault_writes: Mutex<()>,
+    refresh_margin: Duration,
     observer: Option<Arc<dyn CredentialAccessObserver>>,
+    #[cfg(feature = "credential-vault")]
+    vault: Option<CredentialVault>,
 }
 
 struct CredentialEntry {
     credential: ProviderCredential,
     metadata: CredentialMetadata,
+    /// Loaded from a [`CredentialSource`]; such entries are never written to the vault.
+    #[cfg_attr(not(feature = "credential-vault"), allow(dead_code))]
+    from_source: bool,
 }
 
 impl Default for SecureCredentialManager {
     fn default() -> Self {
         Self {
             credentials: Mutex::new(HashMap::new()),
+            sources: Mutex::new(HashMap::new()),
+            pools: Mutex::new(HashMap::new()),
+            vault_writes: Mutex::new(()),
+            refresh_margin: DEFAULT_REFRESH_MARGIN,
             observer: None,
+            #[cfg(feature = "credential-vault")]
+            vault: None,
         }
     }
 }
@@ -204,9 +274,192 @@ impl SecureCredentialManager {
 
     pub fn with_observer(observer: Arc<dyn CredentialAccessObserver>) -> Self {
         Self {
-            credentials: Mutex::new(HashMap::new()),
             observer: Some(observer),
+            ..Self::default()
+        }
+    }
+
+    /// Sets how long before `expires_at` a source-backed credential is reloaded. The margin is
+    /// capped at a quarter of the credential's lifetime.
+    pub fn with_refresh_margin(mut self, margin: Duration) -> Self {
+        self.refresh_margin = margin;
+        self
+    }
+
+    /// Loads credentials from `vault` and writes every later set, rotation and
+    /// revocation back to it. A missing vault file is created on the first write.
+    ///
+    /// Usage metadata is persisted alongside those writes rather than on every access.
+    #[cfg(feature = "credential-vault")]
+    pub fn with_vault(mut self, vault: CredentialVault) -> Result<Self, ProviderError> {
+        let records = if vault.exists() {
+            vault.read()?
+        } else {
+            Vec::new()
+        };
+
+        let now = SystemTime::now();
+        let mut loaded = Vec::new();
+        {
+            let mut credentials = self.credentials_mut()?;
+            for record in records {
+                if record.metadata.is_expired(now) {
+                    continue;
+                }
+
+                loaded.push((record.provider, record.credential.kind()));
+                credentials.insert(
+                    record.provider,
+                    CredentialEntry {
+                        credential: record.credential,
+                        metadata: record.metadata,
+                        from_source: false,
+                    },
+                );
+            }
+        }
+
+        for (provider, kind) in loaded {
+            self.emit(CredentialAccessEvent {
+                provider,
+                kind: Some(kind),
+                action: CredentialAccessAction::Set,
+                key_index: None,
+            });
+        }
+
+        self.vault = Some(vault);
+        Ok(self)
+    }
+
+    /// Writes the explicitly set credentials to the attached vault, if any.
+    /// Credentials loaded from a [`CredentialSource`] are left out.
+    #[cfg_attr(not(feature = "credential-vault"), allow(clippy::let_unit_value))]
+    pub fn persist(&self) -> Result<(), ProviderError> {
+        let _vault_writes = self.vault_writes_mut()?;
+        let snapshot = self.vault_snapshot(self.credentials_mut()?.iter());
+        self.write_vault(snapshot)
+    }
+
+    /// Copies the explicitly set entries among `entries` for [`Self::write_vault`]; empty
+    /// without an attached vault.
+    fn vault_snapshot<'a>(
+        &self,
+        entries: impl Iterator<Item = (&'a ProviderId, &'a CredentialEntry)>,
+    ) -> VaultSnapshot {
+        #[cfg(feature = "credential-vault")]
+        {
+            if self.vault.is_none() {
+                return Vec::new();
+            }
+            entries
+                .filter(|(_, entry)| !entry.from_source)
+                .map(|(provider, entry)| VaultRecord {
+                    provider: *provider,
+                    credential: entry.credential.duplicate(),
+                    metadata: entry.metadata,
+                })
+                .collect()
+        }
+        #[cfg(not(feature = "credential-vault"))]
+        let _ = entries;
+    }
+
+    /// Writes `snapshot` to the attached vault, if any.
+    ///
+    /// Callers hold `vault_writes`, take the snapshot under a short credentials lock, and
+    /// change memory only once this succeeds, so a failed write leaves both the vault and the
+    /// manager unchanged.
+    fn write_vault(&self, snapshot: VaultSnapshot) -> Result<(), ProviderError> {
+        #[cfg(feature = "credential-vault")]
+        if let Some(vault) = &self.vault {
+            vault.write(&snapshot)?;
         }
+        #[cfg(not(feature = "credential-vault"))]
+        let () = snapshot;
+
+        Ok(())
+    }
+
+    /// Registers several API keys for `provider`. While a pool is set, API key
+    /// lookups select from it instead of the single stored key.
+    pub fn set_api_key_pool(
+        &self,
+        provider: ProviderId,
+        pool: ApiKeyPool,
+    ) -> Result<(), ProviderError> {
+        pool.validate()?;
+        let replaced = self.pools_mut()?.insert(provider, pool).is_some();
+        self.emit(CredentialAccessEvent {
+            provider,
+            kind: Some(CredentialKind::ApiKey),
+            action: if replaced {
+                CredentialAccessAction::Rotated
+            } else {
+                CredentialAccessAction::Set
+            },
+            key_index: None,
+        });
+        Ok(())
+    }
+
+    pub fn api_key_pool_status(
+        &self,
+        provider: ProviderId,
+    ) -> Result<Option<Vec<PooledKeyStatus>>, ProviderError> {
+        Ok(self.pools_mut()?.get(&provider).map(ApiKeyPool::status))
+    }
+
+    /// Benches the pooled key `api_key` for `retry_after` (or the pool default).
+    ///
+    /// Returns `false` when `provider` has no pool or the key is not part of it.
+    pub fn report_rate_limited(
+        &self,
+        provider: ProviderId,
+        api_key: &str,
+        retry_after: Option<Duration>,
+    ) -> Result<bool, ProviderError> {
+        let index = self
+            .pools_mut()?
+            .get_mut(&provider)
+            .and_then(|pool| pool.bench(api_key, retry_after, SystemTime::now()));
+        let Some(index) = index else {
+            return Ok(false);
+        };
+
+        self.emit(CredentialAccessEvent {
+            provider,
+            kind: Some(CredentialKind::ApiKey),
+            action: CredentialAccessAction::KeyBenched,
+            key_index: Some(index),
+        });
+        Ok(true)
+    }
+
+    /// Feeds a provider call failure back into key pool state.
+    #[cfg_attr(
+        not(any(feature = "provider-openai", feature = "provider-gemini")),
+        allow(dead_code)
+    )]
+    pub(crate) fn observe_error(&self, provider: ProviderId, api_key: &str, error: &ProviderError) {
+        if error.kind == ProviderErrorKind::RateLimited {
+            let _ = self.report_rate_limited(provider, api_key, error.retry_after);
+        }
+    }
+
+    /// Registers a source that is consulted lazily whenever `provider` has no fresh
+    /// credential. Replaces any previously registered source for that provider.
+    pub fn set_source(
+        &self,
+        provider: ProviderId,
+        source: Arc<dyn CredentialSource>,
+    ) -> Result<(), ProviderError> {
+        self.sources_mut()?.insert(provider, source);
+        Ok(())
+    }
+
+    pub fn has_source(&self, provider: ProviderId) -> Result<bool, ProviderError> {
+        Ok(self.sources_mut()?.contains_key(&provider))
     }
 
     pub fn set_api_key(
@@ -260,12 +513,17 @@ impl SecureCredentialManager {
             return Err(ProviderError::authentication("api key must not be empty"));
         }
 
-        let action =
-            self.insert_credential(provider, ProviderCredential::ApiKey(api_key), expires_at)?;
+        let action = self.insert_credential(
+            provider,
+            ProviderCredential::ApiKey(api_key),
+            expires_at,
+            false,
+        )?;
         self.emit(CredentialAccessEvent {
             provider,
             kind: Some(CredentialKind::ApiKey),
             action,
+            key_index: None,
         });
         Ok(())
     }
@@ -287,16 +545,52 @@ impl SecureCredentialManager {
             provider,
             ProviderCredential::BrowserSession(session),
             expires_at,
+            false,
         )?;
         self.emit(CredentialAccessEvent {
             provider,
             kind: Some(CredentialKind::BrowserSession),
             action,
+            key_index: None,
+        });
+        Ok(())
+    }
+
+    /// Stores a bearer access token that stops resolving once `expires_at` passes.
+    pub fn set_access_token(
+        &self,
+        provider: ProviderId,
+        access_token: impl Into<String>,
+        expires_at: Option<SystemTime>,
+    ) -> Result<(), ProviderError> {
+        let access_token = SecretString::new(access_token);
+        if access_token.is_empty() {
+            return Err(ProviderError::authentication(
+                "access token must not be empty",
+            ));
+        }
+
+        let action = self.insert_credential(
+            provider,
+            ProviderCredential::AccessToken(access_token),
+            expires_at,
+            false,
+        )?;
+        self.emit(CredentialAccessEvent {
+            provider,
+            kind: Some(CredentialKind::AccessToken),
+            action,
+            key_index: None,
         });
         Ok(())
     }
 
     pub fn has_credentials(&self, provider: ProviderId) -> Result<bool, ProviderError> {
+        if self.pools_mut()?.contains_key(&provider) {
+            return Ok(true);
+        }
+
+        self.refresh_from_source(provider)?;
         let mut credentials = self.credentials_mut()?;
         if Self::remove_if_expired(&mut credentials, provider, SystemTime::now()) {
             return Ok(false);
@@ -309,6 +603,11 @@ impl SecureCredentialManager {
         &self,
         provider: ProviderId,
     ) -> Result<Option<CredentialKind>, ProviderError> {
+        if self.pools_mut()?.contains_key(&provider) {
+            return Ok(Some(CredentialKind::ApiKey));
+        }
+
+        self.refresh_from_source(provider)?;
         let mut credentials = self.credentials_mut()?;
         if Self::remove_if_expired(&mut credentials, provider, SystemTime::now()) {
             return Ok(None);
@@ -323,6 +622,7 @@ impl SecureCredentialManager {
         &self,
         provider: ProviderId,
     ) -> Result<Option<CredentialMetadata>, ProviderError> {
+        self.refresh_from_source(provider)?;
         let mut credentials = self.credentials_mut()?;
         if Self::remove_if_expired(&mut credentials, provider, SystemTime::now()) {
             return Ok(None);
@@ -336,48 +636,18 @@ impl SecureCredentialManager {
         provider: ProviderId,
         f: impl FnOnce(&str) -> R,
     ) -> Result<Option<R>, ProviderError> {
-        let now = SystemTime::now();
-        let mut credentials = self.credentials_mut()?;
-
-        if Self::remove_if_expired(&mut credentials, provider, now) {
-            drop(credentials);
-            self.emit(CredentialAccessEvent {
-                provider,
-                kind: Some(CredentialKind::ApiKey),
-                action: CredentialAccessAction::AccessDenied,
-            });
-            return Ok(None);
+        if self.pools_mut()?.contains_key(&provider) {
+            return self.with_pooled_api_key(provider, f);
         }
 
-        let output = match credentials.get_mut(&provider) {
-            Some(entry) => {
-                if !matches!(entry.credential, ProviderCredential::ApiKey(_)) {
-                    None
-                } else {
-                    entry.metadata.mark_used(now);
-                    match &entry.credential {
-                        ProviderCredential::ApiKey(secret) => Some(f(secret.expose())),
-                        _ => None,
-                    }
-                }
-            }
-            None => None,
-        };
-
-        let action = if output.is_some() {
-            CredentialAccessAction::AccessGranted
-        } else {
-            CredentialAccessAction::AccessDenied
-        };
-
-        drop(credentials);
-        self.emit(CredentialAccessEvent {
+        self.with_credential(
             provider,
-            kind: Some(CredentialKind::ApiKey),
-            action,
-        });
-
-        Ok(output)
+            CredentialKind::ApiKey,
+            |credential| match credential {
+                ProviderCredential::ApiKey(secret) => Some(f(secret.expose())),
+                _ => None,
+            },
+        )
     }
 
     pub fn api_key(&self, provider: ProviderId) -> Result<Option<SecretString>, ProviderError> {
@@ -389,6 +659,103 @@ impl SecureCredentialManager {
         provider: ProviderId,
         f: impl FnOnce(&BrowserLoginSession) -> R,
     ) -> Result<Option<R>, ProviderError> {
+        self.with_credential(
+            provider,
+            CredentialKind::BrowserSession,
+            |credential| match credential {
+                ProviderCredential::BrowserSession(session) => Some(f(session)),
+                _ => None,
+            },
+        )
+    }
+
+    pub fn browser_session(
+        &self,
+        provider: ProviderId,
+    ) -> Result<Option<BrowserLoginSession>, ProviderError> {
+        self.with_browser_session(provider, |session| BrowserLoginSession {
+            session_token: session.session_token.clone(),
+            expires_at: session.expires_at,
+        })
+    }
+
+    pub fn with_access_token<R>(
+        &self,
+        provider: ProviderId,
+        f: impl FnOnce(&str) -> R,
+    ) -> Result<Option<R>, ProviderError> {
+        self.with_credential(
+            provider,
+            CredentialKind::AccessToken,
+            |credential| match credential {
+                ProviderCredential::AccessToken(token) => Some(f(token.expose())),
+                _ => None,
+            },
+        )
+    }
+
+    pub fn access_token(
+        &self,
+        provider: ProviderId,
+    ) -> Result<Option<SecretString>, ProviderError> {
+        self.with_access_token(provider, |value| SecretString::new(value.to_string()))
+    }
+
+    pub fn clear(&self, provider: ProviderId) -> Result<bool, ProviderError> {
+        self.revoke(provider)
+    }
+
+    /// Removes the stored credential, key pool and any registered source for `provider`.
+    ///
+    /// Nothing is removed when the vault cannot be rewritten.
+    pub fn revoke(&self, provider: ProviderId) -> Result<bool, ProviderError> {
+        let _vault_writes = self.vault_writes_mut()?;
+        let snapshot = {
+            let credentials = self.credentials_mut()?;
+            credentials.contains_key(&provider).then(|| {
+                self.vault_snapshot(
+                    credentials
+                        .iter()
+                        .filter(|(stored, _)| **stored != provider),
+                )
+            })
+        };
+        if let Some(snapshot) = snapshot {
+            self.write_vault(snapshot)?;
+        }
+
+        let mut sources = self.sources_mut()?;
+        let mut pools = self.pools_mut()?;
+        let mut credentials = self.credentials_mut()?;
+        sources.remove(&provider);
+        let had_pool = pools.remove(&provider).is_some();
+        let removed = credentials.remove(&provider);
+        drop((credentials, pools, sources));
+        let kind = removed
+            .as_ref()
+            .map(|entry| entry.credential.kind())
+            .or(had_pool.then_some(CredentialKind::ApiKey));
+        let had_value = removed.is_some() || had_pool;
+
+        if had_value {
+            self.emit(CredentialAccessEvent {
+                provider,
+                kind,
+                action: CredentialAccessAction::Cleared,
+                key_index: None,
+            });
+        }
+
+        Ok(had_value)
+    }
+
+    fn with_credential<R>(
+        &self,
+        provider: ProviderId,
+        kind: CredentialKind,
+        f: impl FnOnce(&ProviderCredential) -> Option<R>,
+    ) -> Result<Option<R>, ProviderError> {
+        self.refresh_from_source(provider)?;
         let now = SystemTime::now();
         let mut credentials = self.credentials_mut()?;
 
@@ -396,25 +763,19 @@ impl SecureCredentialManager {
             drop(credentials);
             self.emit(CredentialAccessEvent {
                 provider,
-                kind: Some(CredentialKind::BrowserSession),
+                kind: Some(kind),
                 action: CredentialAccessAction::AccessDenied,
+                key_index: None,
             });
             return Ok(None);
         }
 
         let output = match credentials.get_mut(&provider) {
-            Some(entry) => {
-                if !matches!(entry.credential, ProviderCredential::BrowserSession(_)) {
-                    None
-                } else {
-                    entry.metadata.mark_used(now);
-                    match &entry.credential {
-                        ProviderCredential::BrowserSession(session) => Some(f(session)),
-                        _ => None,
-                    }
-                }
+            Some(entry) if entry.credential.kind() == kind => {
+                entry.metadata.mark_used(now);
+                f(&entry.credential)
             }
-            None => None,
+            _ => None,
         };
 
         let action = if output.is_some() {
@@ -426,80 +787,189 @@ impl SecureCredentialManager {
         drop(credentials);
         self.emit(CredentialAccessEvent {
             provider,
-            kind: Some(CredentialKind::BrowserSession),
+            kind: Some(kind),
             action,
+            key_index: None,
         });
 
         Ok(output)
     }
 
-    pub fn browser_session(
+    fn credentials_mut(
         &self,
-        provider: ProviderId,
-    ) -> Result<Option<BrowserLoginSession>, ProviderError> {
-        self.with_browser_session(provider, |session| BrowserLoginSession {
-            session_token: session.session_token.clone(),
-            expires_at: session.expires_at,
-        })
+    ) -> Result<MutexGuard<'_, HashMap<ProviderId, CredentialEntry>>, ProviderError> {
+        self.credentials
+            .lock()
+            .map_err(|_| ProviderError::other("credential manager lock poisoned"))
     }
 
-    pub fn clear(&self, provider: ProviderId) -> Result<bool, ProviderError> {
-        self.revoke(provider)
-    }
+    /// Selects a key from `provider`'s pool; `Ok(None)` when no pool is registered.
+    fn with_pooled_api_key<R>(
+        &self,
+        provider: ProviderId,
+        f: impl FnOnce(&str) -> R,
+    ) -> Result<Option<R>, ProviderError> {
+        let mut pools = self.pools_mut()?;
+        let Some(pool) = pools.get_mut(&provider) else {
+            return Ok(None);
+        };
 
-    pub fn revoke(&self, provider: ProviderId) -> Result<bool, ProviderError> {
-        let mut credentials = self.credentials_mut()?;
-        let removed = credentials.remove(&provider);
-        let kind = removed.as_ref().map(|entry| entry.credential.kind());
-        let had_value = removed.is_some();
-        drop(credentials);
+        let selection = match pool.select(SystemTime::now()) {
+            Ok(selection) => selection,
+            Err(err) => {
+                drop(pools);
+                self.emit(CredentialAccessEvent {
+                    provider,
+                    kind: Some(CredentialKind::ApiKey),
+                    action: CredentialAccessAction::AccessDenied,
+                    key_index: None,
+                });
+                return Err(err);
+            }
+        };
+        let output = f(pool.secret(selection.index).expose());
+        drop(pools);
 
-        if had_value {
+        for index in selection.restored {
             self.emit(CredentialAccessEvent {
                 provider,
-                kind,
-                action: CredentialAccessAction::Cleared,
+                kind: Some(CredentialKind::ApiKey),
+                action: CredentialAccessAction::KeyRestored,
+                key_index: Some(index),
             });
         }
+        self.emit(CredentialAccessEvent {
+            provider,
+            kind: Some(CredentialKind::ApiKey),
+            action: CredentialAccessAction::AccessGranted,
+            key_index: Some(selection.index),
+        });
 
-        Ok(had_value)
+        Ok(Some(output))
     }
 
-    fn credentials_mut(
-        &self,
-    ) -> Result<MutexGuard<'_, HashMap<ProviderId, CredentialEntry>>, ProviderError> {
-        self.credentials
+    fn pools_mut(&self) -> Result<MutexGuard<'_, PoolMap>, ProviderError> {
+        self.pools
             .lock()
             .map_err(|_| ProviderError::other("credential manager lock poisoned"))
     }
 
+    fn vault_writes_mut(&self) -> Result<MutexGuard<'_, ()>, ProviderError> {
+        self.vault_writes
+            .lock()
+            .map_err(|_| ProviderError::other("credential manager lock poisoned"))
+    }
+
+    fn sources_mut(&self) -> Result<MutexGuard<'_, SourceMap>, ProviderError> {
+        self.sources
+            .lock()
+            .map_err(|_| ProviderError::other("credential manager lock poisoned"))
+    }
+
+    /// Reloads `provider` from its source when the cached credential is missing,
+    /// within the refresh margin of expiring, or reported stale by the source.
+    ///
+    /// A failed reload keeps a still-valid cached credential.
+    fn refresh_from_source(&self, provider: ProviderId) -> Result<(), ProviderError> {
+        let Some(source) = self.sources_mut()?.get(&provider).cloned() else {
+            return Ok(());
+        };
+
+        let now = SystemTime::now();
+        let has_usable_credential = {
+            let credentials = self.credentials_mut()?;
+            match credentials.get(&provider) {
+                None => false,
+                Some(entry) => {
+                    let refresh_at = entry.metadata.refresh_at(self.refresh_margin);
+                    if refresh_at.is_none_or(|refresh_at| refresh_at > now) && !source.is_stale() {
+                        return Ok(());
+                    }
+
+                    !entry.metadata.is_expired(now)
+                }
+            }
+        };
+
+        self.load_from_source(provider, source.as_ref(), has_usable_credential)
+    }
+
+    fn load_from_source(
+        &self,
+        provider: ProviderId,
+        source: &dyn CredentialSource,
+        has_usable_credential: bool,
+    ) -> Result<(), ProviderError> {
+        let loaded = match source.load() {
+            Ok(loaded) => loaded,
+            Err(_) if has_usable_credential => return Ok(()),
+            Err(err) => return Err(err),
+        };
+        let Some(loaded) = loaded else {
+            return Ok(());
+        };
+
+        let kind = loaded.credential.kind();
+        let action =
+            self.insert_credential(provider, loaded.credential, loaded.expires_at, true)?;
+        self.emit(CredentialAccessEvent {
+            provider,
+            kind: Some(kind),
+            action,
+            key_index: None,
+        });
+        Ok(())
+    }
+
+    /// Stores `credential`, writing explicitly set ones to the vault before they replace
+    /// anything in memory.
     fn insert_credential(
         &self,
         provider: ProviderId,
         credential: ProviderCredential,
         expires_at: Option<SystemTime>,
+        from_source: bool,
     ) -> Result<CredentialAccessAction, ProviderError> {
         let now = SystemTime::now();
-        let mut credentials = self.credentials_mut()?;
-        let action = if credentials.contains_key(&provider) {
-            CredentialAccessAction::Rotated
-        } else {
-            CredentialAccessAction::Set
-        };
-
-        let metadata = match action {
-            CredentialAccessAction::Set => CredentialMetadata::new(now, expires_at),
-            CredentialAccessAction::Rotated => CredentialMetadata::with_rotation(now, expires_at),
-            _ => unreachable!(),
-        };
+        let _vault_writes = (!from_source)
+            .then(|| self.vault_writes_mut())
+            .transpose()?;
+        let (action, entry, snapshot) = {
+            let credentials = self.credentials_mut()?;
+            let action = if credentials.contains_key(&provider) {
+                CredentialAccessAction::Rotated
+            } else {
+                CredentialAccessAction::Set
+            };
+
+            let metadata = match action {
+                CredentialAccessAction::Set => CredentialMetadata::new(now, expires_at),
+                CredentialAccessAction::Rotated => {
+                    CredentialMetadata::with_rotation(now, expires_at)
+                }
+                _ => unreachable!(),
+            };
 
-        credentials.insert(
-            provider,
-            CredentialEntry {
+            let entry = CredentialEntry {
                 credential,
                 metadata,
-            },
-        );
+                from_source,
+            };
+            let snapshot = (!from_source).then(|| {
+                self.vault_snapshot(
+                    credentials
+                        .iter()
+                        .filter(|(stored, _)| **stored != provider)
+                        .chain(std::iter::once((&provider, &entry))),
+                )
+            });
+            (action, entry, snapshot)
+        };
+
+        if let Some(snapshot) = snapshot {
+            self.write_vault(snapshot)?;
+        }
+        self.credentials_mut()?.insert(provider, entry);
 
         Ok(action)
     }
@@ -12,6 +12,7 @@
 
 use std::error::Error;
 use std::fmt::{Display, Formatter};
+use std::time::Duration;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ProviderErrorKind {
@@ -29,6 +30,8 @@ pub struct ProviderError {
     pub kind: ProviderErrorKind,
     pub message: String,
     pub retryable: bool,
+    /// Server-provided hint for when a retry may succeed, e.g. from `Retry-After`.
+    pub retry_after: Option<Duration>,
 }
 
 impl ProviderError {
@@ -37,9 +40,15 @@ impl ProviderError {
             kind,
             message: message.into(),
             retryable,
+            retry_after: None,
         }
     }
 
+    pub fn with_retry_after(mut self, retry_after: Duration) -> Self {
+        self.retry_after = Some(retry_after);
+        self
+    }
+
     pub fn authentication(message: impl Into<String>) -> Self {
         Self::new(ProviderErrorKind::Authentication, message, false)
     }
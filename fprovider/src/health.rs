@@ -0,0 +1,305 @@
+//! Provider health probes and readiness reporting.
+//!
+//! [`ModelProvider::health`] runs a lightweight probe and reports a [`ProviderHealth`].
+//! Adapters override it with whatever check is cheapest for their API; providers without a
+//! probe report [`HealthStatus::Unknown`]. [`HealthProbeLayer`] replaces any provider's probe
+//! with a minimal one-token completion when a real round trip is required.
+//!
+//! ```rust
+//! use std::time::Duration;
+//!
+//! use fprovider::{HealthStatus, ProviderError, ProviderHealth, ProviderId};
+//!
+//! let healthy = ProviderHealth::healthy(ProviderId::OpenAi, Duration::from_millis(40));
+//! assert!(healthy.is_ready());
+//!
+//! let failed = ProviderHealth::unhealthy(
+//!     ProviderId::OpenAi,
+//!     ProviderError::authentication("bad key"),
+//!     Duration::from_millis(40),
+//! );
+//! assert_eq!(failed.status, HealthStatus::Unhealthy);
+//! assert!(!failed.is_ready());
+//! ```
+
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
+
+use crate::{
+    BoxedEventStream, Message, ModelInfo, ModelProvider, ModelRequest, ModelResponse,
+    ProviderError, ProviderErrorKind, ProviderFuture, ProviderId, ProviderLayer, Role,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HealthStatus {
+    Healthy,
+    Unhealthy,
+    /// The provider has no probe, or the probe could not reach a verdict.
+    Unknown,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProviderHealth {
+    pub provider: ProviderId,
+    pub status: HealthStatus,
+    /// Time spent in the probe; zero when no probe ran.
+    pub latency: Duration,
+    pub checked_at: SystemTime,
+    pub error: Option<ProviderError>,
+    pub detail: Option<String>,
+}
+
+impl ProviderHealth {
+    pub fn healthy(provider: ProviderId, latency: Duration) -> Self {
+        Self::new(provider, HealthStatus::Healthy, latency)
+    }
+
+    pub fn unhealthy(provider: ProviderId, error: ProviderError, latency: Duration) -> Self {
+        Self {
+            error: Some(error),
+            ..Self::new(provider, HealthStatus::Unhealthy, latency)
+        }
+    }
+
+    pub fn unknown(provider: ProviderId, detail: impl Into<String>) -> Self {
+        Self {
+            detail: Some(detail.into()),
+            ..Self::new(provider, HealthStatus::Unknown, Duration::ZERO)
+        }
+    }
+
+    /// Maps the outcome of a probe that started at `started`.
+    pub fn from_probe(
+        provider: ProviderId,
+        started: Instant,
+        outcome: Result<(), ProviderError>,
+    ) -> Self {
+        match outcome {
+            Ok(()) => Self::healthy(provider, started.elapsed()),
+            Err(err) => Self::unhealthy(provider, err, started.elapsed()),
+        }
+    }
+
+    pub fn with_detail(mut self, detail: impl Into<String>) -> Self {
+        self.detail = Some(detail.into());
+        self
+    }
+
+    /// Only a failed probe makes a provider unready; `Unknown` gives it the benefit of the doubt.
+    pub fn is_ready(&self) -> bool {
+        self.status != HealthStatus::Unhealthy
+    }
+
+    pub(crate) fn is_fresh(&self, ttl: Duration) -> bool {
+        self.checked_at
+            .elapsed()
+            .map(|age| age < ttl)
+            .unwrap_or(false)
+    }
+
+    fn new(provider: ProviderId, status: HealthStatus, latency: Duration) -> Self {
+        Self {
+            provider,
+            status,
+            latency,
+            checked_at: SystemTime::now(),
+            error: None,
+            detail: None,
+        }
+    }
+}
+
+/// Maps a probe that lists the provider's models. Rejected credentials (401/403), missing keys,
+/// and unreachable endpoints are unhealthy; a transport that cannot list models gives no verdict.
+#[cfg_attr(
+    not(any(feature = "provider-openai", feature = "provider-gemini")),
+    allow(dead_code)
+)]
+pub(crate) fn model_listing_health(
+    provider: ProviderId,
+    started: Instant,
+    outcome: Result<Vec<ModelInfo>, ProviderError>,
+) -> ProviderHealth {
+    match outcome {
+        Err(err) if err.kind == ProviderErrorKind::InvalidRequest => ProviderHealth {
+            latency: started.elapsed(),
+            ..ProviderHealth::unknown(provider, err.message)
+        },
+        outcome => ProviderHealth::from_probe(provider, started, outcome.map(|_| ())),
+    }
+}
+
+/// Overrides the wrapped provider's health probe with a one-token completion against `model`.
+#[derive(Debug, Clone)]
+pub struct HealthProbeLayer {
+    model: String,
+}
+
+impl HealthProbeLayer {
+    pub fn completion(model: impl Into<String>) -> Self {
+        Self {
+            model: model.into(),
+        }
+    }
+}
+
+impl ProviderLayer for HealthProbeLayer {
+    fn layer(&self, inner: Arc<dyn ModelProvider>) -> Arc<dyn ModelProvider> {
+        Arc::new(CompletionProbeProvider {
+            inner,
+            model: self.model.clone(),
+        })
+    }
+}
+
+struct CompletionProbeProvider {
+    inner: Arc<dyn ModelProvider>,
+    model: String,
+}
+
+impl ModelProvider for CompletionProbeProvider {
+    fn id(&self) -> ProviderId {
+        self.inner.id()
+    }
+
+    fn complete<'a>(
+        &'a self,
+        request: ModelRequest,
+    ) -> ProviderFuture<'a, Result<ModelResponse, ProviderError>> {
+        self.inner.complete(request)
+    }
+
+    fn stream<'a>(
+        &'a self,
+        request: ModelRequest,
+    ) -> ProviderFuture<'a, Result<BoxedEventStream<'a>, ProviderError>> {
+        self.inner.stream(request)
+    }
+
+    fn health<'a>(&'a self) -> ProviderFuture<'a, ProviderHealth> {
+        Box::pin(async move {
+            let started = Instant::now();
+            let outcome = match ModelRequest::builder(self.model.clone())
+                .message(Message::new(Role::User, "ping"))
+                .max_tokens(1)
+                .build()
+            {
+                Ok(request) => self.inner.complete(request).await.map(|_| ()),
+                Err(err) => Err(err),
+            };
+            ProviderHealth::from_probe(self.id(), started, outcome)
+        })
+    }
+
+    fn list_models<'a>(&'a self) -> ProviderFuture<'a, Result<Vec<ModelInfo>, ProviderError>> {
+        self.inner.list_models()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+    use crate::{OutputItem, ProviderRegistry, StopReason, TokenUsage};
+
+    struct ProbeProvider {
+        id: ProviderId,
+        completions: AtomicUsize,
+        fail: bool,
+    }
+
+    impl ProbeProvider {
+        fn new(id: ProviderId, fail: bool) -> Self {
+            Self {
+                id,
+                completions: AtomicUsize::new(0),
+                fail,
+            }
+        }
+    }
+
+    impl ModelProvider for ProbeProvider {
+        fn id(&self) -> ProviderId {
+            self.id
+        }
+
+        fn complete<'a>(
+            &'a self,
+            request: ModelRequest,
+        ) -> ProviderFuture<'a, Result<ModelResponse, ProviderError>> {
+            Box::pin(async move {
+                self.completions.fetch_add(1, Ordering::SeqCst);
+                if self.fail {
+                    return Err(ProviderError::authentication("invalid key"));
+                }
+                assert_eq!(request.options.max_tokens, Some(1));
+                Ok(ModelResponse {
+                    provider: self.id,
+                    model: request.model,
+                    output: vec![OutputItem::Message(Message::new(Role::Assistant, "pong"))],
+                    stop_reason: StopReason::MaxTokens,
+                    usage: TokenUsage::default(),
+                    response_id: None,
+                })
+            })
+        }
+
+        fn stream<'a>(
+            &'a self,
+            _request: ModelRequest,
+        ) -> ProviderFuture<'a, Result<BoxedEventStream<'a>, ProviderError>> {
+            Box::pin(async { Err(ProviderError::invalid_request("stream not used")) })
+        }
+    }
+
+    #[tokio::test]
+    async fn providers_without_a_probe_report_unknown_but_ready() {
+        let health = ProbeProvider::new(ProviderId::OpenAi, false).health().await;
+
+        assert_eq!(health.status, HealthStatus::Unknown);
+        assert!(health.is_ready());
+    }
+
+    #[tokio::test]
+    async fn completion_probe_maps_failures_to_unhealthy() {
+        let layer = HealthProbeLayer::completion("gpt-4o-mini");
+        let healthy = layer
+            .layer(Arc::new(ProbeProvider::new(ProviderId::OpenAi, false)))
+            .health()
+            .await;
+        let failing = layer
+            .layer(Arc::new(ProbeProvider::new(ProviderId::Anthropic, true)))
+            .health()
+            .await;
+
+        assert_eq!(healthy.status, HealthStatus::Healthy);
+        assert_eq!(failing.status, HealthStatus::Unhealthy);
+        assert_eq!(failing.provider, ProviderId::Anthropic);
+        assert!(failing.error.is_some_and(|err| !err.retryable));
+    }
+
+    #[tokio::test]
+    async fn registry_caches_probe_results_until_the_ttl_expires() {
+        let provider = Arc::new(ProbeProvider::new(ProviderId::OpenAi, false));
+        let probed = HealthProbeLayer::completion("gpt-4o-mini").layer(provider.clone());
+        let mut registry = ProviderRegistry::new().with_health_ttl(Duration::from_secs(60));
+        registry.register_arc(probed);
+        registry.register(ProbeProvider::new(ProviderId::Gemini, true));
+
+        let first = registry.health_all().await;
+        let second = registry.health_all().await;
+        assert_eq!(first, second);
+        assert_eq!(first.len(), 2);
+        assert_eq!(provider.completions.load(Ordering::SeqCst), 1);
+        assert!(registry.is_ready().await);
+
+        registry.invalidate_health(ProviderId::OpenAi);
+        registry
+            .health(ProviderId::OpenAi)
+            .await
+            .expect("provider is registered");
+        assert_eq!(provider.completions.load(Ordering::SeqCst), 2);
+        assert!(registry.health(ProviderId::Ollama).await.is_none());
+    }
+}
@@ -0,0 +1,376 @@
+//! Multi-key API credential pools with rate-limit benching.
+//!
+//! ```rust
+//! use fprovider::{ApiKeyPool, KeySelectionStrategy, ProviderId, SecureCredentialManager};
+//!
+//! let manager = SecureCredentialManager::new();
+//! manager
+//!     .set_api_key_pool(
+//!         ProviderId::OpenAi,
+//!         ApiKeyPool::new(KeySelectionStrategy::RoundRobin)
+//!             .with_key("sk-a")
+//!             .with_key("sk-b"),
+//!     )
+//!     .expect("pool should register");
+//!
+//! let first = manager.api_key(ProviderId::OpenAi).expect("lookup").expect("key");
+//! let second = manager.api_key(ProviderId::OpenAi).expect("lookup").expect("key");
+//! assert_eq!((first.expose(), second.expose()), ("sk-a", "sk-b"));
+//! ```
+
+use std::time::{Duration, SystemTime};
+
+use crate::{ProviderError, SecretString};
+
+const DEFAULT_BENCH_DURATION: Duration = Duration::from_secs(60);
+
+/// How an [`ApiKeyPool`] picks the next key among those not currently benched.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum KeySelectionStrategy {
+    /// Cycle through keys in registration order.
+    #[default]
+    RoundRobin,
+    /// Prefer the key whose last rate limit is oldest (or that was never limited).
+    LeastRecentlyRateLimited,
+    /// Smooth weighted round-robin using each key's weight.
+    Weighted,
+}
+
+/// Several API keys for one provider.
+///
+/// Keys that return `RateLimited` are benched for the error's retry-after window
+/// (or the pool's default bench duration) and skipped until it elapses.
+#[derive(Debug)]
+pub struct ApiKeyPool {
+    keys: Vec<PooledKey>,
+    strategy: KeySelectionStrategy,
+    default_bench: Duration,
+    cursor: usize,
+}
+
+#[derive(Debug)]
+struct PooledKey {
+    secret: SecretString,
+    weight: u32,
+    credit: i64,
+    benched_until: Option<SystemTime>,
+    last_rate_limited_at: Option<SystemTime>,
+}
+
+/// Snapshot of a pooled key's state, without the secret.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PooledKeyStatus {
+    pub index: usize,
+    pub weight: u32,
+    pub benched_until: Option<SystemTime>,
+    pub last_rate_limited_at: Option<SystemTime>,
+}
+
+pub(crate) struct PoolSelection {
+    pub(crate) index: usize,
+    pub(crate) restored: Vec<usize>,
+}
+
+impl ApiKeyPool {
+    pub fn new(strategy: KeySelectionStrategy) -> Self {
+        Self {
+            keys: Vec::new(),
+            strategy,
+            default_bench: DEFAULT_BENCH_DURATION,
+            cursor: 0,
+        }
+    }
+
+    pub fn with_key(self, api_key: impl Into<String>) -> Self {
+        self.with_weighted_key(api_key, 1)
+    }
+
+    pub fn with_weighted_key(mut self, api_key: impl Into<String>, weight: u32) -> Self {
+        self.keys.push(PooledKey {
+            secret: SecretString::new(api_key),
+            weight,
+            credit: 0,
+            benched_until: None,
+            last_rate_limited_at: None,
+        });
+        self
+    }
+
+    /// Bench duration used when a rate-limit error carries no retry-after hint.
+    pub fn with_default_bench(mut self, duration: Duration) -> Self {
+        self.default_bench = duration;
+        self
+    }
+
+    pub fn strategy(&self) -> KeySelectionStrategy {
+        self.strategy
+    }
+
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    pub fn status(&self) -> Vec<PooledKeyStatus> {
+        self.keys
+            .iter()
+            .enumerate()
+            .map(|(index, key)| PooledKeyStatus {
+                index,
+                weight: key.weight,
+                benched_until: key.benched_until,
+                last_rate_limited_at: key.last_rate_limited_at,
+            })
+            .collect()
+    }
+
+    pub(crate) fn validate(&self) -> Result<(), ProviderError> {
+        if self.keys.is_empty() {
+            return Err(ProviderError::authentication(
+                "api key pool must contain at least one key",
+            ));
+        }
+        if self.keys.iter().any(|key| key.secret.is_empty()) {
+            return Err(ProviderError::authentication("api key must not be empty"));
+        }
+        if self.keys.iter().any(|key| key.weight == 0) {
+            return Err(ProviderError::authentication(
+                "api key pool weights must be greater than zero",
+            ));
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn secret(&self, index: usize) -> &SecretString {
+        &self.keys[index].secret
+    }
+
+    pub(crate) fn select(&mut self, now: SystemTime) -> Result<PoolSelection, ProviderError> {
+        let mut restored = Vec::new();
+        for (index, key) in self.keys.iter_mut().enumerate() {
+            if key.benched_until.is_some_and(|until| until <= now) {
+                key.benched_until = None;
+                restored.push(index);
+            }
+        }
+
+        let len = self.keys.len();
+        let available = (0..len)
+            .map(|offset| (self.cursor + offset) % len)
+            .filter(|index| self.keys[*index].benched_until.is_none())
+            .collect::<Vec<_>>();
+
+        let Some(&first) = available.first() else {
+            let retry_after = self
+                .keys
+                .iter()
+                .filter_map(|key| key.benched_until)
+                .min()
+                .and_then(|until| until.duration_since(now).ok());
+            let mut error =
+                ProviderError::rate_limited("all pooled API keys are benched after rate limits");
+            if let Some(retry_after) = retry_after {
+                error = error.with_retry_after(retry_after);
+            }
+            return Err(error);
+        };
+
+        let index = match self.strategy {
+            KeySelectionStrategy::RoundRobin => first,
+            KeySelectionStrategy::LeastRecentlyRateLimited => available
+                .iter()
+                .copied()
+                .min_by_key(|index| self.keys[*index].last_rate_limited_at)
+                .unwrap_or(first),
+            KeySelectionStrategy::Weighted => {
+                let total = available
+                    .iter()
+                    .map(|index| i64::from(self.keys[*index].weight))
+                    .sum::<i64>();
+                for index in &available {
+                    let key = &mut self.keys[*index];
+                    key.credit += i64::from(key.weight);
+                }
+                let chosen = available
+                    .iter()
+                    .copied()
+                    .max_by_key(|index| (self.keys[*index].credit, std::cmp::Reverse(*index)))
+                    .unwrap_or(first);
+                self.keys[chosen].credit -= total;
+                chosen
+            }
+        };
+
+        self.cursor = (index + 1) % len;
+        Ok(PoolSelection { index, restored })
+    }
+
+    /// Benches the key matching `api_key`, returning its index when found.
+    pub(crate) fn bench(
+        &mut self,
+        api_key: &str,
+        retry_after: Option<Duration>,
+        now: SystemTime,
+    ) -> Option<usize> {
+        let index = self
+            .keys
+            .iter()
+            .position(|key| key.secret.expose() == api_key)?;
+        let key = &mut self.keys[index];
+        key.benched_until = now.checked_add(retry_after.unwrap_or(self.default_bench));
+        key.last_rate_limited_at = Some(now);
+        Some(index)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use super::*;
+    use crate::{
+        CredentialAccessAction, CredentialAccessEvent, CredentialAccessObserver, CredentialKind,
+        ProviderErrorKind, ProviderId, SecureCredentialManager,
+    };
+
+    fn selections(pool: &mut ApiKeyPool, count: usize) -> Vec<usize> {
+        let now = SystemTime::now();
+        (0..count)
+            .map(|_| pool.select(now).expect("selection should work").index)
+            .collect()
+    }
+
+    #[test]
+    fn round_robin_cycles_keys_and_skips_benched() {
+        let mut pool = ApiKeyPool::new(KeySelectionStrategy::RoundRobin)
+            .with_key("a")
+            .with_key("b")
+            .with_key("c");
+        assert_eq!(selections(&mut pool, 4), vec![0, 1, 2, 0]);
+
+        pool.bench("b", Some(Duration::from_secs(30)), SystemTime::now());
+        assert_eq!(selections(&mut pool, 3), vec![2, 0, 2]);
+    }
+
+    #[test]
+    fn weighted_selection_follows_weights() {
+        let mut pool = ApiKeyPool::new(KeySelectionStrategy::Weighted)
+            .with_weighted_key("a", 3)
+            .with_weighted_key("b", 1);
+
+        let picks = selections(&mut pool, 8);
+        assert_eq!(picks.iter().filter(|index| **index == 0).count(), 6);
+        assert_eq!(picks.iter().filter(|index| **index == 1).count(), 2);
+    }
+
+    #[test]
+    fn least_recently_rate_limited_prefers_oldest_limit() {
+        let now = SystemTime::now();
+        let mut pool = ApiKeyPool::new(KeySelectionStrategy::LeastRecentlyRateLimited)
+            .with_key("a")
+            .with_key("b");
+        pool.bench("a", Some(Duration::ZERO), now - Duration::from_secs(10));
+        pool.bench("b", Some(Duration::ZERO), now - Duration::from_secs(20));
+
+        let selection = pool.select(now).expect("selection should work");
+        assert_eq!(selection.index, 1);
+        assert_eq!(selection.restored, vec![0, 1]);
+    }
+
+    #[test]
+    fn fully_benched_pool_reports_rate_limited_with_retry_after() {
+        let now = SystemTime::now();
+        let mut pool = ApiKeyPool::new(KeySelectionStrategy::RoundRobin).with_key("a");
+        pool.bench("a", Some(Duration::from_secs(30)), now);
+
+        let err = pool
+            .select(now)
+            .err()
+            .expect("benched pool should not select");
+        assert_eq!(err.kind, ProviderErrorKind::RateLimited);
+        assert_eq!(err.retry_after, Some(Duration::from_secs(30)));
+    }
+
+    #[test]
+    fn manager_benches_rate_limited_keys_and_reports_events() {
+        #[derive(Default)]
+        struct Recorder {
+            events: Mutex<Vec<CredentialAccessEvent>>,
+        }
+
+        impl CredentialAccessObserver for Recorder {
+            fn on_event(&self, event: CredentialAccessEvent) {
+                self.events.lock().expect("events lock").push(event);
+            }
+        }
+
+        let recorder = Arc::new(Recorder::default());
+        let manager = SecureCredentialManager::with_observer(recorder.clone());
+        manager
+            .set_api_key_pool(
+                ProviderId::Gemini,
+                ApiKeyPool::new(KeySelectionStrategy::RoundRobin)
+                    .with_key("key-a")
+                    .with_key("key-b"),
+            )
+            .expect("pool should register");
+        assert_eq!(
+            manager
+                .credential_kind(ProviderId::Gemini)
+                .expect("lookup should work"),
+            Some(CredentialKind::ApiKey)
+        );
+
+        let benched = manager
+            .report_rate_limited(ProviderId::Gemini, "key-a", Some(Duration::from_secs(60)))
+            .expect("report should work");
+        assert!(benched);
+
+        for _ in 0..2 {
+            let key = manager
+                .api_key(ProviderId::Gemini)
+                .expect("lookup should work")
+                .expect("key should resolve");
+            assert_eq!(key.expose(), "key-b");
+        }
+
+        let status = manager
+            .api_key_pool_status(ProviderId::Gemini)
+            .expect("status should work")
+            .expect("pool should exist");
+        assert!(status[0].benched_until.is_some());
+        assert!(status[1].benched_until.is_none());
+
+        let events = recorder.events.lock().expect("events lock");
+        assert!(events.iter().any(|event| {
+            event.action == CredentialAccessAction::KeyBenched && event.key_index == Some(0)
+        }));
+        assert!(events.iter().any(|event| {
+            event.action == CredentialAccessAction::AccessGranted && event.key_index == Some(1)
+        }));
+    }
+
+    #[test]
+    fn manager_rejects_invalid_pools() {
+        let manager = SecureCredentialManager::new();
+        let err = manager
+            .set_api_key_pool(
+                ProviderId::OpenAi,
+                ApiKeyPool::new(KeySelectionStrategy::Weighted).with_weighted_key("sk-a", 0),
+            )
+            .expect_err("zero weight should fail");
+        assert_eq!(err.kind, ProviderErrorKind::Authentication);
+
+        let err = manager
+            .set_api_key_pool(
+                ProviderId::OpenAi,
+                ApiKeyPool::new(KeySelectionStrategy::RoundRobin),
+            )
+            .expect_err("empty pool should fail");
+        assert_eq!(err.kind, ProviderErrorKind::Authentication);
+    }
+}
@@ -0,0 +1,529 @@
+//! Composable middleware layers around [`ModelProvider`].
+//!
+//! A [`ProviderLayer`] wraps an `Arc<dyn ModelProvider>` and returns a new provider, in the
+//! spirit of tower's `Layer`. Most cross-cutting concerns only need to observe or rewrite
+//! requests, responses and stream events; those implement [`ProviderInterceptor`] and are
+//! lifted into a layer with [`InterceptorLayer`].
+//!
+//! ```rust
+//! use std::sync::Arc;
+//!
+//! use fprovider::{MetadataLayer, ModelProvider, ProviderLayer, RedactionLayer, apply_layers};
+//!
+//! fn wrap(provider: Arc<dyn ModelProvider>) -> Arc<dyn ModelProvider> {
+//!     let layers: Vec<Arc<dyn ProviderLayer>> = vec![
+//!         Arc::new(MetadataLayer::new().with_entry("tenant", "acme")),
+//!         Arc::new(RedactionLayer::new(["hunter2"])),
+//!     ];
+//!     apply_layers(provider, &layers)
+//! }
+//! ```
+
+use std::fmt;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use futures_core::Stream;
+
+use crate::{
+    BoxedEventStream, ModelInfo, ModelProvider, ModelRequest, ModelResponse, OutputItem,
+    ProviderError, ProviderFuture, ProviderHealth, ProviderId, StreamEvent,
+};
+
+const REDACTED: &str = "[REDACTED]";
+
+/// Wraps a provider with additional behavior.
+pub trait ProviderLayer: Send + Sync {
+    fn layer(&self, inner: Arc<dyn ModelProvider>) -> Arc<dyn ModelProvider>;
+}
+
+/// Applies `layers` around `provider`; the first layer ends up outermost.
+pub fn apply_layers(
+    provider: Arc<dyn ModelProvider>,
+    layers: &[Arc<dyn ProviderLayer>],
+) -> Arc<dyn ModelProvider> {
+    layers
+        .iter()
+        .rev()
+        .fold(provider, |inner, layer| layer.layer(inner))
+}
+
+/// Observes and optionally rewrites traffic flowing through a provider.
+///
+/// Every method defaults to a no-op, so implementations only override what they need.
+pub trait ProviderInterceptor: Send + Sync {
+    fn on_request(&self, _provider: ProviderId, _request: &mut ModelRequest) {}
+
+    fn on_response(&self, _provider: ProviderId, _response: &mut ModelResponse) {}
+
+    fn on_stream_event(&self, _provider: ProviderId, _event: &mut StreamEvent) {}
+
+    fn on_error(&self, _provider: ProviderId, _error: &ProviderError) {}
+}
+
+/// Lifts a [`ProviderInterceptor`] into a [`ProviderLayer`].
+#[derive(Clone)]
+pub struct InterceptorLayer {
+    interceptor: Arc<dyn ProviderInterceptor>,
+}
+
+impl InterceptorLayer {
+    pub fn new(interceptor: Arc<dyn ProviderInterceptor>) -> Self {
+        Self { interceptor }
+    }
+}
+
+impl fmt::Debug for InterceptorLayer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("InterceptorLayer").finish_non_exhaustive()
+    }
+}
+
+impl ProviderLayer for InterceptorLayer {
+    fn layer(&self, inner: Arc<dyn ModelProvider>) -> Arc<dyn ModelProvider> {
+        Arc::new(InterceptedProvider {
+            inner,
+            interceptor: Arc::clone(&self.interceptor),
+        })
+    }
+}
+
+struct InterceptedProvider {
+    inner: Arc<dyn ModelProvider>,
+    interceptor: Arc<dyn ProviderInterceptor>,
+}
+
+impl ModelProvider for InterceptedProvider {
+    fn id(&self) -> ProviderId {
+        self.inner.id()
+    }
+
+    fn complete<'a>(
+        &'a self,
+        mut request: ModelRequest,
+    ) -> ProviderFuture<'a, Result<ModelResponse, ProviderError>> {
+        Box::pin(async move {
+            let provider = self.id();
+            self.interceptor.on_request(provider, &mut request);
+            match self.inner.complete(request).await {
+                Ok(mut response) => {
+                    self.interceptor.on_response(provider, &mut response);
+                    Ok(response)
+                }
+                Err(err) => {
+                    self.interceptor.on_error(provider, &err);
+                    Err(err)
+                }
+            }
+        })
+    }
+
+    fn stream<'a>(
+        &'a self,
+        mut request: ModelRequest,
+    ) -> ProviderFuture<'a, Result<BoxedEventStream<'a>, ProviderError>> {
+        Box::pin(async move {
+            let provider = self.id();
+            self.interceptor.on_request(provider, &mut request);
+            match self.inner.stream(request).await {
+                Ok(inner) => Ok(Box::pin(InterceptedStream {
+                    inner,
+                    provider,
+                    interceptor: Arc::clone(&self.interceptor),
+                }) as BoxedEventStream<'a>),
+                Err(err) => {
+                    self.interceptor.on_error(provider, &err);
+                    Err(err)
+                }
+            }
+        })
+    }
+
+    fn health<'a>(&'a self) -> ProviderFuture<'a, ProviderHealth> {
+        self.inner.health()
+    }
+
+    fn list_models<'a>(&'a self) -> ProviderFuture<'a, Result<Vec<ModelInfo>, ProviderError>> {
+        self.inner.list_models()
+    }
+}
+
+struct InterceptedStream<'a> {
+    inner: BoxedEventStream<'a>,
+    provider: ProviderId,
+    interceptor: Arc<dyn ProviderInterceptor>,
+}
+
+impl Stream for InterceptedStream<'_> {
+    type Item = Result<StreamEvent, ProviderError>;
+
+    fn poll_next(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<StreamEvent, ProviderError>>> {
+        let next = self.inner.as_mut().poll_next(cx);
+        match next {
+            Poll::Ready(Some(Ok(mut event))) => {
+                if let StreamEvent::ResponseComplete(response) = &mut event {
+                    self.interceptor.on_response(self.provider, response);
+                }
+                self.interceptor.on_stream_event(self.provider, &mut event);
+                Poll::Ready(Some(Ok(event)))
+            }
+            Poll::Ready(Some(Err(err))) => {
+                self.interceptor.on_error(self.provider, &err);
+                Poll::Ready(Some(Err(err)))
+            }
+            other => other,
+        }
+    }
+}
+
+/// Inserts fixed metadata entries into every request, overriding existing values.
+#[derive(Debug, Clone, Default)]
+pub struct MetadataLayer {
+    entries: Vec<(String, String)>,
+}
+
+impl MetadataLayer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_entry(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.entries.push((key.into(), value.into()));
+        self
+    }
+}
+
+impl ProviderInterceptor for MetadataLayer {
+    fn on_request(&self, _provider: ProviderId, request: &mut ModelRequest) {
+        for (key, value) in &self.entries {
+            request.metadata.insert(key.clone(), value.clone());
+        }
+    }
+}
+
+impl ProviderLayer for MetadataLayer {
+    fn layer(&self, inner: Arc<dyn ModelProvider>) -> Arc<dyn ModelProvider> {
+        InterceptorLayer::new(Arc::new(self.clone())).layer(inner)
+    }
+}
+
+type RequestRewrite = dyn Fn(&mut ModelRequest) + Send + Sync;
+
+/// Rewrites each request before it reaches the wrapped provider.
+#[derive(Clone)]
+pub struct MapRequestLayer {
+    rewrite: Arc<RequestRewrite>,
+}
+
+impl MapRequestLayer {
+    pub fn new<F>(rewrite: F) -> Self
+    where
+        F: Fn(&mut ModelRequest) + Send + Sync + 'static,
+    {
+        Self {
+            rewrite: Arc::new(rewrite),
+        }
+    }
+}
+
+impl fmt::Debug for MapRequestLayer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MapRequestLayer").finish_non_exhaustive()
+    }
+}
+
+impl ProviderInterceptor for MapRequestLayer {
+    fn on_request(&self, _provider: ProviderId, request: &mut ModelRequest) {
+        (self.rewrite)(request);
+    }
+}
+
+impl ProviderLayer for MapRequestLayer {
+    fn layer(&self, inner: Arc<dyn ModelProvider>) -> Arc<dyn ModelProvider> {
+        InterceptorLayer::new(Arc::new(self.clone())).layer(inner)
+    }
+}
+
+/// Replaces sensitive literals with `[REDACTED]` in requests and provider output.
+///
+/// Message content, tool results and tool definitions are scrubbed before the request is
+/// sent; request metadata is not. Streamed text is scrubbed per delta, so a literal split
+/// across two deltas is only caught in the final `ResponseComplete`.
+#[derive(Clone, Default)]
+pub struct RedactionLayer {
+    patterns: Vec<String>,
+}
+
+impl RedactionLayer {
+    pub fn new<I, S>(patterns: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            patterns: patterns
+                .into_iter()
+                .map(Into::into)
+                .filter(|pattern| !pattern.is_empty())
+                .collect(),
+        }
+    }
+
+    pub fn with_pattern(mut self, pattern: impl Into<String>) -> Self {
+        let pattern = pattern.into();
+        if !pattern.is_empty() {
+            self.patterns.push(pattern);
+        }
+        self
+    }
+
+    fn redact(&self, value: &mut String) {
+        for pattern in &self.patterns {
+            if value.contains(pattern.as_str()) {
+                *value = value.replace(pattern.as_str(), REDACTED);
+            }
+        }
+    }
+}
+
+impl fmt::Debug for RedactionLayer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RedactionLayer")
+            .field("patterns", &self.patterns.len())
+            .finish()
+    }
+}
+
+impl ProviderInterceptor for RedactionLayer {
+    fn on_request(&self, _provider: ProviderId, request: &mut ModelRequest) {
+        for message in &mut request.messages {
+            self.redact(&mut message.content);
+        }
+        for result in &mut request.tool_results {
+            self.redact(&mut result.output);
+        }
+        for tool in &mut request.tools {
+            self.redact(&mut tool.description);
+        }
+    }
+
+    fn on_response(&self, _provider: ProviderId, response: &mut ModelResponse) {
+        for item in &mut response.output {
+            match item {
+                OutputItem::Message(message) => self.redact(&mut message.content),
+                OutputItem::ToolCall(call) => self.redact(&mut call.arguments),
+            }
+        }
+    }
+
+    fn on_stream_event(&self, _provider: ProviderId, event: &mut StreamEvent) {
+        match event {
+            StreamEvent::TextDelta(text) => self.redact(text),
+            StreamEvent::MessageComplete(message) => self.redact(&mut message.content),
+            _ => {}
+        }
+    }
+}
+
+impl ProviderLayer for RedactionLayer {
+    fn layer(&self, inner: Arc<dyn ModelProvider>) -> Arc<dyn ModelProvider> {
+        InterceptorLayer::new(Arc::new(self.clone())).layer(inner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::future::Future;
+    use std::sync::Mutex;
+    use std::task::{RawWaker, RawWakerVTable, Waker};
+
+    use super::*;
+    use crate::{Message, Role, StopReason, TokenUsage, VecEventStream};
+
+    #[derive(Default)]
+    struct EchoProvider {
+        seen: Mutex<Vec<ModelRequest>>,
+    }
+
+    impl ModelProvider for EchoProvider {
+        fn id(&self) -> ProviderId {
+            ProviderId::Ollama
+        }
+
+        fn complete<'a>(
+            &'a self,
+            request: ModelRequest,
+        ) -> ProviderFuture<'a, Result<ModelResponse, ProviderError>> {
+            Box::pin(async move {
+                self.seen.lock().expect("seen lock").push(request.clone());
+                Ok(echo_response(&request))
+            })
+        }
+
+        fn stream<'a>(
+            &'a self,
+            request: ModelRequest,
+        ) -> ProviderFuture<'a, Result<BoxedEventStream<'a>, ProviderError>> {
+            Box::pin(async move {
+                self.seen.lock().expect("seen lock").push(request.clone());
+                let response = echo_response(&request);
+                Ok(Box::pin(VecEventStream::new(vec![
+                    Ok(StreamEvent::TextDelta("partial".to_string())),
+                    Ok(StreamEvent::ResponseComplete(response)),
+                    Err(ProviderError::transport("stream broke")),
+                ])) as BoxedEventStream<'a>)
+            })
+        }
+    }
+
+    fn echo_response(request: &ModelRequest) -> ModelResponse {
+        ModelResponse {
+            provider: ProviderId::Ollama,
+            model: request.model.clone(),
+            output: vec![OutputItem::Message(Message::new(
+                Role::Assistant,
+                request.messages[0].content.clone(),
+            ))],
+            stop_reason: StopReason::EndTurn,
+            usage: TokenUsage::default(),
+            response_id: None,
+        }
+    }
+
+    #[derive(Default)]
+    struct Recorder {
+        calls: Mutex<Vec<String>>,
+    }
+
+    struct Tagged(&'static str, Arc<Recorder>);
+
+    impl ProviderInterceptor for Tagged {
+        fn on_request(&self, _provider: ProviderId, _request: &mut ModelRequest) {
+            self.1
+                .calls
+                .lock()
+                .expect("calls lock")
+                .push(format!("{}:request", self.0));
+        }
+
+        fn on_stream_event(&self, _provider: ProviderId, event: &mut StreamEvent) {
+            if let StreamEvent::TextDelta(text) = event {
+                text.push_str(self.0);
+            }
+        }
+
+        fn on_error(&self, _provider: ProviderId, error: &ProviderError) {
+            self.1
+                .calls
+                .lock()
+                .expect("calls lock")
+                .push(format!("{}:error:{}", self.0, error.message));
+        }
+    }
+
+    #[test]
+    fn layers_apply_in_order_with_first_outermost() {
+        let recorder = Arc::new(Recorder::default());
+        let layers: Vec<Arc<dyn ProviderLayer>> = vec![
+            Arc::new(InterceptorLayer::new(Arc::new(Tagged(
+                "outer",
+                recorder.clone(),
+            )))),
+            Arc::new(InterceptorLayer::new(Arc::new(Tagged(
+                "inner",
+                recorder.clone(),
+            )))),
+        ];
+        let provider = apply_layers(Arc::new(EchoProvider::default()), &layers);
+        assert_eq!(provider.id(), ProviderId::Ollama);
+
+        let request = ModelRequest::new("llama3", vec![Message::new(Role::User, "hi")]);
+        let mut stream = block_on(provider.stream(request)).expect("stream should start");
+        let first = poll_once(&mut stream);
+        assert_eq!(
+            first,
+            Some(Ok(StreamEvent::TextDelta("partialinnerouter".to_string())))
+        );
+        let _ = poll_once(&mut stream);
+        let failure = poll_once(&mut stream);
+        assert!(matches!(failure, Some(Err(_))));
+
+        let calls = recorder.calls.lock().expect("calls lock");
+        assert_eq!(
+            *calls,
+            vec![
+                "outer:request",
+                "inner:request",
+                "inner:error:stream broke",
+                "outer:error:stream broke",
+            ]
+        );
+    }
+
+    #[test]
+    fn built_in_layers_rewrite_and_redact_requests() {
+        let echo = Arc::new(EchoProvider::default());
+        let layers: Vec<Arc<dyn ProviderLayer>> = vec![
+            Arc::new(MetadataLayer::new().with_entry("tenant", "acme")),
+            Arc::new(MapRequestLayer::new(|request| {
+                request.model = "llama3:8b".to_string();
+            })),
+            Arc::new(RedactionLayer::new(["hunter2"])),
+        ];
+        let provider = apply_layers(echo.clone(), &layers);
+
+        let request = ModelRequest::new(
+            "llama3",
+            vec![Message::new(Role::User, "my password is hunter2")],
+        );
+        let response = block_on(provider.complete(request)).expect("completion should work");
+        assert_eq!(response.model, "llama3:8b");
+
+        let seen = echo.seen.lock().expect("seen lock");
+        assert_eq!(seen[0].messages[0].content, "my password is [REDACTED]");
+        assert_eq!(seen[0].metadata.get("tenant"), Some(&"acme".to_string()));
+    }
+
+    fn poll_once(stream: &mut BoxedEventStream<'_>) -> Option<Result<StreamEvent, ProviderError>> {
+        let waker = noop_waker();
+        let mut cx = Context::from_waker(&waker);
+        match stream.as_mut().poll_next(&mut cx) {
+            Poll::Ready(item) => item,
+            Poll::Pending => panic!("in-memory stream should be ready"),
+        }
+    }
+
+    fn block_on<F: Future>(future: F) -> F::Output {
+        let mut future = std::pin::pin!(future);
+        let waker = noop_waker();
+        let mut cx = Context::from_waker(&waker);
+
+        loop {
+            match future.as_mut().poll(&mut cx) {
+                Poll::Ready(value) => return value,
+                Poll::Pending => std::thread::yield_now(),
+            }
+        }
+    }
+
+    fn noop_waker() -> Waker {
+        unsafe fn clone(_: *const ()) -> RawWaker {
+            RawWaker::new(std::ptr::null(), &VTABLE)
+        }
+
+        unsafe fn wake(_: *const ()) {}
+
+        unsafe fn wake_by_ref(_: *const ()) {}
+
+        unsafe fn drop(_: *const ()) {}
+
+        static VTABLE: RawWakerVTable = RawWakerVTable::new(clone, wake, wake_by_ref, drop);
+
+        let raw_waker = RawWaker::new(std::ptr::null(), &VTABLE);
+        unsafe { Waker::from_raw(raw_waker) }
+    }
+}
@@ -13,29 +13,57 @@
 //! ```
 
 pub mod adapters;
+mod cache;
+mod credential_source;
 mod credentials;
 mod error;
+mod health;
+mod key_pool;
+mod layer;
 mod model;
 pub mod prelude;
 mod provider;
 mod registry;
 mod resilience;
 mod stream;
-
+mod stream_timeout;
+#[cfg(feature = "credential-vault")]
+mod vault;
+
+#[cfg(feature = "response-cache-sqlite")]
+pub use cache::SqliteResponseCache;
+pub use cache::{
+    CacheKey, CacheLayer, CachedResponse, CachingProvider, InMemoryResponseCache, ResponseCache,
+};
+pub use credential_source::{
+    CommandCredentialSource, CredentialSource, EnvCredentialSource, FileCredentialSource,
+    SourcedCredential,
+};
 pub use credentials::{
     BrowserLoginSession, CredentialAccessAction, CredentialAccessEvent, CredentialAccessObserver,
     CredentialKind, CredentialMetadata, ProviderCredential, SecretString, SecureCredentialManager,
 };
 pub use error::{ProviderError, ProviderErrorKind};
 pub use fcommon::{BoxFuture, MetadataMap};
+pub use health::{HealthProbeLayer, HealthStatus, ProviderHealth};
+pub use key_pool::{ApiKeyPool, KeySelectionStrategy, PooledKeyStatus};
+pub use layer::{
+    InterceptorLayer, MapRequestLayer, MetadataLayer, ProviderInterceptor, ProviderLayer,
+    RedactionLayer, apply_layers,
+};
 pub use model::{
-    Message, ModelRequest, ModelRequestBuilder, ModelResponse, OutputItem, ProviderId, Role,
-    StopReason, TokenUsage, ToolCall, ToolDefinition, ToolResult,
+    Message, ModelInfo, ModelRequest, ModelRequestBuilder, ModelResponse, OutputItem, ProviderId,
+    ResponseContinuation, Role, StopReason, TokenUsage, ToolCall, ToolDefinition, ToolResult,
 };
 pub use provider::{ModelProvider, ProviderFuture};
 pub use registry::ProviderRegistry;
 pub use resilience::{NoopOperationHooks, ProviderOperationHooks, RetryPolicy, execute_with_retry};
-pub use stream::{BoxedEventStream, ModelEventStream, StreamEvent, VecEventStream};
+pub use stream::{
+    BoxedEventStream, ModelEventStream, StreamAccumulator, StreamEvent, VecEventStream,
+};
+pub use stream_timeout::{StreamTimeoutLayer, StreamTimeouts};
+#[cfg(feature = "credential-vault")]
+pub use vault::{CredentialVault, VaultKey};
 
 #[cfg(test)]
 mod tests {
@@ -72,7 +100,9 @@ mod tests {
                         input_tokens: 5,
                         output_tokens: 4,
                         total_tokens: 9,
+                        cached_input_tokens: 0,
                     },
+                    response_id: None,
                 })
             })
         }
@@ -99,6 +129,7 @@ mod tests {
         assert_eq!(ProviderId::OpenAi.to_string(), "openai");
         assert_eq!(ProviderId::Anthropic.to_string(), "anthropic");
         assert_eq!(ProviderId::Ollama.to_string(), "ollama");
+        assert_eq!(ProviderId::Gemini.to_string(), "gemini");
     }
 
     #[test]
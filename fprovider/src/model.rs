@@ -27,6 +27,7 @@ pub enum ProviderId {
     OpenAi,
     Anthropic,
     Ollama,
+    Gemini,
 }
 
 impl Display for ProviderId {
@@ -36,6 +37,7 @@ impl Display for ProviderId {
             Self::OpenAi => "openai",
             Self::Anthropic => "anthropic",
             Self::Ollama => "ollama",
+            Self::Gemini => "gemini",
         };
 
         f.write_str(id)
@@ -105,6 +107,20 @@ pub struct TokenUsage {
     pub input_tokens: u32,
     pub output_tokens: u32,
     pub total_tokens: u32,
+    /// Portion of `input_tokens` served from the provider's prompt cache.
+    pub cached_input_tokens: u32,
+}
+
+impl TokenUsage {
+    /// Adds `other` field by field, saturating at `u32::MAX`.
+    pub fn accumulate(&mut self, other: TokenUsage) {
+        self.input_tokens = self.input_tokens.saturating_add(other.input_tokens);
+        self.output_tokens = self.output_tokens.saturating_add(other.output_tokens);
+        self.total_tokens = self.total_tokens.saturating_add(other.total_tokens);
+        self.cached_input_tokens = self
+            .cached_input_tokens
+            .saturating_add(other.cached_input_tokens);
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -114,6 +130,76 @@ pub struct ModelResponse {
     pub output: Vec<OutputItem>,
     pub stop_reason: StopReason,
     pub usage: TokenUsage,
+    /// Provider-side response id, when the provider can continue from it later.
+    pub response_id: Option<String>,
+}
+
+/// A model reported by a provider's model listing. Fields the provider does not expose are
+/// left as `None`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModelInfo {
+    pub provider: ProviderId,
+    pub id: String,
+    pub display_name: Option<String>,
+    pub owner: Option<String>,
+    /// Creation time as Unix seconds.
+    pub created: Option<u64>,
+    /// Maximum input tokens accepted by the model.
+    pub context_window: Option<u32>,
+}
+
+impl ModelInfo {
+    pub fn new(provider: ProviderId, id: impl Into<String>) -> Self {
+        Self {
+            provider,
+            id: id.into(),
+            display_name: None,
+            owner: None,
+            created: None,
+            context_window: None,
+        }
+    }
+
+    pub fn with_display_name(mut self, display_name: impl Into<String>) -> Self {
+        self.display_name = Some(display_name.into());
+        self
+    }
+
+    pub fn with_owner(mut self, owner: impl Into<String>) -> Self {
+        self.owner = Some(owner.into());
+        self
+    }
+
+    pub fn with_created(mut self, created: u64) -> Self {
+        self.created = Some(created);
+        self
+    }
+
+    pub fn with_context_window(mut self, context_window: u32) -> Self {
+        self.context_window = Some(context_window);
+        self
+    }
+}
+
+/// Points a request at a previously stored provider response.
+///
+/// The referenced response already holds the first `message_count` request messages
+/// (including its own assistant output), so providers that support stateful
+/// continuation only need to send the messages after that prefix. Providers that
+/// do not support it ignore the continuation and send the full history.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResponseContinuation {
+    pub response_id: String,
+    pub message_count: usize,
+}
+
+impl ResponseContinuation {
+    pub fn new(response_id: impl Into<String>, message_count: usize) -> Self {
+        Self {
+            response_id: response_id.into(),
+            message_count,
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -124,6 +210,7 @@ pub struct ModelRequest {
     pub tools: Vec<ToolDefinition>,
     pub tool_results: Vec<ToolResult>,
     pub metadata: MetadataMap,
+    pub continuation: Option<ResponseContinuation>,
 }
 
 impl ModelRequest {
@@ -139,6 +226,7 @@ impl ModelRequest {
             tools: Vec::new(),
             tool_results: Vec::new(),
             metadata: MetadataMap::new(),
+            continuation: None,
         }
     }
 
@@ -176,6 +264,11 @@ impl ModelRequest {
         self
     }
 
+    pub fn with_continuation(mut self, continuation: ResponseContinuation) -> Self {
+        self.continuation = Some(continuation);
+        self
+    }
+
     pub fn enable_streaming(mut self) -> Self {
         self.options.stream = true;
         self
@@ -222,6 +315,7 @@ pub struct ModelRequestBuilder {
     tools: Vec<ToolDefinition>,
     tool_results: Vec<ToolResult>,
     metadata: MetadataMap,
+    continuation: Option<ResponseContinuation>,
 }
 
 impl ModelRequestBuilder {
@@ -233,6 +327,7 @@ impl ModelRequestBuilder {
             tools: Vec::new(),
             tool_results: Vec::new(),
             metadata: MetadataMap::new(),
+            continuation: None,
         }
     }
 
@@ -271,6 +366,11 @@ impl ModelRequestBuilder {
         self
     }
 
+    pub fn continuation(mut self, continuation: ResponseContinuation) -> Self {
+        self.continuation = Some(continuation);
+        self
+    }
+
     pub fn streaming(mut self, stream: bool) -> Self {
         self.options.stream = stream;
         self
@@ -288,6 +388,7 @@ impl ModelRequestBuilder {
             tools: self.tools,
             tool_results: self.tool_results,
             metadata: self.metadata,
+            continuation: self.continuation,
         };
 
         request.validate()?;
@@ -1,9 +1,10 @@
 //! Common `fprovider` imports for downstream crates.
 
 pub use crate::{
-    BoxedEventStream, Message, ModelEventStream, ModelProvider, ModelRequest, ModelRequestBuilder,
-    ModelResponse, NoopOperationHooks, OutputItem, ProviderError, ProviderErrorKind, ProviderId,
-    ProviderOperationHooks, ProviderRegistry, RetryPolicy, Role, StopReason, StreamEvent,
-    TokenUsage, ToolCall, ToolDefinition, ToolResult, execute_with_retry,
+    BoxedEventStream, Message, ModelEventStream, ModelInfo, ModelProvider, ModelRequest,
+    ModelRequestBuilder, ModelResponse, NoopOperationHooks, OutputItem, ProviderError,
+    ProviderErrorKind, ProviderId, ProviderOperationHooks, ProviderRegistry, RetryPolicy, Role,
+    StopReason, StreamAccumulator, StreamEvent, TokenUsage, ToolCall, ToolDefinition, ToolResult,
+    execute_with_retry,
 };
 pub use fcommon::{BoxFuture, MetadataMap};
@@ -2,7 +2,10 @@
 
 use fcommon::BoxFuture;
 
-use crate::{BoxedEventStream, ModelRequest, ModelResponse, ProviderError, ProviderId};
+use crate::{
+    BoxedEventStream, ModelInfo, ModelRequest, ModelResponse, ProviderError, ProviderHealth,
+    ProviderId,
+};
 
 pub type ProviderFuture<'a, T> = BoxFuture<'a, T>;
 
@@ -18,4 +21,20 @@ pub trait ModelProvider: Send + Sync {
         &'a self,
         request: ModelRequest,
     ) -> ProviderFuture<'a, Result<BoxedEventStream<'a>, ProviderError>>;
+
+    /// Runs a lightweight readiness probe. Wrappers should forward to the inner provider.
+    fn health<'a>(&'a self) -> ProviderFuture<'a, ProviderHealth> {
+        Box::pin(async move { ProviderHealth::unknown(self.id(), "no health probe configured") })
+    }
+
+    /// Lists the models available to the configured credentials. Wrappers should forward to
+    /// the inner provider.
+    fn list_models<'a>(&'a self) -> ProviderFuture<'a, Result<Vec<ModelInfo>, ProviderError>> {
+        Box::pin(async move {
+            Err(ProviderError::invalid_request(format!(
+                "provider '{}' does not support model listing",
+                self.id()
+            )))
+        })
+    }
 }
@@ -8,15 +8,30 @@
 //! assert_eq!(registry.len(), 0);
 //! ```
 
-use std::sync::Arc;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
 use fcommon::Registry;
 
-use crate::{ModelProvider, ProviderId};
+use crate::{ModelProvider, ProviderHealth, ProviderId};
+
+const DEFAULT_HEALTH_TTL: Duration = Duration::from_secs(30);
 
-#[derive(Default)]
 pub struct ProviderRegistry {
     providers: Registry<ProviderId, Arc<dyn ModelProvider>>,
+    health_ttl: Duration,
+    health_cache: Mutex<HashMap<ProviderId, ProviderHealth>>,
+}
+
+impl Default for ProviderRegistry {
+    fn default() -> Self {
+        Self {
+            providers: Registry::default(),
+            health_ttl: DEFAULT_HEALTH_TTL,
+            health_cache: Mutex::new(HashMap::new()),
+        }
+    }
 }
 
 impl ProviderRegistry {
@@ -24,11 +39,24 @@ impl ProviderRegistry {
         Self::default()
     }
 
+    /// How long a probe result is reused before the provider is probed again.
+    pub fn with_health_ttl(mut self, health_ttl: Duration) -> Self {
+        self.health_ttl = health_ttl;
+        self
+    }
+
     pub fn register<P>(&mut self, provider: P)
     where
         P: ModelProvider + 'static,
     {
-        self.providers.insert(provider.id(), Arc::new(provider));
+        self.register_arc(Arc::new(provider));
+    }
+
+    /// Registers an already shared provider, e.g. one wrapped in layers.
+    pub fn register_arc(&mut self, provider: Arc<dyn ModelProvider>) {
+        let provider_id = provider.id();
+        self.invalidate_health(provider_id);
+        self.providers.insert(provider_id, provider);
     }
 
     pub fn get(&self, provider_id: ProviderId) -> Option<Arc<dyn ModelProvider>> {
@@ -36,6 +64,7 @@ impl ProviderRegistry {
     }
 
     pub fn remove(&mut self, provider_id: ProviderId) -> Option<Arc<dyn ModelProvider>> {
+        self.invalidate_health(provider_id);
         self.providers.remove(&provider_id)
     }
 
@@ -50,4 +79,56 @@ impl ProviderRegistry {
     pub fn is_empty(&self) -> bool {
         self.providers.is_empty()
     }
+
+    /// Returns the provider's health, probing it only when the cached result is older than the TTL.
+    pub async fn health(&self, provider_id: ProviderId) -> Option<ProviderHealth> {
+        let provider = self.get(provider_id)?;
+        if let Some(cached) = self.cached_health(provider_id) {
+            return Some(cached);
+        }
+
+        let health = provider.health().await;
+        if let Ok(mut cache) = self.health_cache.lock() {
+            cache.insert(provider_id, health.clone());
+        }
+        Some(health)
+    }
+
+    /// Health of every registered provider, probed one after another and ordered by provider id.
+    pub async fn health_all(&self) -> Vec<ProviderHealth> {
+        let mut provider_ids = self
+            .providers
+            .values()
+            .map(|provider| provider.id())
+            .collect::<Vec<_>>();
+        provider_ids.sort_by_key(|provider_id| provider_id.to_string());
+
+        let mut report = Vec::with_capacity(provider_ids.len());
+        for provider_id in provider_ids {
+            if let Some(health) = self.health(provider_id).await {
+                report.push(health);
+            }
+        }
+        report
+    }
+
+    /// `true` when no registered provider reported itself unhealthy.
+    pub async fn is_ready(&self) -> bool {
+        self.health_all().await.iter().all(ProviderHealth::is_ready)
+    }
+
+    /// Drops the cached probe result so the next health check probes again.
+    pub fn invalidate_health(&self, provider_id: ProviderId) {
+        if let Ok(mut cache) = self.health_cache.lock() {
+            cache.remove(&provider_id);
+        }
+    }
+
+    fn cached_health(&self, provider_id: ProviderId) -> Option<ProviderHealth> {
+        let cache = self.health_cache.lock().ok()?;
+        cache
+            .get(&provider_id)
+            .filter(|health| health.is_fresh(self.health_ttl))
+            .cloned()
+    }
 }
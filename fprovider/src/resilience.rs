@@ -79,6 +79,10 @@ pub trait ProviderOperationHooks: Send + Sync {
         _error: &ProviderError,
     ) {
     }
+
+    fn on_cache_hit(&self, _provider: ProviderId, _operation: &str) {}
+
+    fn on_cache_miss(&self, _provider: ProviderId, _operation: &str) {}
 }
 
 #[derive(Debug, Default, Clone, Copy)]
@@ -6,6 +6,36 @@
 //! let stream = VecEventStream::new(vec![Ok(StreamEvent::TextDelta("hello".into()))]);
 //! let _boxed: BoxedEventStream<'static> = Box::pin(stream);
 //! ```
+//!
+//! [`StreamAccumulator`] folds events back into a [`ModelResponse`]:
+//!
+//! ```rust
+//! use fprovider::{ProviderId, StreamAccumulator, StreamEvent, ToolCall};
+//!
+//! let mut accumulator = StreamAccumulator::new(ProviderId::OpenAi, "gpt-4o-mini");
+//! for event in [
+//!     StreamEvent::TextDelta("Checking ".into()),
+//!     StreamEvent::TextDelta("the weather".into()),
+//!     StreamEvent::ToolCallDelta(ToolCall {
+//!         id: "call_1".into(),
+//!         name: "weather".into(),
+//!         arguments: "{\"city\":".into(),
+//!     }),
+//!     StreamEvent::ToolCallDelta(ToolCall {
+//!         id: String::new(),
+//!         name: String::new(),
+//!         arguments: "\"Oslo\"}".into(),
+//!     }),
+//! ] {
+//!     accumulator.push(&event).expect("events are in order");
+//! }
+//!
+//! assert_eq!(accumulator.text(), "Checking the weather");
+//! assert_eq!(accumulator.tool_calls()[0].arguments, "{\"city\":\"Oslo\"}");
+//!
+//! let response = accumulator.finish();
+//! assert_eq!(response.output.len(), 2);
+//! ```
 
 use std::collections::VecDeque;
 use std::pin::Pin;
@@ -13,7 +43,10 @@ use std::task::{Context, Poll};
 
 use futures_core::Stream;
 
-use crate::{Message, ModelResponse, ProviderError, ToolCall};
+use crate::{
+    Message, ModelResponse, OutputItem, ProviderError, ProviderId, Role, StopReason, TokenUsage,
+    ToolCall,
+};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum StreamEvent {
@@ -21,6 +54,8 @@ pub enum StreamEvent {
     ToolCallDelta(ToolCall),
     MessageComplete(Message),
     ResponseComplete(ModelResponse),
+    /// Interim token usage; the final figures arrive in `ResponseComplete`.
+    Usage(TokenUsage),
 }
 
 /// Provider stream contract.
@@ -28,6 +63,8 @@ pub enum StreamEvent {
 /// Invariants for consumers:
 /// - Events are emitted in source order.
 /// - `TextDelta` and `ToolCallDelta` may appear zero or more times.
+/// - `Usage` may appear zero or more times before `ResponseComplete`; each report
+///   supersedes the previous one.
 /// - `MessageComplete` and `ResponseComplete` are terminal milestones and, when present,
 ///   arrive after all related deltas.
 /// - Once the stream yields `None`, it must not yield additional items.
@@ -60,3 +97,308 @@ impl Stream for VecEventStream {
         Poll::Ready(self.events.pop_front())
     }
 }
+
+/// Folds a provider event stream into a single [`ModelResponse`].
+///
+/// Text deltas are concatenated. Tool call deltas are merged by id: a delta whose arguments
+/// extend the stored arguments is treated as a cumulative snapshot and replaces them, any
+/// other delta is appended as a fragment. A delta with an empty id continues the most recent
+/// call. When the stream carries a `ResponseComplete`, its stop reason, usage and response id
+/// are used, and its output takes precedence over the accumulated deltas wherever it is not
+/// empty. The latest `Usage` event stands in for usage the final response leaves at zero.
+///
+/// [`StreamAccumulator::push`] rejects events that break the ordering invariants documented
+/// on [`ModelEventStream`].
+#[derive(Debug, Clone)]
+pub struct StreamAccumulator {
+    provider: ProviderId,
+    model: String,
+    text: String,
+    tool_calls: Vec<ToolCall>,
+    message: Option<Message>,
+    usage: Option<TokenUsage>,
+    response: Option<ModelResponse>,
+}
+
+impl StreamAccumulator {
+    /// `provider` and `model` are used until the stream reports its own in `ResponseComplete`.
+    pub fn new(provider: ProviderId, model: impl Into<String>) -> Self {
+        Self {
+            provider,
+            model: model.into(),
+            text: String::new(),
+            tool_calls: Vec::new(),
+            message: None,
+            usage: None,
+            response: None,
+        }
+    }
+
+    /// Drains `stream` and returns the folded response, or the first stream error.
+    pub async fn collect(
+        provider: ProviderId,
+        model: impl Into<String>,
+        mut stream: BoxedEventStream<'_>,
+    ) -> Result<ModelResponse, ProviderError> {
+        let mut accumulator = Self::new(provider, model);
+        while let Some(event) = std::future::poll_fn(|cx| stream.as_mut().poll_next(cx)).await {
+            accumulator.push(&event?)?;
+        }
+
+        Ok(accumulator.finish())
+    }
+
+    pub fn push(&mut self, event: &StreamEvent) -> Result<(), ProviderError> {
+        if self.response.is_some() {
+            return Err(ordering_error("event received after ResponseComplete"));
+        }
+
+        match event {
+            StreamEvent::TextDelta(delta) => {
+                self.ensure_message_open("TextDelta")?;
+                self.text.push_str(delta);
+            }
+            StreamEvent::ToolCallDelta(delta) => {
+                self.ensure_message_open("ToolCallDelta")?;
+                self.merge_tool_call(delta);
+            }
+            StreamEvent::MessageComplete(message) => {
+                self.message = Some(message.clone());
+            }
+            StreamEvent::ResponseComplete(response) => {
+                self.response = Some(response.clone());
+            }
+            StreamEvent::Usage(usage) => {
+                self.usage = Some(*usage);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Assistant text received so far.
+    pub fn text(&self) -> &str {
+        match &self.message {
+            Some(message) if self.text.is_empty() => &message.content,
+            _ => &self.text,
+        }
+    }
+
+    /// Tool calls received so far, in first-seen order.
+    pub fn tool_calls(&self) -> &[ToolCall] {
+        &self.tool_calls
+    }
+
+    /// Whether a `ResponseComplete` event has been received.
+    pub fn is_complete(&self) -> bool {
+        self.response.is_some()
+    }
+
+    /// Builds a response from everything received so far without consuming the accumulator.
+    pub fn snapshot(&self) -> ModelResponse {
+        self.clone().finish()
+    }
+
+    pub fn finish(self) -> ModelResponse {
+        let text = match self.message {
+            Some(message) if self.text.is_empty() => message.content,
+            _ => self.text,
+        };
+
+        let Some(mut response) = self.response else {
+            return ModelResponse {
+                provider: self.provider,
+                stop_reason: if self.tool_calls.is_empty() {
+                    StopReason::Other
+                } else {
+                    StopReason::ToolUse
+                },
+                output: build_output(text, self.tool_calls),
+                model: self.model,
+                usage: self.usage.unwrap_or_default(),
+                response_id: None,
+            };
+        };
+
+        let has_text = response.output.iter().any(|item| {
+            matches!(item, OutputItem::Message(message) if message.role == Role::Assistant && !message.content.is_empty())
+        });
+        let has_tool_calls = response
+            .output
+            .iter()
+            .any(|item| matches!(item, OutputItem::ToolCall(_)));
+
+        if !has_text && !text.is_empty() {
+            response.output.retain(|item| !matches!(item, OutputItem::Message(message) if message.role == Role::Assistant));
+            response
+                .output
+                .insert(0, OutputItem::Message(Message::new(Role::Assistant, text)));
+        }
+        if !has_tool_calls {
+            response
+                .output
+                .extend(self.tool_calls.into_iter().map(OutputItem::ToolCall));
+        }
+        if response.usage == TokenUsage::default()
+            && let Some(usage) = self.usage
+        {
+            response.usage = usage;
+        }
+
+        response
+    }
+
+    fn ensure_message_open(&self, event: &str) -> Result<(), ProviderError> {
+        if self.message.is_some() {
+            return Err(ordering_error(&format!(
+                "{event} received after MessageComplete"
+            )));
+        }
+
+        Ok(())
+    }
+
+    fn merge_tool_call(&mut self, delta: &ToolCall) {
+        let existing = if delta.id.is_empty() {
+            self.tool_calls.last_mut()
+        } else {
+            self.tool_calls.iter_mut().find(|call| call.id == delta.id)
+        };
+
+        let Some(call) = existing else {
+            self.tool_calls.push(delta.clone());
+            return;
+        };
+
+        if !delta.name.is_empty() {
+            call.name = delta.name.clone();
+        }
+        if delta.arguments.starts_with(call.arguments.as_str()) {
+            call.arguments = delta.arguments.clone();
+        } else {
+            call.arguments.push_str(&delta.arguments);
+        }
+    }
+}
+
+fn build_output(text: String, tool_calls: Vec<ToolCall>) -> Vec<OutputItem> {
+    let mut output = Vec::with_capacity(tool_calls.len() + 1);
+    if !text.is_empty() {
+        output.push(OutputItem::Message(Message::new(Role::Assistant, text)));
+    }
+    output.extend(tool_calls.into_iter().map(OutputItem::ToolCall));
+    output
+}
+
+fn ordering_error(detail: &str) -> ProviderError {
+    ProviderError::other(format!("stream ordering violation: {detail}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn call(id: &str, name: &str, arguments: &str) -> ToolCall {
+        ToolCall {
+            id: id.to_string(),
+            name: name.to_string(),
+            arguments: arguments.to_string(),
+        }
+    }
+
+    #[test]
+    fn accumulator_merges_fragment_and_snapshot_tool_call_deltas() {
+        let mut accumulator = StreamAccumulator::new(ProviderId::OpenAi, "gpt-4o-mini");
+        for event in [
+            StreamEvent::ToolCallDelta(call("call_a", "lookup", "{\"q\":")),
+            StreamEvent::ToolCallDelta(call("", "", "1}")),
+            StreamEvent::ToolCallDelta(call("call_b", "search", "{\"t")),
+            StreamEvent::ToolCallDelta(call("call_b", "", "{\"term\":\"x\"}")),
+            StreamEvent::Usage(TokenUsage {
+                input_tokens: 7,
+                output_tokens: 3,
+                total_tokens: 10,
+                cached_input_tokens: 4,
+            }),
+        ] {
+            accumulator.push(&event).expect("ordered events");
+        }
+
+        assert_eq!(
+            accumulator.tool_calls(),
+            &[
+                call("call_a", "lookup", "{\"q\":1}"),
+                call("call_b", "search", "{\"term\":\"x\"}"),
+            ]
+        );
+
+        let snapshot = accumulator.snapshot();
+        assert_eq!(snapshot.stop_reason, StopReason::ToolUse);
+        assert_eq!(snapshot.usage.cached_input_tokens, 4);
+        assert_eq!(snapshot.output.len(), 2);
+        assert!(!accumulator.is_complete());
+    }
+
+    #[test]
+    fn accumulator_prefers_response_complete_metadata_and_fills_missing_output() {
+        let mut accumulator = StreamAccumulator::new(ProviderId::OpenAi, "fallback");
+        let final_response = ModelResponse {
+            provider: ProviderId::OpenAi,
+            model: "gpt-4o-mini-2024".to_string(),
+            output: vec![OutputItem::Message(Message::new(Role::Assistant, ""))],
+            stop_reason: StopReason::EndTurn,
+            usage: TokenUsage {
+                input_tokens: 4,
+                output_tokens: 2,
+                total_tokens: 6,
+                cached_input_tokens: 0,
+            },
+            response_id: Some("resp_1".to_string()),
+        };
+        for event in [
+            StreamEvent::TextDelta("hel".to_string()),
+            StreamEvent::TextDelta("lo".to_string()),
+            StreamEvent::MessageComplete(Message::new(Role::Assistant, "hello")),
+            StreamEvent::ResponseComplete(final_response),
+        ] {
+            accumulator.push(&event).expect("ordered events");
+        }
+
+        assert!(accumulator.is_complete());
+        let response = accumulator.finish();
+        assert_eq!(response.model, "gpt-4o-mini-2024");
+        assert_eq!(response.usage.total_tokens, 6);
+        assert_eq!(response.response_id.as_deref(), Some("resp_1"));
+        assert_eq!(
+            response.output,
+            vec![OutputItem::Message(Message::new(Role::Assistant, "hello"))]
+        );
+    }
+
+    #[test]
+    fn accumulator_rejects_out_of_order_events() {
+        let mut accumulator = StreamAccumulator::new(ProviderId::OpenAi, "gpt-4o-mini");
+        accumulator
+            .push(&StreamEvent::MessageComplete(Message::new(
+                Role::Assistant,
+                "done",
+            )))
+            .expect("message complete");
+        let err = accumulator
+            .push(&StreamEvent::TextDelta("late".to_string()))
+            .expect_err("delta after message complete");
+        assert!(err.message.contains("after MessageComplete"));
+
+        let mut accumulator = StreamAccumulator::new(ProviderId::OpenAi, "gpt-4o-mini");
+        accumulator
+            .push(&StreamEvent::ResponseComplete(
+                StreamAccumulator::new(ProviderId::OpenAi, "gpt-4o-mini").finish(),
+            ))
+            .expect("response complete");
+        assert!(
+            accumulator
+                .push(&StreamEvent::TextDelta("late".to_string()))
+                .is_err()
+        );
+    }
+}
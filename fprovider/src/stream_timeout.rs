@@ -0,0 +1,384 @@
+//! First-token and inter-chunk idle deadlines for provider event streams.
+//!
+//! HTTP client timeouts bound the whole request, which either cuts off long streams or never
+//! fires on a connection that stalls mid-response. [`StreamTimeouts`] instead bounds the wait
+//! for the first event and the gap between consecutive events, failing the stream with a
+//! retryable [`ProviderError::timeout`] that names the phase and elapsed time.
+//!
+//! ```rust
+//! use std::sync::Arc;
+//! use std::time::Duration;
+//!
+//! use fprovider::{ModelProvider, ProviderLayer, StreamTimeoutLayer, StreamTimeouts};
+//!
+//! fn wrap(provider: Arc<dyn ModelProvider>) -> Arc<dyn ModelProvider> {
+//!     let timeouts = StreamTimeouts::new()
+//!         .with_first_token(Duration::from_secs(20))
+//!         .with_idle(Duration::from_secs(10));
+//!     StreamTimeoutLayer::new(timeouts).layer(provider)
+//! }
+//! ```
+
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+use futures_core::Stream;
+use futures_timer::Delay;
+
+use crate::{
+    BoxedEventStream, ModelInfo, ModelProvider, ModelRequest, ModelResponse, ProviderError,
+    ProviderFuture, ProviderHealth, ProviderId, ProviderLayer, StreamEvent,
+};
+
+/// Deadlines enforced while reading a provider stream. `None` disables a deadline.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct StreamTimeouts {
+    /// Maximum wait for the first event, measured from when the stream was requested.
+    pub first_token: Option<Duration>,
+    /// Maximum gap between two consecutive events once the first one arrived.
+    pub idle: Option<Duration>,
+}
+
+impl StreamTimeouts {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_first_token(mut self, first_token: Duration) -> Self {
+        self.first_token = Some(first_token);
+        self
+    }
+
+    pub fn with_idle(mut self, idle: Duration) -> Self {
+        self.idle = Some(idle);
+        self
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.first_token.is_some() || self.idle.is_some()
+    }
+
+    /// Applies the deadlines to `stream`, starting the first-token clock now.
+    pub fn wrap<'a>(&self, stream: BoxedEventStream<'a>) -> BoxedEventStream<'a> {
+        self.wrap_since(stream, Instant::now())
+    }
+
+    /// Applies the deadlines to `stream`, with the first-token clock started at `started`.
+    pub fn wrap_since<'a>(
+        &self,
+        stream: BoxedEventStream<'a>,
+        started: Instant,
+    ) -> BoxedEventStream<'a> {
+        if !self.is_enabled() {
+            return stream;
+        }
+
+        Box::pin(TimeoutEventStream::new(stream, *self, started))
+    }
+
+    /// Awaits `open` and applies the deadlines to the stream it returns. The first-token deadline,
+    /// measured from `started`, also bounds the wait for the provider to open the stream at all.
+    pub async fn open_since<'a, F>(
+        &self,
+        open: F,
+        started: Instant,
+    ) -> Result<BoxedEventStream<'a>, ProviderError>
+    where
+        F: Future<Output = Result<BoxedEventStream<'a>, ProviderError>>,
+    {
+        let Some(first_token) = self.first_token else {
+            return Ok(self.wrap_since(open.await?, started));
+        };
+
+        let mut open = std::pin::pin!(open);
+        let mut deadline = Delay::new(first_token.saturating_sub(started.elapsed()));
+        let stream = std::future::poll_fn(|cx| {
+            if let Poll::Ready(opened) = open.as_mut().poll(cx) {
+                return Poll::Ready(opened);
+            }
+            match Pin::new(&mut deadline).poll(cx) {
+                Poll::Ready(()) => Poll::Ready(Err(ProviderError::timeout(format!(
+                    "stream timed out in {} phase after {:?}",
+                    StreamPhase::FirstToken,
+                    started.elapsed()
+                )))),
+                Poll::Pending => Poll::Pending,
+            }
+        })
+        .await?;
+        Ok(self.wrap_since(stream, started))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum StreamPhase {
+    FirstToken,
+    Idle,
+}
+
+impl fmt::Display for StreamPhase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::FirstToken => f.write_str("first token"),
+            Self::Idle => f.write_str("idle"),
+        }
+    }
+}
+
+struct TimeoutEventStream<'a> {
+    inner: BoxedEventStream<'a>,
+    timeouts: StreamTimeouts,
+    phase: StreamPhase,
+    phase_started: Instant,
+    deadline: Option<Delay>,
+    finished: bool,
+}
+
+impl<'a> TimeoutEventStream<'a> {
+    fn new(inner: BoxedEventStream<'a>, timeouts: StreamTimeouts, started: Instant) -> Self {
+        let deadline = timeouts
+            .first_token
+            .map(|limit| Delay::new(limit.saturating_sub(started.elapsed())));
+        Self {
+            inner,
+            timeouts,
+            phase: StreamPhase::FirstToken,
+            phase_started: started,
+            deadline,
+            finished: false,
+        }
+    }
+}
+
+impl Stream for TimeoutEventStream<'_> {
+    type Item = Result<StreamEvent, ProviderError>;
+
+    fn poll_next(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<StreamEvent, ProviderError>>> {
+        if self.finished {
+            return Poll::Ready(None);
+        }
+
+        if let Poll::Ready(item) = self.inner.as_mut().poll_next(cx) {
+            match item {
+                Some(item) => {
+                    self.phase = StreamPhase::Idle;
+                    self.phase_started = Instant::now();
+                    self.deadline = self.timeouts.idle.map(Delay::new);
+                    return Poll::Ready(Some(item));
+                }
+                None => {
+                    self.finished = true;
+                    self.deadline = None;
+                    return Poll::Ready(None);
+                }
+            }
+        }
+
+        let Some(deadline) = self.deadline.as_mut() else {
+            return Poll::Pending;
+        };
+        match Pin::new(deadline).poll(cx) {
+            Poll::Ready(()) => {
+                self.finished = true;
+                self.deadline = None;
+                Poll::Ready(Some(Err(ProviderError::timeout(format!(
+                    "stream timed out in {} phase after {:?}",
+                    self.phase,
+                    self.phase_started.elapsed()
+                )))))
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+/// Applies [`StreamTimeouts`] to every stream opened through the wrapped provider.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StreamTimeoutLayer {
+    timeouts: StreamTimeouts,
+}
+
+impl StreamTimeoutLayer {
+    pub fn new(timeouts: StreamTimeouts) -> Self {
+        Self { timeouts }
+    }
+}
+
+impl ProviderLayer for StreamTimeoutLayer {
+    fn layer(&self, inner: Arc<dyn ModelProvider>) -> Arc<dyn ModelProvider> {
+        Arc::new(StreamTimeoutProvider {
+            inner,
+            timeouts: self.timeouts,
+        })
+    }
+}
+
+struct StreamTimeoutProvider {
+    inner: Arc<dyn ModelProvider>,
+    timeouts: StreamTimeouts,
+}
+
+impl ModelProvider for StreamTimeoutProvider {
+    fn id(&self) -> ProviderId {
+        self.inner.id()
+    }
+
+    fn complete<'a>(
+        &'a self,
+        request: ModelRequest,
+    ) -> ProviderFuture<'a, Result<ModelResponse, ProviderError>> {
+        self.inner.complete(request)
+    }
+
+    fn stream<'a>(
+        &'a self,
+        request: ModelRequest,
+    ) -> ProviderFuture<'a, Result<BoxedEventStream<'a>, ProviderError>> {
+        Box::pin(async move {
+            self.timeouts
+                .open_since(self.inner.stream(request), Instant::now())
+                .await
+        })
+    }
+
+    fn health<'a>(&'a self) -> ProviderFuture<'a, ProviderHealth> {
+        self.inner.health()
+    }
+
+    fn list_models<'a>(&'a self) -> ProviderFuture<'a, Result<Vec<ModelInfo>, ProviderError>> {
+        self.inner.list_models()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+
+    use super::*;
+    use crate::{Message, ProviderErrorKind, Role, VecEventStream};
+
+    /// Yields its events, then never completes.
+    struct StalledStream(VecDeque<StreamEvent>);
+
+    impl Stream for StalledStream {
+        type Item = Result<StreamEvent, ProviderError>;
+
+        fn poll_next(
+            mut self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+        ) -> Poll<Option<Result<StreamEvent, ProviderError>>> {
+            match self.0.pop_front() {
+                Some(event) => Poll::Ready(Some(Ok(event))),
+                None => Poll::Pending,
+            }
+        }
+    }
+
+    fn stalled_after(events: Vec<StreamEvent>) -> BoxedEventStream<'static> {
+        Box::pin(StalledStream(events.into()))
+    }
+
+    async fn next(stream: &mut BoxedEventStream<'_>) -> Option<Result<StreamEvent, ProviderError>> {
+        std::future::poll_fn(|cx| stream.as_mut().poll_next(cx)).await
+    }
+
+    #[tokio::test]
+    async fn first_token_deadline_fails_a_silent_stream() {
+        let timeouts = StreamTimeouts::new().with_first_token(Duration::from_millis(20));
+        let mut stream = timeouts.wrap(stalled_after(Vec::new()));
+
+        let err = next(&mut stream)
+            .await
+            .expect("stream should yield an error")
+            .expect_err("stream should time out");
+        assert_eq!(err.kind, ProviderErrorKind::Timeout);
+        assert!(err.retryable);
+        assert!(err.message.contains("first token phase"));
+        assert!(next(&mut stream).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn idle_deadline_resets_on_every_event() {
+        let timeouts = StreamTimeouts::new().with_idle(Duration::from_millis(20));
+        let mut stream = timeouts.wrap(stalled_after(vec![
+            StreamEvent::TextDelta("hel".to_string()),
+            StreamEvent::TextDelta("lo".to_string()),
+        ]));
+
+        for _ in 0..2 {
+            assert!(matches!(
+                next(&mut stream).await,
+                Some(Ok(StreamEvent::TextDelta(_)))
+            ));
+        }
+        let err = next(&mut stream)
+            .await
+            .expect("stream should yield an error")
+            .expect_err("stream should time out");
+        assert!(err.message.contains("idle phase"));
+    }
+
+    /// Provider whose `stream()` never returns.
+    struct HangingProvider;
+
+    impl ModelProvider for HangingProvider {
+        fn id(&self) -> ProviderId {
+            ProviderId::OpenAi
+        }
+
+        fn complete<'a>(
+            &'a self,
+            _request: ModelRequest,
+        ) -> ProviderFuture<'a, Result<ModelResponse, ProviderError>> {
+            Box::pin(std::future::pending())
+        }
+
+        fn stream<'a>(
+            &'a self,
+            _request: ModelRequest,
+        ) -> ProviderFuture<'a, Result<BoxedEventStream<'a>, ProviderError>> {
+            Box::pin(std::future::pending())
+        }
+    }
+
+    #[tokio::test]
+    async fn first_token_deadline_bounds_opening_the_stream() {
+        let provider = StreamTimeoutLayer::new(
+            StreamTimeouts::new().with_first_token(Duration::from_millis(20)),
+        )
+        .layer(Arc::new(HangingProvider));
+        let request = ModelRequest::new("gpt-4o-mini", vec![Message::new(Role::User, "hi")]);
+
+        let err = match provider.stream(request).await {
+            Ok(_) => panic!("stream should time out before opening"),
+            Err(err) => err,
+        };
+        assert_eq!(err.kind, ProviderErrorKind::Timeout);
+        assert!(err.message.contains("first token phase"));
+    }
+
+    #[tokio::test]
+    async fn streams_that_finish_in_time_pass_through() {
+        let events = vec![Ok(StreamEvent::MessageComplete(Message::new(
+            Role::Assistant,
+            "done",
+        )))];
+        let mut stream = StreamTimeouts::new()
+            .with_first_token(Duration::from_millis(1))
+            .with_idle(Duration::from_millis(1))
+            .wrap(Box::pin(VecEventStream::new(events.clone())));
+
+        let mut seen = Vec::new();
+        while let Some(event) = next(&mut stream).await {
+            seen.push(event);
+        }
+        assert_eq!(seen, events);
+    }
+}
@@ -0,0 +1,734 @@
+//! Encrypted on-disk credential vault backing [`SecureCredentialManager`].
+//!
+//! The vault is a JSON envelope around a ChaCha20-Poly1305 ciphertext. The key is
+//! derived with Argon2id from either a passphrase or the contents of a key file,
+//! using a random salt stored alongside the ciphertext.
+//!
+//! ```rust
+//! use fprovider::{CredentialVault, ProviderId, SecureCredentialManager};
+//!
+//! let path = std::env::temp_dir().join(format!("fprovider-doc-vault-{}", std::process::id()));
+//! let manager = SecureCredentialManager::new()
+//!     .with_vault(CredentialVault::with_passphrase(&path, "correct horse"))
+//!     .expect("vault should attach");
+//! manager
+//!     .set_api_key(ProviderId::OpenAi, "sk-test-123")
+//!     .expect("api key should store");
+//!
+//! let reopened = SecureCredentialManager::new()
+//!     .with_vault(CredentialVault::with_passphrase(&path, "correct horse"))
+//!     .expect("vault should reopen");
+//! assert!(reopened.has_credentials(ProviderId::OpenAi).expect("lookup should work"));
+//! # std::fs::remove_file(&path).ok();
+//! ```
+//!
+//! [`SecureCredentialManager`]: crate::SecureCredentialManager
+
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use argon2::{Algorithm, Argon2, Params, Version};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use serde::{Deserialize, Serialize};
+use zeroize::{Zeroize, Zeroizing};
+
+use crate::{
+    BrowserLoginSession, CredentialKind, CredentialMetadata, ProviderCredential, ProviderError,
+    ProviderId, SecretString,
+};
+
+const VAULT_VERSION: u32 = 1;
+const VAULT_AAD: &[u8] = b"fiddlesticks-credential-vault-v1";
+const SALT_LEN: usize = 16;
+const KEY_LEN: usize = 32;
+/// How far above the Argon2 defaults a vault header may set each cost, so a tampered file
+/// cannot make key derivation exhaust memory or CPU.
+const MAX_KDF_COST_FACTOR: u32 = 4;
+
+/// Secret material the vault encryption key is derived from.
+pub enum VaultKey {
+    Passphrase(SecretString),
+    /// Path to a file whose raw contents act as the key material.
+    KeyFile(PathBuf),
+}
+
+impl std::fmt::Debug for VaultKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Passphrase(_) => f.write_str("VaultKey::Passphrase([REDACTED])"),
+            Self::KeyFile(path) => f.debug_tuple("VaultKey::KeyFile").field(path).finish(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct CredentialVault {
+    path: PathBuf,
+    key: VaultKey,
+}
+
+impl CredentialVault {
+    pub fn new(path: impl Into<PathBuf>, key: VaultKey) -> Self {
+        Self {
+            path: path.into(),
+            key,
+        }
+    }
+
+    pub fn with_passphrase(path: impl Into<PathBuf>, passphrase: impl Into<String>) -> Self {
+        Self::new(path, VaultKey::Passphrase(SecretString::new(passphrase)))
+    }
+
+    pub fn with_key_file(path: impl Into<PathBuf>, key_file: impl Into<PathBuf>) -> Self {
+        Self::new(path, VaultKey::KeyFile(key_file.into()))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn exists(&self) -> bool {
+        self.path.exists()
+    }
+
+    pub(crate) fn read(&self) -> Result<Vec<VaultRecord>, ProviderError> {
+        let contents = std::fs::read_to_string(&self.path).map_err(|err| {
+            ProviderError::other(format!(
+                "failed to read credential vault '{}': {err}",
+                self.path.display()
+            ))
+        })?;
+        let file: VaultFile = serde_json::from_str(&contents)
+            .map_err(|_| ProviderError::other("credential vault file is malformed"))?;
+        if file.version != VAULT_VERSION {
+            return Err(ProviderError::other(format!(
+                "unsupported credential vault version {}",
+                file.version
+            )));
+        }
+
+        if file.kdf.algorithm != "argon2id" {
+            return Err(ProviderError::other(format!(
+                "unsupported credential vault kdf '{}'",
+                file.kdf.algorithm
+            )));
+        }
+        file.kdf.check_limits()?;
+
+        let salt = decode(&file.kdf.salt)?;
+        let nonce = decode(&file.nonce)?;
+        let ciphertext = decode(&file.ciphertext)?;
+        if nonce.len() != 12 {
+            return Err(ProviderError::other("credential vault file is malformed"));
+        }
+
+        let key = self.derive_key(&salt, &file.kdf)?;
+        let cipher = ChaCha20Poly1305::new(Key::from_slice(key.as_slice()));
+        let plaintext = Zeroizing::new(
+            cipher
+                .decrypt(
+                    Nonce::from_slice(&nonce),
+                    Payload {
+                        msg: &ciphertext,
+                        aad: VAULT_AAD,
+                    },
+                )
+                .map_err(|_| {
+                    ProviderError::authentication(
+                        "failed to decrypt credential vault: wrong key or corrupted file",
+                    )
+                })?,
+        );
+
+        let stored: Vec<StoredCredential> = serde_json::from_slice(&plaintext)
+            .map_err(|_| ProviderError::other("credential vault payload is malformed"))?;
+        stored.iter().map(StoredCredential::to_record).collect()
+    }
+
+    pub(crate) fn write(&self, records: &[VaultRecord]) -> Result<(), ProviderError> {
+        let stored = records
+            .iter()
+            .map(StoredCredential::from_record)
+            .collect::<Vec<_>>();
+        let plaintext = Zeroizing::new(
+            serde_json::to_vec(&stored)
+                .map_err(|err| ProviderError::other(format!("failed to encode vault: {err}")))?,
+        );
+
+        let mut salt = [0_u8; SALT_LEN];
+        OsRng.fill_bytes(&mut salt);
+        let kdf = KdfParams::new(&salt);
+        let key = self.derive_key(&salt, &kdf)?;
+        let cipher = ChaCha20Poly1305::new(Key::from_slice(key.as_slice()));
+        let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let ciphertext = cipher
+            .encrypt(
+                &nonce,
+                Payload {
+                    msg: &plaintext,
+                    aad: VAULT_AAD,
+                },
+            )
+            .map_err(|_| ProviderError::other("failed to encrypt credential vault"))?;
+
+        let file = VaultFile {
+            version: VAULT_VERSION,
+            kdf,
+            nonce: BASE64.encode(nonce),
+            ciphertext: BASE64.encode(ciphertext),
+        };
+        let contents = serde_json::to_string_pretty(&file)
+            .map_err(|err| ProviderError::other(format!("failed to encode vault: {err}")))?;
+
+        self.write_atomically(contents.as_bytes())
+    }
+
+    fn derive_key(
+        &self,
+        salt: &[u8],
+        kdf: &KdfParams,
+    ) -> Result<Zeroizing<[u8; KEY_LEN]>, ProviderError> {
+        let material = match &self.key {
+            VaultKey::Passphrase(passphrase) => {
+                Zeroizing::new(passphrase.expose().as_bytes().to_vec())
+            }
+            VaultKey::KeyFile(path) => Zeroizing::new(std::fs::read(path).map_err(|err| {
+                ProviderError::authentication(format!(
+                    "failed to read vault key file '{}': {err}",
+                    path.display()
+                ))
+            })?),
+        };
+        if material.is_empty() {
+            return Err(ProviderError::authentication(
+                "vault key material must not be empty",
+            ));
+        }
+
+        let params = Params::new(
+            kdf.memory_kib,
+            kdf.iterations,
+            kdf.parallelism,
+            Some(KEY_LEN),
+        )
+        .map_err(|err| ProviderError::other(format!("invalid vault kdf parameters: {err}")))?;
+        let mut key = Zeroizing::new([0_u8; KEY_LEN]);
+        Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+            .hash_password_into(&material, salt, key.as_mut_slice())
+            .map_err(|err| ProviderError::other(format!("failed to derive vault key: {err}")))?;
+
+        Ok(key)
+    }
+
+    fn write_atomically(&self, contents: &[u8]) -> Result<(), ProviderError> {
+        let io_error = |err: std::io::Error| {
+            ProviderError::other(format!(
+                "failed to write credential vault '{}': {err}",
+                self.path.display()
+            ))
+        };
+
+        if let Some(parent) = self.path.parent()
+            && !parent.as_os_str().is_empty()
+        {
+            std::fs::create_dir_all(parent).map_err(io_error)?;
+        }
+
+        // Unique per write, so concurrent writers (other processes or managers sharing the
+        // vault) never truncate or rename each other's temporary file.
+        let mut temp_name = self.path.as_os_str().to_owned();
+        temp_name.push(format!(
+            ".{}.{:016x}.tmp",
+            std::process::id(),
+            OsRng.next_u64()
+        ));
+        let temp_path = PathBuf::from(temp_name);
+
+        let mut options = std::fs::OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+
+        let mut file = options.open(&temp_path).map_err(io_error)?;
+        let written = std::io::Write::write_all(&mut file, contents)
+            .and_then(|()| file.sync_all())
+            .and_then(|()| {
+                drop(file);
+                std::fs::rename(&temp_path, &self.path)
+            });
+        if written.is_err() {
+            let _ = std::fs::remove_file(&temp_path);
+        }
+        written.map_err(io_error)
+    }
+}
+
+/// Decrypted vault entry exchanged with [`crate::SecureCredentialManager`].
+pub(crate) struct VaultRecord {
+    pub(crate) provider: ProviderId,
+    pub(crate) credential: ProviderCredential,
+    pub(crate) metadata: CredentialMetadata,
+}
+
+#[derive(Serialize, Deserialize)]
+struct VaultFile {
+    version: u32,
+    kdf: KdfParams,
+    nonce: String,
+    ciphertext: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct KdfParams {
+    algorithm: String,
+    salt: String,
+    memory_kib: u32,
+    iterations: u32,
+    parallelism: u32,
+}
+
+impl KdfParams {
+    fn new(salt: &[u8]) -> Self {
+        Self {
+            algorithm: "argon2id".to_string(),
+            salt: BASE64.encode(salt),
+            memory_kib: Params::DEFAULT_M_COST,
+            iterations: Params::DEFAULT_T_COST,
+            parallelism: Params::DEFAULT_P_COST,
+        }
+    }
+
+    fn check_limits(&self) -> Result<(), ProviderError> {
+        let within =
+            |value: u32, default: u32| value <= default.saturating_mul(MAX_KDF_COST_FACTOR);
+        if within(self.memory_kib, Params::DEFAULT_M_COST)
+            && within(self.iterations, Params::DEFAULT_T_COST)
+            && within(self.parallelism, Params::DEFAULT_P_COST)
+        {
+            return Ok(());
+        }
+
+        Err(ProviderError::other(format!(
+            "credential vault kdf parameters exceed supported limits \
+             (memory {} KiB, {} iterations, parallelism {})",
+            self.memory_kib, self.iterations, self.parallelism
+        )))
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct StoredCredential {
+    provider: String,
+    kind: String,
+    secret: String,
+    created_at_ms: u64,
+    expires_at_ms: Option<u64>,
+    last_used_at_ms: Option<u64>,
+    last_rotated_at_ms: Option<u64>,
+    access_count: u64,
+}
+
+impl StoredCredential {
+    fn from_record(record: &VaultRecord) -> Self {
+        let (kind, secret) = match &record.credential {
+            ProviderCredential::ApiKey(secret) => ("api_key", secret.expose()),
+            ProviderCredential::AccessToken(secret) => ("access_token", secret.expose()),
+            ProviderCredential::BrowserSession(session) => {
+                ("browser_session", session.session_token.expose())
+            }
+        };
+
+        Self {
+            provider: record.provider.to_string(),
+            kind: kind.to_string(),
+            secret: secret.to_string(),
+            created_at_ms: to_millis(record.metadata.created_at),
+            expires_at_ms: record.metadata.expires_at.map(to_millis),
+            last_used_at_ms: record.metadata.last_used_at.map(to_millis),
+            last_rotated_at_ms: record.metadata.last_rotated_at.map(to_millis),
+            access_count: record.metadata.access_count,
+        }
+    }
+
+    fn to_record(&self) -> Result<VaultRecord, ProviderError> {
+        let provider = parse_provider(&self.provider)?;
+        let expires_at = self.expires_at_ms.map(from_millis);
+        let credential = match parse_kind(&self.kind)? {
+            CredentialKind::ApiKey => ProviderCredential::ApiKey(SecretString::new(&*self.secret)),
+            CredentialKind::AccessToken => {
+                ProviderCredential::AccessToken(SecretString::new(&*self.secret))
+            }
+            CredentialKind::BrowserSession => ProviderCredential::BrowserSession(
+                BrowserLoginSession::new(&*self.secret, expires_at),
+            ),
+        };
+
+        Ok(VaultRecord {
+            provider,
+            credential,
+            metadata: CredentialMetadata {
+                created_at: from_millis(self.created_at_ms),
+                expires_at,
+                last_used_at: self.last_used_at_ms.map(from_millis),
+                last_rotated_at: self.last_rotated_at_ms.map(from_millis),
+                access_count: self.access_count,
+            },
+        })
+    }
+}
+
+impl Drop for StoredCredential {
+    fn drop(&mut self) {
+        self.secret.zeroize();
+    }
+}
+
+fn parse_provider(value: &str) -> Result<ProviderId, ProviderError> {
+    [
+        ProviderId::OpenCodeZen,
+        ProviderId::OpenAi,
+        ProviderId::Anthropic,
+        ProviderId::Ollama,
+        ProviderId::Gemini,
+    ]
+    .into_iter()
+    .find(|provider| provider.to_string() == value)
+    .ok_or_else(|| ProviderError::other(format!("credential vault has unknown provider '{value}'")))
+}
+
+fn parse_kind(value: &str) -> Result<CredentialKind, ProviderError> {
+    match value {
+        "api_key" => Ok(CredentialKind::ApiKey),
+        "access_token" => Ok(CredentialKind::AccessToken),
+        "browser_session" => Ok(CredentialKind::BrowserSession),
+        _ => Err(ProviderError::other(format!(
+            "credential vault has unknown credential kind '{value}'"
+        ))),
+    }
+}
+
+fn decode(value: &str) -> Result<Vec<u8>, ProviderError> {
+    BASE64
+        .decode(value)
+        .map_err(|_| ProviderError::other("credential vault file is malformed"))
+}
+
+fn to_millis(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+fn from_millis(millis: u64) -> SystemTime {
+    UNIX_EPOCH + Duration::from_millis(millis)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CredentialAccessAction, ProviderErrorKind, SecureCredentialManager};
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("fprovider-vault-{name}-{}", std::process::id()))
+    }
+
+    #[test]
+    fn vault_round_trips_credentials_and_metadata() {
+        let path = temp_path("round-trip");
+        let manager = SecureCredentialManager::new()
+            .with_vault(CredentialVault::with_passphrase(&path, "passphrase"))
+            .expect("vault should attach");
+        manager
+            .set_api_key_with_ttl(ProviderId::OpenAi, "sk-vault", Duration::from_secs(600))
+            .expect("key should store");
+        manager
+            .set_browser_session(ProviderId::OpenCodeZen, "session-1", None)
+            .expect("session should store");
+
+        let contents = std::fs::read_to_string(&path).expect("vault should exist");
+        assert!(!contents.contains("sk-vault"));
+        assert!(!contents.contains("session-1"));
+
+        let reopened = SecureCredentialManager::new()
+            .with_vault(CredentialVault::with_passphrase(&path, "passphrase"))
+            .expect("vault should reopen");
+        let key = reopened
+            .api_key(ProviderId::OpenAi)
+            .expect("lookup should work")
+            .expect("key should load");
+        assert_eq!(key.expose(), "sk-vault");
+        let metadata = reopened
+            .credential_metadata(ProviderId::OpenAi)
+            .expect("metadata lookup should work")
+            .expect("metadata should load");
+        assert!(metadata.expires_at.is_some());
+        assert_eq!(
+            reopened
+                .credential_kind(ProviderId::OpenCodeZen)
+                .expect("lookup should work"),
+            Some(CredentialKind::BrowserSession)
+        );
+
+        std::fs::remove_file(&path).expect("vault should be removed");
+    }
+
+    #[test]
+    fn concurrent_writers_use_separate_temp_files() {
+        let dir = temp_path("concurrent");
+        std::fs::create_dir_all(&dir).expect("directory should be created");
+        let path = dir.join("vault.json");
+
+        std::thread::scope(|scope| {
+            for provider in [ProviderId::OpenAi, ProviderId::Anthropic] {
+                let path = &path;
+                scope.spawn(move || {
+                    let manager = SecureCredentialManager::new()
+                        .with_vault(CredentialVault::with_passphrase(path, "passphrase"))
+                        .expect("vault should attach");
+                    for index in 0..3 {
+                        manager
+                            .set_api_key(provider, format!("sk-{index}"))
+                            .expect("concurrent writes should not clobber each other");
+                    }
+                });
+            }
+        });
+
+        let files = std::fs::read_dir(&dir)
+            .expect("directory should list")
+            .map(|entry| entry.expect("entry should read").file_name())
+            .collect::<Vec<_>>();
+        assert_eq!(files, vec![std::ffi::OsString::from("vault.json")]);
+        SecureCredentialManager::new()
+            .with_vault(CredentialVault::with_passphrase(&path, "passphrase"))
+            .expect("vault should stay readable");
+
+        std::fs::remove_dir_all(&dir).expect("directory should be removed");
+    }
+
+    #[test]
+    fn rotate_and_revoke_write_through_to_disk() {
+        let path = temp_path("write-through");
+        let open = || {
+            SecureCredentialManager::new()
+                .with_vault(CredentialVault::with_passphrase(&path, "passphrase"))
+                .expect("vault should attach")
+        };
+
+        let manager = open();
+        manager
+            .set_api_key(ProviderId::Anthropic, "sk-old")
+            .expect("key should store");
+        manager
+            .rotate_api_key(ProviderId::Anthropic, "sk-new", None)
+            .expect("key should rotate");
+
+        let rotated = open()
+            .credential_metadata(ProviderId::Anthropic)
+            .expect("lookup should work")
+            .expect("metadata should load");
+        assert!(rotated.last_rotated_at.is_some());
+        assert_eq!(
+            open()
+                .api_key(ProviderId::Anthropic)
+                .expect("lookup should work")
+                .map(|key| key.expose().to_string()),
+            Some("sk-new".to_string())
+        );
+
+        manager
+            .revoke(ProviderId::Anthropic)
+            .expect("revoke should work");
+        assert!(
+            !open()
+                .has_credentials(ProviderId::Anthropic)
+                .expect("lookup should work")
+        );
+
+        std::fs::remove_file(&path).expect("vault should be removed");
+    }
+
+    #[test]
+    fn wrong_passphrase_is_rejected() {
+        let path = temp_path("wrong-passphrase");
+        SecureCredentialManager::new()
+            .with_vault(CredentialVault::with_passphrase(&path, "right"))
+            .expect("vault should attach")
+            .set_api_key(ProviderId::OpenAi, "sk-secret")
+            .expect("key should store");
+
+        let err = SecureCredentialManager::new()
+            .with_vault(CredentialVault::with_passphrase(&path, "wrong"))
+            .err()
+            .expect("wrong passphrase should fail");
+        assert_eq!(err.kind, ProviderErrorKind::Authentication);
+
+        std::fs::remove_file(&path).expect("vault should be removed");
+    }
+
+    #[test]
+    fn key_file_derives_vault_key() {
+        let path = temp_path("key-file");
+        let key_path = temp_path("key-file-key");
+        std::fs::write(&key_path, [7_u8; 32]).expect("key file should write");
+
+        SecureCredentialManager::new()
+            .with_vault(CredentialVault::with_key_file(&path, &key_path))
+            .expect("vault should attach")
+            .set_access_token(ProviderId::OpenAi, "token", None)
+            .expect("token should store");
+
+        let reopened = SecureCredentialManager::new()
+            .with_vault(CredentialVault::with_key_file(&path, &key_path))
+            .expect("vault should reopen");
+        assert_eq!(
+            reopened
+                .credential_kind(ProviderId::OpenAi)
+                .expect("lookup should work"),
+            Some(CredentialKind::AccessToken)
+        );
+
+        std::fs::remove_file(&path).expect("vault should be removed");
+        std::fs::remove_file(&key_path).expect("key file should be removed");
+    }
+
+    #[test]
+    fn excessive_kdf_parameters_are_rejected() {
+        let path = temp_path("kdf-limits");
+        let vault = CredentialVault::with_passphrase(&path, "passphrase");
+        vault.write(&[]).expect("vault should write");
+
+        let mut file: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&path).expect("vault should exist"))
+                .expect("vault should parse");
+        file["kdf"]["memory_kib"] = serde_json::json!(u32::MAX);
+        std::fs::write(&path, file.to_string()).expect("vault should rewrite");
+
+        let err = vault.read().err().expect("oversized kdf should fail");
+        assert_eq!(err.kind, ProviderErrorKind::Other);
+        assert!(err.message.contains("exceed supported limits"));
+
+        std::fs::remove_file(&path).expect("vault should be removed");
+    }
+
+    #[test]
+    fn failed_vault_writes_leave_credentials_unchanged() {
+        let dir = temp_path("failed-write");
+        let path = dir.join("vault");
+        let manager = SecureCredentialManager::new()
+            .with_vault(CredentialVault::with_passphrase(&path, "passphrase"))
+            .expect("vault should attach");
+        manager
+            .set_api_key(ProviderId::OpenAi, "sk-kept")
+            .expect("key should store");
+
+        // A file where the vault directory was makes every later write fail.
+        std::fs::remove_dir_all(&dir).expect("vault dir should be removed");
+        std::fs::write(&dir, b"not a directory").expect("blocker should write");
+
+        assert!(manager.set_api_key(ProviderId::OpenAi, "sk-lost").is_err());
+        assert!(manager.revoke(ProviderId::OpenAi).is_err());
+        assert_eq!(
+            manager
+                .api_key(ProviderId::OpenAi)
+                .expect("lookup should work")
+                .map(|key| key.expose().to_string()),
+            Some("sk-kept".to_string())
+        );
+
+        std::fs::remove_file(&dir).expect("blocker should be removed");
+    }
+
+    #[test]
+    fn source_credentials_are_not_written_to_the_vault() {
+        use std::sync::Arc;
+
+        struct StaticSource;
+
+        impl crate::CredentialSource for StaticSource {
+            fn load(&self) -> Result<Option<crate::SourcedCredential>, ProviderError> {
+                Ok(Some(crate::SourcedCredential::new(
+                    CredentialKind::ApiKey,
+                    "sk-sourced",
+                    None,
+                )))
+            }
+        }
+
+        let path = temp_path("sourced");
+        let open = || {
+            SecureCredentialManager::new()
+                .with_vault(CredentialVault::with_passphrase(&path, "passphrase"))
+                .expect("vault should attach")
+        };
+
+        let manager = open();
+        manager
+            .set_source(ProviderId::OpenAi, Arc::new(StaticSource))
+            .expect("source should register");
+        assert!(
+            manager
+                .has_credentials(ProviderId::OpenAi)
+                .expect("lookup should work")
+        );
+        manager
+            .set_api_key(ProviderId::Gemini, "gemini-key")
+            .expect("key should store");
+        manager.persist().expect("persist should work");
+
+        let reopened = open();
+        assert!(
+            !reopened
+                .has_credentials(ProviderId::OpenAi)
+                .expect("lookup should work")
+        );
+        assert!(
+            reopened
+                .has_credentials(ProviderId::Gemini)
+                .expect("lookup should work")
+        );
+
+        std::fs::remove_file(&path).expect("vault should be removed");
+    }
+
+    #[test]
+    fn loading_vault_reports_set_events() {
+        use std::sync::{Arc, Mutex};
+
+        #[derive(Default)]
+        struct Recorder {
+            actions: Mutex<Vec<CredentialAccessAction>>,
+        }
+
+        impl crate::CredentialAccessObserver for Recorder {
+            fn on_event(&self, event: crate::CredentialAccessEvent) {
+                self.actions
+                    .lock()
+                    .expect("actions lock")
+                    .push(event.action);
+            }
+        }
+
+        let path = temp_path("events");
+        SecureCredentialManager::new()
+            .with_vault(CredentialVault::with_passphrase(&path, "passphrase"))
+            .expect("vault should attach")
+            .set_api_key(ProviderId::Gemini, "key")
+            .expect("key should store");
+
+        let recorder = Arc::new(Recorder::default());
+        SecureCredentialManager::with_observer(recorder.clone())
+            .with_vault(CredentialVault::with_passphrase(&path, "passphrase"))
+            .expect("vault should reopen");
+        assert_eq!(
+            *recorder.actions.lock().expect("actions lock"),
+            vec![CredentialAccessAction::Set]
+        );
+
+        std::fs::remove_file(&path).expect("vault should be removed");
+    }
+}
@@ -8,8 +8,8 @@ use fprovider::adapters::openai::{
     OpenAiResponse, OpenAiStreamChunk, OpenAiToolCall, OpenAiTransport, OpenAiUsage,
 };
 use fprovider::{
-    Message, ModelProvider, ModelRequest, ProviderError, ProviderFuture, ProviderId, Role,
-    SecureCredentialManager, StopReason,
+    HealthStatus, Message, ModelInfo, ModelProvider, ModelRequest, ProviderError, ProviderFuture,
+    ProviderId, Role, SecureCredentialManager, StopReason,
 };
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -32,9 +32,11 @@ impl OpenAiTransport for FakeTransport {
             *self.captured_auth.lock().expect("auth lock") = Some(match auth {
                 OpenAiAuth::ApiKey(value) => CapturedAuth(value.expose().to_string()),
                 OpenAiAuth::BrowserSession(value) => CapturedAuth(value.expose().to_string()),
+                OpenAiAuth::BearerToken(value) => CapturedAuth(value.expose().to_string()),
             });
 
             Ok(OpenAiResponse {
+                id: None,
                 model: "claude-3-5-sonnet-latest".to_string(),
                 message: OpenAiAssistantMessage {
                     content: "anthropic-ok".to_string(),
@@ -49,6 +51,7 @@ impl OpenAiTransport for FakeTransport {
                     prompt_tokens: 2,
                     completion_tokens: 3,
                     total_tokens: 5,
+                    cached_tokens: 0,
                 },
             })
         })
@@ -64,12 +67,14 @@ impl OpenAiTransport for FakeTransport {
             *self.captured_auth.lock().expect("auth lock") = Some(match auth {
                 OpenAiAuth::ApiKey(value) => CapturedAuth(value.expose().to_string()),
                 OpenAiAuth::BrowserSession(value) => CapturedAuth(value.expose().to_string()),
+                OpenAiAuth::BearerToken(value) => CapturedAuth(value.expose().to_string()),
             });
 
             let output = futures_util::stream::iter(
                 vec![
                     OpenAiStreamChunk::TextDelta("hello".to_string()),
                     OpenAiStreamChunk::ResponseComplete(OpenAiResponse {
+                        id: None,
                         model: "claude-3-5-sonnet-latest".to_string(),
                         message: OpenAiAssistantMessage {
                             content: "hello".to_string(),
@@ -80,6 +85,7 @@ impl OpenAiTransport for FakeTransport {
                             prompt_tokens: 1,
                             completion_tokens: 1,
                             total_tokens: 2,
+                            cached_tokens: 0,
                         },
                     }),
                 ]
@@ -90,6 +96,24 @@ impl OpenAiTransport for FakeTransport {
             Ok(Box::pin(output) as OpenAiChunkStream<'a>)
         })
     }
+
+    fn list_models<'a>(
+        &'a self,
+        auth: OpenAiAuth,
+    ) -> ProviderFuture<'a, Result<Vec<ModelInfo>, ProviderError>> {
+        Box::pin(async move {
+            *self.captured_auth.lock().expect("auth lock") = Some(match auth {
+                OpenAiAuth::ApiKey(value) => CapturedAuth(value.expose().to_string()),
+                OpenAiAuth::BrowserSession(value) => CapturedAuth(value.expose().to_string()),
+                OpenAiAuth::BearerToken(value) => CapturedAuth(value.expose().to_string()),
+            });
+
+            Ok(vec![
+                ModelInfo::new(ProviderId::OpenAi, "claude-sonnet-4-0")
+                    .with_display_name("Claude Sonnet 4"),
+            ])
+        })
+    }
 }
 
 #[tokio::test]
@@ -151,6 +175,82 @@ async fn stream_maps_response_complete_to_anthropic_provider_id() {
     assert_eq!(saw_provider, Some(ProviderId::Anthropic));
 }
 
+#[tokio::test]
+async fn list_models_relabels_transport_models_as_anthropic() {
+    let credentials = Arc::new(SecureCredentialManager::new());
+    credentials
+        .set_anthropic_api_key("sk-ant-test-xyz")
+        .expect("key should set");
+
+    let transport = Arc::new(FakeTransport::default());
+    let provider = AnthropicProvider::new(credentials, transport.clone());
+
+    let models = provider
+        .list_models()
+        .await
+        .expect("listing should succeed");
+
+    assert_eq!(models.len(), 1);
+    assert_eq!(models[0].provider, ProviderId::Anthropic);
+    assert_eq!(models[0].owner.as_deref(), Some("anthropic"));
+    assert_eq!(models[0].display_name.as_deref(), Some("Claude Sonnet 4"));
+    assert_eq!(
+        transport.captured_auth.lock().expect("auth lock").clone(),
+        Some(CapturedAuth("sk-ant-test-xyz".to_string()))
+    );
+}
+
+#[derive(Debug)]
+struct RejectingTransport;
+
+impl OpenAiTransport for RejectingTransport {
+    fn complete<'a>(
+        &'a self,
+        _request: OpenAiRequest,
+        _auth: OpenAiAuth,
+    ) -> ProviderFuture<'a, Result<OpenAiResponse, ProviderError>> {
+        Box::pin(async { Err(ProviderError::other("not used")) })
+    }
+
+    fn stream<'a>(
+        &'a self,
+        _request: OpenAiRequest,
+        _auth: OpenAiAuth,
+    ) -> ProviderFuture<'a, Result<OpenAiChunkStream<'a>, ProviderError>> {
+        Box::pin(async { Err(ProviderError::other("not used")) })
+    }
+
+    fn list_models<'a>(
+        &'a self,
+        _auth: OpenAiAuth,
+    ) -> ProviderFuture<'a, Result<Vec<ModelInfo>, ProviderError>> {
+        Box::pin(async {
+            Err(ProviderError::authentication(
+                "http 401 Unauthorized: invalid x-api-key",
+            ))
+        })
+    }
+}
+
+#[tokio::test]
+async fn health_probes_the_models_endpoint() {
+    let credentials = Arc::new(SecureCredentialManager::new());
+    credentials
+        .set_anthropic_api_key("sk-ant-test-xyz")
+        .expect("key should set");
+
+    let healthy = AnthropicProvider::new(credentials.clone(), Arc::new(FakeTransport::default()))
+        .health()
+        .await;
+    assert_eq!(healthy.status, HealthStatus::Healthy);
+
+    let rejected = AnthropicProvider::new(credentials, Arc::new(RejectingTransport))
+        .health()
+        .await;
+    assert_eq!(rejected.status, HealthStatus::Unhealthy);
+    assert!(!rejected.is_ready());
+}
+
 #[tokio::test]
 async fn missing_anthropic_credentials_returns_authentication_error() {
     let credentials = Arc::new(SecureCredentialManager::new());
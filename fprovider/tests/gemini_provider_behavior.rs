@@ -0,0 +1,332 @@
+#![cfg(feature = "provider-gemini")]
+
+use std::sync::{Arc, Mutex};
+
+use fprovider::adapters::gemini::{
+    GeminiChunkStream, GeminiProvider, GeminiRequest, GeminiResponse, GeminiTransport,
+};
+use fprovider::{
+    HealthStatus, Message, ModelInfo, ModelProvider, ModelRequest, OutputItem, ProviderError,
+    ProviderFuture, ProviderId, Role, SecretString, SecureCredentialManager, StopReason,
+    StreamEvent, ToolDefinition, ToolResult,
+};
+use serde_json::json;
+
+#[derive(Debug, Default)]
+struct FakeTransport {
+    captured_api_key: Mutex<Option<String>>,
+    captured_request: Mutex<Option<GeminiRequest>>,
+}
+
+impl FakeTransport {
+    fn capture(&self, request: GeminiRequest, api_key: SecretString) {
+        *self.captured_request.lock().expect("request lock") = Some(request);
+        *self.captured_api_key.lock().expect("api key lock") = Some(api_key.expose().to_string());
+    }
+
+    fn captured_request(&self) -> GeminiRequest {
+        self.captured_request
+            .lock()
+            .expect("request lock")
+            .clone()
+            .expect("request should be captured")
+    }
+}
+
+impl GeminiTransport for FakeTransport {
+    fn generate_content<'a>(
+        &'a self,
+        request: GeminiRequest,
+        api_key: SecretString,
+    ) -> ProviderFuture<'a, Result<GeminiResponse, ProviderError>> {
+        Box::pin(async move {
+            self.capture(request, api_key);
+
+            Ok(serde_json::from_value(json!({
+                "candidates": [{
+                    "content": {
+                        "role": "model",
+                        "parts": [
+                            { "text": "checking" },
+                            { "functionCall": { "name": "lookup", "args": { "query": "rust" } } }
+                        ]
+                    },
+                    "finishReason": "STOP"
+                }],
+                "usageMetadata": {
+                    "promptTokenCount": 4,
+                    "candidatesTokenCount": 6,
+                    "totalTokenCount": 10
+                },
+                "modelVersion": "gemini-2.0-flash-001"
+            }))
+            .expect("fixture should deserialize"))
+        })
+    }
+
+    fn stream_generate_content<'a>(
+        &'a self,
+        request: GeminiRequest,
+        api_key: SecretString,
+    ) -> ProviderFuture<'a, Result<GeminiChunkStream<'a>, ProviderError>> {
+        Box::pin(async move {
+            self.capture(request, api_key);
+
+            let chunks = [
+                json!({
+                    "candidates": [{ "content": { "role": "model", "parts": [{ "text": "hel" }] } }]
+                }),
+                json!({
+                    "candidates": [{
+                        "content": { "role": "model", "parts": [{ "text": "lo" }] },
+                        "finishReason": "STOP"
+                    }],
+                    "usageMetadata": {
+                        "promptTokenCount": 2,
+                        "candidatesTokenCount": 1,
+                        "totalTokenCount": 3,
+                        "cachedContentTokenCount": 1
+                    }
+                }),
+            ]
+            .into_iter()
+            .map(|chunk| {
+                Ok(serde_json::from_value::<GeminiResponse>(chunk)
+                    .expect("fixture should deserialize"))
+            })
+            .collect::<Vec<_>>();
+
+            Ok(Box::pin(futures_util::stream::iter(chunks)) as GeminiChunkStream<'a>)
+        })
+    }
+}
+
+fn provider_with_key(transport: Arc<FakeTransport>) -> GeminiProvider {
+    let credentials = Arc::new(SecureCredentialManager::new());
+    credentials
+        .set_gemini_api_key("gemini-test-key")
+        .expect("key should set");
+    GeminiProvider::new(credentials, transport)
+}
+
+#[tokio::test]
+async fn complete_uses_gemini_credentials_and_maps_function_calls() {
+    let transport = Arc::new(FakeTransport::default());
+    let provider = provider_with_key(transport.clone());
+    let request = ModelRequest::new(
+        "gemini-2.0-flash",
+        vec![
+            Message::new(Role::System, "be brief"),
+            Message::new(Role::User, "hi"),
+            Message::new(Role::Assistant, "hello"),
+            Message::new(Role::User, "search"),
+        ],
+    )
+    .with_tools(vec![ToolDefinition {
+        name: "lookup".to_string(),
+        description: "Search docs".to_string(),
+        input_schema: r#"{"type":"object"}"#.to_string(),
+    }]);
+
+    let response = provider
+        .complete(request)
+        .await
+        .expect("complete should succeed");
+    assert_eq!(response.provider, ProviderId::Gemini);
+    assert_eq!(response.model, "gemini-2.0-flash-001");
+    assert_eq!(response.stop_reason, StopReason::ToolUse);
+    assert_eq!(response.usage.input_tokens, 4);
+    assert_eq!(response.usage.output_tokens, 6);
+    assert_eq!(response.usage.total_tokens, 10);
+    assert!(matches!(
+        &response.output[1],
+        OutputItem::ToolCall(call)
+            if call.id == "tool_call_0" && call.name == "lookup" && call.arguments == r#"{"query":"rust"}"#
+    ));
+
+    assert_eq!(
+        transport
+            .captured_api_key
+            .lock()
+            .expect("api key lock")
+            .as_deref(),
+        Some("gemini-test-key")
+    );
+
+    let captured = transport.captured_request();
+    let body = serde_json::to_value(&captured).expect("request should serialize");
+    assert_eq!(captured.model, "gemini-2.0-flash");
+    assert_eq!(body["systemInstruction"]["parts"][0]["text"], "be brief");
+    assert_eq!(body["contents"][0]["role"], "user");
+    assert_eq!(body["contents"][1]["role"], "model");
+    assert_eq!(body["contents"][2]["role"], "user");
+    assert_eq!(
+        body["tools"][0]["functionDeclarations"][0]["name"],
+        "lookup"
+    );
+    assert!(body.get("model").is_none());
+}
+
+#[tokio::test]
+async fn tool_results_are_sent_as_function_call_and_response_pairs() {
+    let transport = Arc::new(FakeTransport::default());
+    let provider = provider_with_key(transport.clone());
+
+    provider
+        .complete(ModelRequest::new(
+            "gemini-2.0-flash",
+            vec![Message::new(Role::User, "search")],
+        ))
+        .await
+        .expect("first turn should succeed");
+
+    let follow_up = ModelRequest::new(
+        "gemini-2.0-flash",
+        vec![
+            Message::new(Role::User, "search"),
+            Message::new(Role::Assistant, "checking"),
+        ],
+    )
+    .with_tool_results(vec![ToolResult {
+        tool_call_id: "tool_call_0".to_string(),
+        output: "found it".to_string(),
+    }]);
+    provider
+        .complete(follow_up)
+        .await
+        .expect("follow-up turn should succeed");
+
+    let body =
+        serde_json::to_value(transport.captured_request()).expect("request should serialize");
+    let model_turn = &body["contents"][1];
+    assert_eq!(model_turn["role"], "model");
+    assert_eq!(model_turn["parts"][0]["text"], "checking");
+    assert_eq!(model_turn["parts"][1]["functionCall"]["name"], "lookup");
+    assert_eq!(
+        model_turn["parts"][1]["functionCall"]["args"]["query"],
+        "rust"
+    );
+
+    let response_turn = &body["contents"][2];
+    assert_eq!(response_turn["role"], "user");
+    assert_eq!(
+        response_turn["parts"][0]["functionResponse"]["name"],
+        "lookup"
+    );
+    assert_eq!(
+        response_turn["parts"][0]["functionResponse"]["response"]["output"],
+        "found it"
+    );
+}
+
+#[tokio::test]
+async fn synthesized_tool_call_ids_are_unique_across_requests() {
+    let transport = Arc::new(FakeTransport::default());
+    let provider = provider_with_key(transport);
+    let request = ModelRequest::new("gemini-2.0-flash", vec![Message::new(Role::User, "search")]);
+
+    let mut ids = Vec::new();
+    for _ in 0..2 {
+        let response = provider
+            .complete(request.clone())
+            .await
+            .expect("complete should succeed");
+        ids.extend(response.output.iter().filter_map(|item| match item {
+            OutputItem::ToolCall(call) => Some(call.id.clone()),
+            _ => None,
+        }));
+    }
+
+    assert_eq!(ids, vec!["tool_call_0", "tool_call_1"]);
+}
+
+#[tokio::test]
+async fn stream_emits_deltas_and_gemini_response_complete() {
+    let transport = Arc::new(FakeTransport::default());
+    let provider = provider_with_key(transport);
+    let request = ModelRequest::new("gemini-2.0-flash", vec![Message::new(Role::User, "stream")]);
+
+    let mut stream = provider
+        .stream(request)
+        .await
+        .expect("stream should succeed");
+    let mut deltas = String::new();
+    let mut interim_usage = None;
+    let mut completed = None;
+    while let Some(item) = futures_util::StreamExt::next(&mut stream).await {
+        match item.expect("stream event should be ok") {
+            StreamEvent::TextDelta(delta) => deltas.push_str(&delta),
+            StreamEvent::Usage(usage) => interim_usage = Some(usage),
+            StreamEvent::ResponseComplete(response) => completed = Some(response),
+            _ => {}
+        }
+    }
+
+    let completed = completed.expect("stream should complete");
+    assert_eq!(deltas, "hello");
+    assert_eq!(completed.provider, ProviderId::Gemini);
+    assert_eq!(completed.stop_reason, StopReason::EndTurn);
+    assert_eq!(completed.usage.total_tokens, 3);
+    assert_eq!(completed.usage.cached_input_tokens, 1);
+    assert_eq!(interim_usage, Some(completed.usage));
+}
+
+#[tokio::test]
+async fn missing_gemini_credentials_returns_authentication_error() {
+    let credentials = Arc::new(SecureCredentialManager::new());
+    let transport = Arc::new(FakeTransport::default());
+    let provider = GeminiProvider::new(credentials, transport);
+    let request = ModelRequest::new("gemini-2.0-flash", vec![Message::new(Role::User, "hi")]);
+
+    let error = provider
+        .complete(request)
+        .await
+        .expect_err("missing gemini credentials should fail");
+    assert_eq!(error.kind, fprovider::ProviderErrorKind::Authentication);
+    assert_eq!(error.message, "no Gemini credentials configured");
+}
+
+#[derive(Debug)]
+struct RejectingTransport;
+
+impl GeminiTransport for RejectingTransport {
+    fn generate_content<'a>(
+        &'a self,
+        _request: GeminiRequest,
+        _api_key: SecretString,
+    ) -> ProviderFuture<'a, Result<GeminiResponse, ProviderError>> {
+        Box::pin(async { Err(ProviderError::other("not used")) })
+    }
+
+    fn stream_generate_content<'a>(
+        &'a self,
+        _request: GeminiRequest,
+        _api_key: SecretString,
+    ) -> ProviderFuture<'a, Result<GeminiChunkStream<'a>, ProviderError>> {
+        Box::pin(async { Err(ProviderError::other("not used")) })
+    }
+
+    fn list_models<'a>(
+        &'a self,
+        _api_key: SecretString,
+    ) -> ProviderFuture<'a, Result<Vec<ModelInfo>, ProviderError>> {
+        Box::pin(async {
+            Err(ProviderError::authentication(
+                "http 403 Forbidden: API key not valid",
+            ))
+        })
+    }
+}
+
+#[tokio::test]
+async fn health_reports_rejected_keys_as_unhealthy() {
+    let credentials = Arc::new(SecureCredentialManager::new());
+    credentials
+        .set_gemini_api_key("gemini-revoked-key")
+        .expect("key should set");
+    let provider = GeminiProvider::new(credentials, Arc::new(RejectingTransport));
+
+    let health = provider.health().await;
+    assert_eq!(health.status, HealthStatus::Unhealthy);
+    assert!(!health.is_ready());
+}
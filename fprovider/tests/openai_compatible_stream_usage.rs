@@ -0,0 +1,162 @@
+#![cfg(feature = "provider-openai")]
+
+use std::sync::{Arc, Mutex};
+
+use fprovider::{
+    Message, ModelProvider, ModelRequest, Role, SecureCredentialManager, StreamEvent, TokenUsage,
+};
+use futures_util::StreamExt;
+use reqwest::Client;
+use serde_json::Value;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+/// Minimal chat completions server streaming SSE. Like the real endpoints, the final usage chunk is
+/// only sent when the request asked for it with `stream_options.include_usage`.
+async fn spawn_sse_server() -> (String, Arc<Mutex<Vec<Value>>>) {
+    let listener = TcpListener::bind("127.0.0.1:0")
+        .await
+        .expect("listener should bind");
+    let base_url = format!("http://{}/v1", listener.local_addr().expect("local addr"));
+    let bodies = Arc::new(Mutex::new(Vec::new()));
+
+    let server_bodies = Arc::clone(&bodies);
+    tokio::spawn(async move {
+        while let Ok((mut socket, _)) = listener.accept().await {
+            let mut raw = Vec::new();
+            let mut buffer = [0_u8; 4096];
+            let content_length = loop {
+                let read = socket.read(&mut buffer).await.expect("request should read");
+                raw.extend_from_slice(&buffer[..read]);
+                if let Some(end) = raw.windows(4).position(|window| window == b"\r\n\r\n") {
+                    let head = String::from_utf8_lossy(&raw[..end]).to_string();
+                    raw.drain(..end + 4);
+                    break head
+                        .lines()
+                        .find_map(|line| {
+                            let (name, value) = line.split_once(':')?;
+                            name.eq_ignore_ascii_case("content-length")
+                                .then(|| value.trim().parse::<usize>().ok())?
+                        })
+                        .unwrap_or(0);
+                }
+            };
+            while raw.len() < content_length {
+                let read = socket.read(&mut buffer).await.expect("body should read");
+                raw.extend_from_slice(&buffer[..read]);
+            }
+
+            let request: Value = serde_json::from_slice(&raw).expect("request body should be json");
+            let include_usage = request["stream_options"]["include_usage"] == Value::Bool(true);
+            server_bodies.lock().expect("bodies lock").push(request);
+
+            let mut body = String::from(
+                "data: {\"model\":\"m\",\"choices\":[{\"delta\":{\"content\":\"hi\"},\"finish_reason\":null}]}\n\n\
+                 data: {\"model\":\"m\",\"choices\":[{\"delta\":{},\"finish_reason\":\"stop\"}]}\n\n",
+            );
+            if include_usage {
+                body.push_str(
+                    "data: {\"model\":\"m\",\"choices\":[],\"usage\":{\"prompt_tokens\":5,\"completion_tokens\":2,\"total_tokens\":7}}\n\n",
+                );
+            }
+            body.push_str("data: [DONE]\n\n");
+
+            let response = format!(
+                "HTTP/1.1 200 OK\r\ncontent-type: text/event-stream\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{body}",
+                body.len()
+            );
+            socket
+                .write_all(response.as_bytes())
+                .await
+                .expect("response should write");
+        }
+    });
+
+    (base_url, bodies)
+}
+
+async fn streamed_usage(provider: &dyn ModelProvider) -> TokenUsage {
+    let request = ModelRequest::new("m", vec![Message::new(Role::User, "hi")]);
+    let mut stream = provider.stream(request).await.expect("stream should open");
+
+    let mut usage = None;
+    while let Some(event) = stream.next().await {
+        if let StreamEvent::ResponseComplete(response) = event.expect("event should be ok") {
+            usage = Some(response.usage);
+        }
+    }
+    usage.expect("stream should complete")
+}
+
+fn assert_usage_requested(usage: TokenUsage, bodies: &Mutex<Vec<Value>>) {
+    assert_eq!(usage.input_tokens, 5);
+    assert_eq!(usage.output_tokens, 2);
+    assert_eq!(usage.total_tokens, 7);
+    let bodies = bodies.lock().expect("bodies lock");
+    assert_eq!(
+        bodies[0]["stream_options"]["include_usage"],
+        Value::Bool(true)
+    );
+}
+
+#[cfg(feature = "provider-anthropic")]
+#[tokio::test]
+async fn anthropic_stream_ends_with_usage() {
+    use fprovider::adapters::anthropic::AnthropicProvider;
+
+    let (base_url, bodies) = spawn_sse_server().await;
+    let credentials = Arc::new(SecureCredentialManager::new());
+    credentials
+        .set_anthropic_api_key("sk-ant-stream")
+        .expect("key should set");
+    let transport =
+        AnthropicProvider::default_http_transport(Client::new()).with_base_url(base_url);
+    let provider = AnthropicProvider::new(credentials, Arc::new(transport));
+
+    assert_usage_requested(streamed_usage(&provider).await, &bodies);
+}
+
+#[cfg(feature = "provider-ollama")]
+#[tokio::test]
+async fn ollama_stream_ends_with_usage() {
+    use fprovider::adapters::ollama::OllamaProvider;
+
+    let (base_url, bodies) = spawn_sse_server().await;
+    let transport = OllamaProvider::default_http_transport(Client::new()).with_base_url(base_url);
+    let provider = OllamaProvider::new(Arc::new(transport));
+
+    assert_usage_requested(streamed_usage(&provider).await, &bodies);
+}
+
+#[cfg(feature = "provider-opencode-zen")]
+#[tokio::test]
+async fn opencode_zen_stream_ends_with_usage() {
+    use fprovider::adapters::opencode_zen::OpenCodeZenProvider;
+
+    let (base_url, bodies) = spawn_sse_server().await;
+    let credentials = Arc::new(SecureCredentialManager::new());
+    credentials
+        .set_opencode_zen_api_key("zen-stream")
+        .expect("key should set");
+    let transport =
+        OpenCodeZenProvider::default_http_transport(Client::new()).with_base_url(base_url);
+    let provider = OpenCodeZenProvider::new(credentials, Arc::new(transport));
+
+    assert_usage_requested(streamed_usage(&provider).await, &bodies);
+}
+
+#[tokio::test]
+async fn azure_stream_ends_with_usage() {
+    use fprovider::adapters::openai::{AzureOpenAiConfig, OpenAiHttpTransport, OpenAiProvider};
+
+    let (base_url, bodies) = spawn_sse_server().await;
+    let endpoint = base_url.trim_end_matches("/v1").to_string();
+    let credentials = Arc::new(SecureCredentialManager::new());
+    credentials
+        .set_openai_api_key("sk-azure-stream")
+        .expect("key should set");
+    let transport = OpenAiHttpTransport::azure(Client::new(), AzureOpenAiConfig::new(endpoint));
+    let provider = OpenAiProvider::new(credentials, Arc::new(transport));
+
+    assert_usage_requested(streamed_usage(&provider).await, &bodies);
+}
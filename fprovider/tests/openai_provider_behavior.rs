@@ -7,8 +7,9 @@ use fprovider::adapters::openai::{
     OpenAiStreamChunk, OpenAiTransport,
 };
 use fprovider::{
-    Message, ModelProvider, ModelRequest, ProviderError, ProviderFuture, ProviderId, Role,
-    SecureCredentialManager, StopReason, ToolDefinition, ToolResult,
+    ApiKeyPool, KeySelectionStrategy, Message, ModelProvider, ModelRequest, ProviderError,
+    ProviderFuture, ProviderId, Role, SecureCredentialManager, StopReason, ToolDefinition,
+    ToolResult,
 };
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -33,9 +34,13 @@ impl OpenAiTransport for FakeTransport {
                 OpenAiAuth::BrowserSession(value) => {
                     CapturedAuth("browser_session", value.expose().to_string())
                 }
+                OpenAiAuth::BearerToken(value) => {
+                    CapturedAuth("bearer_token", value.expose().to_string())
+                }
             });
 
             Ok(OpenAiResponse {
+                id: None,
                 model: "gpt-4o-mini".to_string(),
                 message: fprovider::adapters::openai::OpenAiAssistantMessage {
                     content: "hello world".to_string(),
@@ -50,6 +55,7 @@ impl OpenAiTransport for FakeTransport {
                     prompt_tokens: 7,
                     completion_tokens: 3,
                     total_tokens: 10,
+                    cached_tokens: 0,
                 },
             })
         })
@@ -67,6 +73,9 @@ impl OpenAiTransport for FakeTransport {
                 OpenAiAuth::BrowserSession(value) => {
                     CapturedAuth("browser_session", value.expose().to_string())
                 }
+                OpenAiAuth::BearerToken(value) => {
+                    CapturedAuth("bearer_token", value.expose().to_string())
+                }
             });
 
             let output = futures_util::stream::iter(
@@ -167,3 +176,80 @@ async fn missing_openai_credentials_returns_auth_error() {
     assert_eq!(error.kind, fprovider::ProviderErrorKind::Authentication);
     assert_eq!(error.message, "no OpenAI API key configured");
 }
+
+/// Rate-limits `limited_key` and otherwise delegates to [`FakeTransport`].
+#[derive(Debug, Default)]
+struct RateLimitingTransport {
+    limited_key: &'static str,
+    inner: FakeTransport,
+}
+
+impl OpenAiTransport for RateLimitingTransport {
+    fn complete<'a>(
+        &'a self,
+        request: OpenAiRequest,
+        auth: OpenAiAuth,
+    ) -> ProviderFuture<'a, Result<OpenAiResponse, ProviderError>> {
+        if matches!(&auth, OpenAiAuth::ApiKey(key) if key.expose() == self.limited_key) {
+            return Box::pin(async {
+                Err(ProviderError::rate_limited("slow down")
+                    .with_retry_after(std::time::Duration::from_secs(60)))
+            });
+        }
+        self.inner.complete(request, auth)
+    }
+
+    fn stream<'a>(
+        &'a self,
+        request: OpenAiRequest,
+        auth: OpenAiAuth,
+    ) -> ProviderFuture<'a, Result<OpenAiChunkStream<'a>, ProviderError>> {
+        self.inner.stream(request, auth)
+    }
+}
+
+#[tokio::test]
+async fn rate_limited_pooled_key_is_benched_for_later_calls() {
+    let credentials = Arc::new(SecureCredentialManager::new());
+    credentials
+        .set_api_key_pool(
+            ProviderId::OpenAi,
+            ApiKeyPool::new(KeySelectionStrategy::RoundRobin)
+                .with_key("sk-a")
+                .with_key("sk-b"),
+        )
+        .expect("pool should register");
+
+    let transport = Arc::new(RateLimitingTransport {
+        limited_key: "sk-a",
+        ..RateLimitingTransport::default()
+    });
+    let provider = OpenAiProvider::new(credentials.clone(), transport.clone());
+    let request = ModelRequest::new("gpt-4o-mini", vec![Message::new(Role::User, "hi")]);
+
+    let error = provider
+        .complete(request.clone())
+        .await
+        .expect_err("first key should be rate limited");
+    assert_eq!(error.retry_after, Some(std::time::Duration::from_secs(60)));
+
+    for _ in 0..2 {
+        provider
+            .complete(request.clone())
+            .await
+            .expect("remaining key should succeed");
+        let auth = transport
+            .inner
+            .captured_auth
+            .lock()
+            .expect("auth lock")
+            .clone();
+        assert_eq!(auth, Some(CapturedAuth("api_key", "sk-b".to_string())));
+    }
+
+    let status = credentials
+        .api_key_pool_status(ProviderId::OpenAi)
+        .expect("status should work")
+        .expect("pool should exist");
+    assert!(status[0].benched_until.is_some());
+}
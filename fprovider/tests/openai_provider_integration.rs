@@ -24,6 +24,7 @@ impl OpenAiTransport for IntegrationFakeTransport {
         Box::pin(async move {
             *self.seen_auth.lock().expect("auth lock") = Some(auth);
             Ok(OpenAiResponse {
+                id: None,
                 model: "gpt-4o-mini".to_string(),
                 message: fprovider::adapters::openai::OpenAiAssistantMessage {
                     content: "integration-ok".to_string(),
@@ -34,6 +35,7 @@ impl OpenAiTransport for IntegrationFakeTransport {
                     prompt_tokens: 1,
                     completion_tokens: 1,
                     total_tokens: 2,
+                    cached_tokens: 0,
                 },
             })
         })
@@ -0,0 +1,318 @@
+#![cfg(feature = "provider-openai")]
+
+use std::sync::{Arc, Mutex};
+
+use fprovider::adapters::openai::{
+    OpenAiAuth, OpenAiFinishReason, OpenAiMessage, OpenAiRequest, OpenAiResponsesHttpTransport,
+    OpenAiRole, OpenAiStreamChunk, OpenAiTransport,
+};
+use fprovider::{ProviderErrorKind, ProviderId, ResponseContinuation, SecretString};
+use futures_util::StreamExt;
+use serde_json::{Value, json};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+#[derive(Debug, Default)]
+struct ServerLog {
+    paths: Vec<String>,
+    authorization: Vec<String>,
+    bodies: Vec<Value>,
+}
+
+/// Minimal HTTP/1.1 server answering every request with a fixed status, content type and body.
+async fn spawn_server(
+    status: &'static str,
+    content_type: &'static str,
+    body: String,
+) -> (String, Arc<Mutex<ServerLog>>) {
+    let listener = TcpListener::bind("127.0.0.1:0")
+        .await
+        .expect("listener should bind");
+    let base_url = format!("http://{}/v1", listener.local_addr().expect("local addr"));
+    let log = Arc::new(Mutex::new(ServerLog::default()));
+
+    let server_log = Arc::clone(&log);
+    tokio::spawn(async move {
+        while let Ok((mut socket, _)) = listener.accept().await {
+            let mut raw = Vec::new();
+            let mut buffer = [0_u8; 4096];
+            let (head, content_length) = loop {
+                let read = socket.read(&mut buffer).await.expect("request should read");
+                raw.extend_from_slice(&buffer[..read]);
+                if let Some(end) = raw.windows(4).position(|window| window == b"\r\n\r\n") {
+                    let head = String::from_utf8_lossy(&raw[..end]).to_string();
+                    raw.drain(..end + 4);
+                    let content_length = head
+                        .lines()
+                        .find_map(|line| {
+                            let (name, value) = line.split_once(':')?;
+                            name.eq_ignore_ascii_case("content-length")
+                                .then(|| value.trim().parse::<usize>().ok())?
+                        })
+                        .unwrap_or(0);
+                    break (head, content_length);
+                }
+            };
+            while raw.len() < content_length {
+                let read = socket.read(&mut buffer).await.expect("body should read");
+                raw.extend_from_slice(&buffer[..read]);
+            }
+
+            {
+                let mut log = server_log.lock().expect("log lock");
+                let request_line = head.lines().next().unwrap_or_default();
+                log.paths.push(
+                    request_line
+                        .split(' ')
+                        .nth(1)
+                        .unwrap_or_default()
+                        .to_string(),
+                );
+                log.authorization.extend(head.lines().find_map(|line| {
+                    let (name, value) = line.split_once(':')?;
+                    name.eq_ignore_ascii_case("authorization")
+                        .then(|| value.trim().to_string())
+                }));
+                if !raw.is_empty() {
+                    log.bodies
+                        .push(serde_json::from_slice(&raw).expect("request body should be json"));
+                }
+            }
+
+            let response = format!(
+                "HTTP/1.1 {status}\r\ncontent-type: {content_type}\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{body}",
+                body.len()
+            );
+            socket
+                .write_all(response.as_bytes())
+                .await
+                .expect("response should write");
+        }
+    });
+
+    (base_url, log)
+}
+
+fn request() -> OpenAiRequest {
+    OpenAiRequest {
+        model: "gpt-4o-mini".to_string(),
+        messages: vec![
+            OpenAiMessage {
+                role: OpenAiRole::User,
+                content: "first".to_string(),
+                tool_call_id: None,
+            },
+            OpenAiMessage {
+                role: OpenAiRole::Assistant,
+                content: "ok".to_string(),
+                tool_call_id: None,
+            },
+            OpenAiMessage {
+                role: OpenAiRole::User,
+                content: "second".to_string(),
+                tool_call_id: None,
+            },
+        ],
+        tools: Vec::new(),
+        temperature: None,
+        max_tokens: Some(64),
+        stream: false,
+        continuation: Some(ResponseContinuation::new("resp_0", 2)),
+    }
+}
+
+fn api_key() -> OpenAiAuth {
+    OpenAiAuth::ApiKey(SecretString::new("sk-http"))
+}
+
+#[tokio::test]
+async fn complete_posts_full_history_to_responses_endpoint() {
+    let body = json!({
+        "id": "resp_1",
+        "model": "gpt-4o-mini",
+        "status": "completed",
+        "output": [
+            {
+                "type": "message",
+                "role": "assistant",
+                "content": [{ "type": "output_text", "text": "hello" }],
+            },
+            {
+                "type": "function_call",
+                "call_id": "call_1",
+                "name": "lookup",
+                "arguments": "{\"q\":\"x\"}",
+            },
+        ],
+        "usage": { "input_tokens": 5, "output_tokens": 3, "total_tokens": 8 },
+    });
+    let (base_url, log) = spawn_server("200 OK", "application/json", body.to_string()).await;
+    let transport =
+        OpenAiResponsesHttpTransport::new(reqwest::Client::new()).with_base_url(base_url);
+
+    let response = transport
+        .complete(request(), api_key())
+        .await
+        .expect("complete should succeed");
+
+    assert_eq!(response.id.as_deref(), Some("resp_1"));
+    assert_eq!(response.message.content, "hello");
+    assert_eq!(response.message.tool_calls.len(), 1);
+    assert_eq!(response.message.tool_calls[0].name, "lookup");
+    assert_eq!(response.finish_reason, OpenAiFinishReason::Stop);
+    assert_eq!(response.usage.total_tokens, 8);
+
+    let log = log.lock().expect("log lock");
+    assert_eq!(log.paths, vec!["/v1/responses".to_string()]);
+    assert_eq!(log.authorization, vec!["Bearer sk-http".to_string()]);
+    let payload = &log.bodies[0];
+    assert_eq!(payload["store"], false);
+    assert_eq!(payload["max_output_tokens"], 64);
+    assert!(payload.get("type").is_none());
+    assert!(payload.get("stream").is_none());
+    assert!(payload.get("previous_response_id").is_none());
+    assert_eq!(payload["input"].as_array().map(Vec::len), Some(3));
+}
+
+#[tokio::test]
+async fn stream_reads_sse_events_through_shared_accumulator() {
+    let events = [
+        json!({ "type": "response.created", "response": { "id": "resp_2" } }),
+        json!({ "type": "response.output_text.delta", "delta": "hel" }),
+        json!({ "type": "response.output_text.delta", "delta": "lo" }),
+        json!({
+            "type": "response.completed",
+            "response": {
+                "id": "resp_2",
+                "model": "gpt-4o-mini",
+                "status": "completed",
+                "output": [],
+                "usage": { "input_tokens": 2, "output_tokens": 1, "total_tokens": 3 },
+            },
+        }),
+    ];
+    let body = events
+        .iter()
+        .map(|event| {
+            format!(
+                "event: {}\ndata: {event}\n\n",
+                event["type"].as_str().unwrap_or_default()
+            )
+        })
+        .collect::<String>();
+    let (base_url, log) = spawn_server("200 OK", "text/event-stream", body).await;
+    let transport =
+        OpenAiResponsesHttpTransport::new(reqwest::Client::new()).with_base_url(base_url);
+
+    let mut stream = transport
+        .stream(request(), api_key())
+        .await
+        .expect("stream should start");
+    let mut chunks = Vec::new();
+    while let Some(chunk) = stream.next().await {
+        chunks.push(chunk.expect("chunk should be ok"));
+    }
+
+    assert_eq!(chunks[0], OpenAiStreamChunk::TextDelta("hel".to_string()));
+    assert_eq!(chunks[1], OpenAiStreamChunk::TextDelta("lo".to_string()));
+    let Some(OpenAiStreamChunk::ResponseComplete(response)) = chunks.last() else {
+        panic!("stream should end with a completed response");
+    };
+    assert_eq!(response.id.as_deref(), Some("resp_2"));
+    assert_eq!(response.message.content, "hello");
+    assert_eq!(response.usage.total_tokens, 3);
+
+    let log = log.lock().expect("log lock");
+    assert_eq!(log.bodies[0]["stream"], true);
+}
+
+#[tokio::test]
+async fn stream_maps_inline_sse_error_events() {
+    let event = json!({
+        "type": "error",
+        "code": "rate_limit_exceeded",
+        "message": "Slow down",
+    });
+    let (base_url, _log) =
+        spawn_server("200 OK", "text/event-stream", format!("data: {event}\n\n")).await;
+    let transport =
+        OpenAiResponsesHttpTransport::new(reqwest::Client::new()).with_base_url(base_url);
+
+    let mut stream = transport
+        .stream(request(), api_key())
+        .await
+        .expect("stream should start");
+    let error = stream
+        .next()
+        .await
+        .expect("stream should yield an item")
+        .expect_err("error event should surface as an error");
+
+    assert_eq!(error.kind, ProviderErrorKind::RateLimited);
+    assert_eq!(error.message, "Slow down");
+}
+
+#[tokio::test]
+async fn complete_maps_http_error_status() {
+    let body = json!({ "error": { "message": "Too many requests" } });
+    let (base_url, _log) = spawn_server(
+        "429 Too Many Requests",
+        "application/json",
+        body.to_string(),
+    )
+    .await;
+    let transport =
+        OpenAiResponsesHttpTransport::new(reqwest::Client::new()).with_base_url(base_url);
+
+    let error = transport
+        .complete(request(), api_key())
+        .await
+        .expect_err("429 should fail");
+
+    assert_eq!(error.kind, ProviderErrorKind::RateLimited);
+    assert_eq!(error.message, "Too many requests");
+}
+
+#[tokio::test]
+async fn list_models_reads_openai_and_anthropic_shaped_entries() {
+    let body = json!({
+        "object": "list",
+        "data": [
+            {
+                "id": "gpt-4o",
+                "object": "model",
+                "created": 1_715_367_049,
+                "owned_by": "system",
+            },
+            {
+                "id": "claude-sonnet-4-0",
+                "type": "model",
+                "display_name": "Claude Sonnet 4",
+                "created_at": "2025-02-19T00:00:00Z",
+            },
+        ],
+        "has_more": false,
+    });
+    let (base_url, log) = spawn_server("200 OK", "application/json", body.to_string()).await;
+    let transport =
+        OpenAiResponsesHttpTransport::new(reqwest::Client::new()).with_base_url(base_url);
+
+    let models = transport
+        .list_models(api_key())
+        .await
+        .expect("listing should succeed");
+
+    assert_eq!(models.len(), 2);
+    assert_eq!(models[0].id, "claude-sonnet-4-0");
+    assert_eq!(models[0].display_name.as_deref(), Some("Claude Sonnet 4"));
+    assert_eq!(models[0].created, Some(1_739_923_200));
+    assert_eq!(models[1].id, "gpt-4o");
+    assert_eq!(models[1].provider, ProviderId::OpenAi);
+    assert_eq!(models[1].owner.as_deref(), Some("system"));
+    assert_eq!(models[1].created, Some(1_715_367_049));
+    assert_eq!(models[1].context_window, None);
+
+    let log = log.lock().expect("log lock");
+    assert_eq!(log.paths, vec!["/v1/models".to_string()]);
+    assert_eq!(log.authorization, vec!["Bearer sk-http".to_string()]);
+}
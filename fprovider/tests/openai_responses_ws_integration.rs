@@ -0,0 +1,329 @@
+#![cfg(feature = "provider-openai")]
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use fprovider::adapters::openai::{
+    OpenAiAuth, OpenAiMessage, OpenAiRequest, OpenAiResponsesWebSocketTransport, OpenAiRole,
+    OpenAiTransport,
+};
+use fprovider::{ResponseContinuation, SecretString};
+use futures_util::{SinkExt, StreamExt};
+use serde_json::{Value, json};
+use tokio::net::TcpListener;
+use tokio_tungstenite::tungstenite::Message as WsMessage;
+
+#[derive(Debug, Default)]
+struct ServerLog {
+    payloads: Vec<Value>,
+    connections: usize,
+}
+
+/// Minimal Responses WebSocket server that completes every request with `resp_<n>`.
+///
+/// When `reject_previous_ids` is set, requests carrying `previous_response_id` get a
+/// `previous_response_not_found` error instead, mimicking an evicted response. Responses are
+/// held back until at least `hold_until` requests have arrived across all connections.
+async fn spawn_server(
+    reject_previous_ids: bool,
+    hold_until: usize,
+) -> (String, Arc<Mutex<ServerLog>>) {
+    let listener = TcpListener::bind("127.0.0.1:0")
+        .await
+        .expect("listener should bind");
+    let url = format!("ws://{}", listener.local_addr().expect("local addr"));
+    let log = Arc::new(Mutex::new(ServerLog::default()));
+
+    let server_log = Arc::clone(&log);
+    tokio::spawn(async move {
+        while let Ok((stream, _)) = listener.accept().await {
+            let log = Arc::clone(&server_log);
+            tokio::spawn(async move {
+                let mut socket = tokio_tungstenite::accept_async(stream)
+                    .await
+                    .expect("handshake should succeed");
+                log.lock().expect("log lock").connections += 1;
+
+                while let Some(Ok(message)) = socket.next().await {
+                    // Pings are answered by tungstenite itself.
+                    let WsMessage::Text(text) = message else {
+                        continue;
+                    };
+                    let payload: Value = serde_json::from_str(&text).expect("payload json");
+                    let count = {
+                        let mut log = log.lock().expect("log lock");
+                        log.payloads.push(payload.clone());
+                        log.payloads.len()
+                    };
+                    while log.lock().expect("log lock").payloads.len() < hold_until {
+                        tokio::time::sleep(Duration::from_millis(5)).await;
+                    }
+
+                    let event =
+                        if reject_previous_ids && payload.get("previous_response_id").is_some() {
+                            json!({
+                                "type": "error",
+                                "status": 400,
+                                "error": {
+                                    "code": "previous_response_not_found",
+                                    "message": "Previous response not found",
+                                },
+                            })
+                        } else {
+                            json!({
+                                "type": "response.completed",
+                                "response": {
+                                    "id": format!("resp_{count}"),
+                                    "model": "gpt-4o-mini",
+                                    "status": "completed",
+                                    "output": [{
+                                        "type": "message",
+                                        "role": "assistant",
+                                        "content": [{ "type": "output_text", "text": "ok" }],
+                                    }],
+                                },
+                            })
+                        };
+
+                    socket
+                        .send(WsMessage::Text(event.to_string().into()))
+                        .await
+                        .expect("event should send");
+                }
+            });
+        }
+    });
+
+    (url, log)
+}
+
+fn message(role: OpenAiRole, content: &str) -> OpenAiMessage {
+    OpenAiMessage {
+        role,
+        content: content.to_string(),
+        tool_call_id: None,
+    }
+}
+
+fn request(
+    messages: Vec<OpenAiMessage>,
+    continuation: Option<ResponseContinuation>,
+) -> OpenAiRequest {
+    OpenAiRequest {
+        model: "gpt-4o-mini".to_string(),
+        messages,
+        tools: Vec::new(),
+        temperature: None,
+        max_tokens: None,
+        stream: false,
+        continuation,
+    }
+}
+
+fn follow_up(continuation: Option<ResponseContinuation>) -> OpenAiRequest {
+    request(
+        vec![
+            message(OpenAiRole::User, "first"),
+            message(OpenAiRole::Assistant, "ok"),
+            message(OpenAiRole::User, "second"),
+        ],
+        continuation,
+    )
+}
+
+fn api_key(value: &str) -> OpenAiAuth {
+    OpenAiAuth::ApiKey(SecretString::new(value))
+}
+
+#[tokio::test]
+async fn continuation_sends_only_new_input_with_previous_response_id() {
+    let (url, log) = spawn_server(false, 0).await;
+    let transport = OpenAiResponsesWebSocketTransport::new().with_url(url);
+
+    let first = transport
+        .complete(
+            request(vec![message(OpenAiRole::User, "first")], None),
+            api_key("sk-a"),
+        )
+        .await
+        .expect("first request should succeed");
+    assert_eq!(first.id.as_deref(), Some("resp_1"));
+
+    let second = transport
+        .complete(
+            follow_up(Some(ResponseContinuation::new("resp_1", 2))),
+            api_key("sk-a"),
+        )
+        .await
+        .expect("continued request should succeed");
+    assert_eq!(second.id.as_deref(), Some("resp_2"));
+
+    let log = log.lock().expect("log lock");
+    let payload = &log.payloads[1];
+    assert_eq!(payload["previous_response_id"], "resp_1");
+    assert_eq!(payload["input"].as_array().map(Vec::len), Some(1));
+    assert_eq!(payload["input"][0]["content"][0]["text"], "second");
+}
+
+#[tokio::test]
+async fn expired_previous_response_falls_back_to_full_resend() {
+    let (url, log) = spawn_server(true, 0).await;
+    let transport = OpenAiResponsesWebSocketTransport::new().with_url(url);
+
+    transport
+        .complete(
+            request(vec![message(OpenAiRole::User, "first")], None),
+            api_key("sk-a"),
+        )
+        .await
+        .expect("first request should succeed");
+
+    let response = transport
+        .complete(
+            follow_up(Some(ResponseContinuation::new("resp_1", 2))),
+            api_key("sk-a"),
+        )
+        .await
+        .expect("request should fall back to full resend");
+    assert_eq!(response.message.content, "ok");
+
+    let log = log.lock().expect("log lock");
+    assert_eq!(log.payloads.len(), 3);
+    assert_eq!(log.payloads[1]["previous_response_id"], "resp_1");
+    assert!(log.payloads[2].get("previous_response_id").is_none());
+    assert_eq!(log.payloads[2]["input"].as_array().map(Vec::len), Some(3));
+}
+
+#[tokio::test]
+async fn reconnect_drops_continuation_and_resends_full_history() {
+    let (url, log) = spawn_server(false, 0).await;
+    let transport = OpenAiResponsesWebSocketTransport::new().with_url(url);
+
+    transport
+        .complete(
+            request(vec![message(OpenAiRole::User, "first")], None),
+            api_key("sk-a"),
+        )
+        .await
+        .expect("first request should succeed");
+
+    // A different credential leases from its own pool, which has to open a new socket.
+    transport
+        .complete(
+            follow_up(Some(ResponseContinuation::new("resp_1", 2))),
+            api_key("sk-b"),
+        )
+        .await
+        .expect("request after reconnect should succeed");
+
+    let third = transport
+        .complete(
+            request(
+                vec![
+                    message(OpenAiRole::User, "first"),
+                    message(OpenAiRole::Assistant, "ok"),
+                    message(OpenAiRole::User, "second"),
+                    message(OpenAiRole::Assistant, "ok"),
+                    message(OpenAiRole::User, "third"),
+                ],
+                Some(ResponseContinuation::new("resp_2", 4)),
+            ),
+            api_key("sk-b"),
+        )
+        .await
+        .expect("continuation on the new connection should succeed");
+    assert_eq!(third.id.as_deref(), Some("resp_3"));
+
+    let log = log.lock().expect("log lock");
+    assert_eq!(log.connections, 2);
+    assert!(log.payloads[1].get("previous_response_id").is_none());
+    assert_eq!(log.payloads[1]["input"].as_array().map(Vec::len), Some(3));
+    assert_eq!(log.payloads[2]["previous_response_id"], "resp_2");
+    assert_eq!(log.payloads[2]["input"].as_array().map(Vec::len), Some(1));
+}
+
+#[tokio::test]
+async fn concurrent_requests_for_one_key_run_on_parallel_connections() {
+    // Neither response is sent until both requests arrived, so serialized requests would hang.
+    let (url, log) = spawn_server(false, 2).await;
+    let transport = OpenAiResponsesWebSocketTransport::new()
+        .with_url(url)
+        .with_pool_size(2);
+
+    let (first, second) = tokio::time::timeout(Duration::from_secs(5), async {
+        tokio::join!(
+            transport.complete(
+                request(vec![message(OpenAiRole::User, "one")], None),
+                api_key("sk-a")
+            ),
+            transport.complete(
+                request(vec![message(OpenAiRole::User, "two")], None),
+                api_key("sk-a")
+            ),
+        )
+    })
+    .await
+    .expect("requests should not serialize on one socket");
+    first.expect("first request should succeed");
+    second.expect("second request should succeed");
+
+    transport
+        .complete(
+            request(vec![message(OpenAiRole::User, "three")], None),
+            api_key("sk-a"),
+        )
+        .await
+        .expect("pooled connection should be reused");
+
+    assert_eq!(log.lock().expect("log lock").connections, 2);
+}
+
+#[tokio::test]
+async fn idle_connections_are_pinged_before_reuse() {
+    let (url, log) = spawn_server(false, 0).await;
+    let transport = OpenAiResponsesWebSocketTransport::new()
+        .with_url(url)
+        .with_ping_interval(Duration::ZERO);
+
+    for prompt in ["first", "second"] {
+        transport
+            .complete(
+                request(vec![message(OpenAiRole::User, prompt)], None),
+                api_key("sk-a"),
+            )
+            .await
+            .expect("request should succeed");
+    }
+
+    assert_eq!(log.lock().expect("log lock").connections, 1);
+}
+
+#[tokio::test]
+async fn idle_connections_are_capped_across_credentials() {
+    let (url, log) = spawn_server(false, 0).await;
+    let transport = OpenAiResponsesWebSocketTransport::new()
+        .with_url(url)
+        .with_max_idle_connections(1);
+
+    // A rotated-away key's idle connection is evicted once another key's connection is released.
+    for key in ["sk-a", "sk-b", "sk-a"] {
+        transport
+            .complete(
+                request(vec![message(OpenAiRole::User, key)], None),
+                api_key(key),
+            )
+            .await
+            .expect("request should succeed");
+    }
+
+    assert_eq!(log.lock().expect("log lock").connections, 3);
+
+    transport
+        .complete(
+            request(vec![message(OpenAiRole::User, "again")], None),
+            api_key("sk-a"),
+        )
+        .await
+        .expect("request should succeed");
+    assert_eq!(log.lock().expect("log lock").connections, 3);
+}
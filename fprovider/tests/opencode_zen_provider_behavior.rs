@@ -32,9 +32,11 @@ impl OpenAiTransport for FakeTransport {
             *self.captured_auth.lock().expect("auth lock") = Some(match auth {
                 OpenAiAuth::ApiKey(value) => CapturedAuth(value.expose().to_string()),
                 OpenAiAuth::BrowserSession(value) => CapturedAuth(value.expose().to_string()),
+                OpenAiAuth::BearerToken(value) => CapturedAuth(value.expose().to_string()),
             });
 
             Ok(OpenAiResponse {
+                id: None,
                 model: "kimi-k2.5".to_string(),
                 message: OpenAiAssistantMessage {
                     content: "zen-ok".to_string(),
@@ -49,6 +51,7 @@ impl OpenAiTransport for FakeTransport {
                     prompt_tokens: 2,
                     completion_tokens: 3,
                     total_tokens: 5,
+                    cached_tokens: 0,
                 },
             })
         })
@@ -64,12 +67,14 @@ impl OpenAiTransport for FakeTransport {
             *self.captured_auth.lock().expect("auth lock") = Some(match auth {
                 OpenAiAuth::ApiKey(value) => CapturedAuth(value.expose().to_string()),
                 OpenAiAuth::BrowserSession(value) => CapturedAuth(value.expose().to_string()),
+                OpenAiAuth::BearerToken(value) => CapturedAuth(value.expose().to_string()),
             });
 
             let output = futures_util::stream::iter(
                 vec![
                     OpenAiStreamChunk::TextDelta("hello".to_string()),
                     OpenAiStreamChunk::ResponseComplete(OpenAiResponse {
+                        id: None,
                         model: "kimi-k2.5".to_string(),
                         message: OpenAiAssistantMessage {
                             content: "hello".to_string(),
@@ -80,6 +85,7 @@ impl OpenAiTransport for FakeTransport {
                             prompt_tokens: 1,
                             completion_tokens: 1,
                             total_tokens: 2,
+                            cached_tokens: 0,
                         },
                     }),
                 ]
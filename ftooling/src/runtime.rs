@@ -30,6 +30,11 @@ pub trait ToolRuntime: Send + Sync {
         tool_call: ToolCall,
         context: ToolExecutionContext,
     ) -> ToolFuture<'a, Result<ToolExecutionResult, ToolError>>;
+
+    /// Whether `tool_call` must be approved by a person before it is executed.
+    fn requires_approval(&self, _tool_call: &ToolCall) -> bool {
+        false
+    }
 }
 
 #[derive(Clone)]
@@ -75,6 +80,12 @@ impl DefaultToolRuntime {
 }
 
 impl ToolRuntime for DefaultToolRuntime {
+    fn requires_approval(&self, tool_call: &ToolCall) -> bool {
+        self.registry
+            .get(&tool_call.name)
+            .is_some_and(|tool| tool.requires_approval())
+    }
+
     fn execute<'a>(
         &'a self,
         tool_call: ToolCall,
@@ -462,4 +473,31 @@ mod tests {
         assert!(removed.is_some());
         assert!(registry.is_empty());
     }
+
+    #[test]
+    fn runtime_reports_tools_that_require_approval() {
+        let mut registry = ToolRegistry::new();
+        registry.register(EchoTool);
+        registry.register(
+            crate::FunctionTool::new(
+                ToolDefinition {
+                    name: "deploy".to_string(),
+                    description: "Deploys the service".to_string(),
+                    input_schema: "{\"type\":\"object\"}".to_string(),
+                },
+                |_args, _ctx| async move { Ok("deployed".to_string()) },
+            )
+            .with_approval_required(),
+        );
+        let runtime = DefaultToolRuntime::new(Arc::new(registry));
+        let call = |name: &str| ToolCall {
+            id: "call_9".to_string(),
+            name: name.to_string(),
+            arguments: "{}".to_string(),
+        };
+
+        assert!(runtime.requires_approval(&call("deploy")));
+        assert!(!runtime.requires_approval(&call("echo")));
+        assert!(!runtime.requires_approval(&call("missing")));
+    }
 }
@@ -11,9 +11,11 @@
 //!         input_schema: r#"{"type":"string"}"#.to_string(),
 //!     },
 //!     |args, _ctx| async move { Ok(args) },
-//! );
+//! )
+//! .with_approval_required();
 //!
 //! assert_eq!(tool.definition().name, "echo");
+//! assert!(tool.requires_approval());
 //! ```
 
 use std::future::Future;
@@ -29,6 +31,12 @@ pub type ToolFuture<'a, T> = BoxFuture<'a, T>;
 pub trait Tool: Send + Sync {
     fn definition(&self) -> ToolDefinition;
 
+    /// Whether a person must confirm each call before it runs, for tools with side effects
+    /// such as shell access, deployments, or payments.
+    fn requires_approval(&self) -> bool {
+        false
+    }
+
     fn invoke<'a>(
         &'a self,
         args_json: &'a str,
@@ -43,6 +51,7 @@ type ToolHandler = dyn Fn(String, ToolExecutionContext) -> ToolFuture<'static, R
 pub struct FunctionTool {
     definition: ToolDefinition,
     handler: Arc<ToolHandler>,
+    requires_approval: bool,
 }
 
 impl FunctionTool {
@@ -57,8 +66,14 @@ impl FunctionTool {
         Self {
             definition,
             handler,
+            requires_approval: false,
         }
     }
+
+    pub fn with_approval_required(mut self) -> Self {
+        self.requires_approval = true;
+        self
+    }
 }
 
 impl Tool for FunctionTool {
@@ -66,6 +81,10 @@ impl Tool for FunctionTool {
         self.definition.clone()
     }
 
+    fn requires_approval(&self) -> bool {
+        self.requires_approval
+    }
+
     fn invoke<'a>(
         &'a self,
         args_json: &'a str,